@@ -0,0 +1,80 @@
+package main
+
+import (
+	"os"
+	"strings"
+	"unicode"
+)
+
+var asciiNames = []string{"auto", "on", "off"}
+
+// useASCII resolves --ascii to whether to print nothing but ASCII: auto
+// does unless the terminal takes UTF-8.
+func useASCII(mode string) bool {
+	switch mode {
+	case "on":
+		return true
+	case "off":
+		return false
+	}
+	return !terminalUTF8()
+}
+
+// locale is the locale character handling comes from, the first of
+// LC_ALL, LC_CTYPE and LANG set.
+func locale() string {
+	for _, name := range []string{"LC_ALL", "LC_CTYPE", "LANG"} {
+		if l := os.Getenv(name); l != "" {
+			return l
+		}
+	}
+	return ""
+}
+
+func localeUTF8(locale string) bool {
+	lower := strings.ToLower(locale)
+	return strings.Contains(lower, "utf-8") || strings.Contains(lower, "utf8")
+}
+
+// asciiFallbacks are the ASCII stand-ins for the glyphs drawn most, the
+// A-likes aside, which letters cover.
+var asciiFallbacks = map[rune]byte{
+	'─': '-', '━': '-', '═': '-', '│': '|', '┃': '|', '║': '|',
+	'╱': '/', '╲': '\\', '╳': 'X',
+	'░': '.', '▒': ':', '▓': '%', '█': '#',
+	'▀': '"', '▄': '_', '▌': '[', '▐': ']',
+	'·': '.', '•': '*', '…': '.',
+	'←': '<', '→': '>', '↑': '^', '↓': 'v',
+	'₳': 'A', '∀': 'V', 'Λ': '^', 'Д': 'A',
+}
+
+// asciiGlyph is an ASCII character standing in for text, one cell of the
+// screen: itself if it is one, without any combining marks; a look-alike
+// for box drawing, blocks and the like; or the nearest case of A for any
+// other letter, since nearly all of them are A in some script.
+func asciiGlyph(text string) string {
+	for _, r := range text {
+		if unicode.In(r, unicode.Mn, unicode.Me) {
+			continue
+		}
+		switch c, ok := asciiFallbacks[r]; {
+		case r < 0x80:
+			return string(r)
+		case ok:
+			return string(c)
+		case r >= 0x2500 && r <= 0x257F:
+			// The rest of the box drawing is corners and crossings.
+			return "+"
+		case r >= 0x2800 && r <= 0x28FF:
+			return ":"
+		case unicode.IsLower(r):
+			return "a"
+		case unicode.IsLetter(r):
+			return "A"
+		case unicode.IsDigit(r):
+			return "0"
+		}
+		return "#"
+	}
+	return " "
+}
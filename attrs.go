@@ -0,0 +1,53 @@
+package main
+
+import (
+	"os"
+	"strconv"
+	"strings"
+)
+
+// Attr is a set of text attributes, as bits.
+type Attr uint8
+
+const (
+	AttrBold Attr = 1 << iota
+	AttrDim
+	AttrItalic
+	AttrUnderline
+	AttrReverse
+)
+
+// attrCodes are the SGR parameters turning each attribute on, in bit order.
+var attrCodes = [...]int{1, 2, 3, 4, 7}
+
+// appendAttrs appends the SGR sequence switching from whatever attributes
+// were on to just a: everything off, then a's on.
+func appendAttrs(b []byte, a Attr) []byte {
+	b = append(b, "\x1b[22;23;24;27"...)
+	for i, code := range attrCodes {
+		if a&(1<<i) != 0 {
+			b = append(b, ';')
+			b = strconv.AppendInt(b, int64(code), 10)
+		}
+	}
+	return append(b, 'm')
+}
+
+var attrsNames = []string{"auto", "on", "off"}
+
+// sendAttrs resolves --attrs to whether the screen sends text attributes:
+// auto sends them unless TERM names a terminal known to draw them badly,
+// like the Linux console, which shows underline and italic as colors.
+func sendAttrs(mode string) bool {
+	switch mode {
+	case "on":
+		return true
+	case "off":
+		return false
+	}
+	return attrsSupported(os.Getenv("TERM"))
+}
+
+func attrsSupported(term string) bool {
+	return term != "linux" && term != "dumb" && !strings.HasPrefix(term, "vt") && !strings.HasPrefix(term, "cons")
+}
@@ -0,0 +1,90 @@
+//go:build audio
+
+package main
+
+import (
+	"encoding/binary"
+	"fmt"
+	"io"
+	"math"
+	"os/exec"
+	"sync"
+)
+
+const (
+	audioRate  = 44100
+	audioFrame = 1024
+)
+
+// AudioReactor drives chaos and speed from bass energy and brightness from
+// treble energy of the default input, captured through parec (PulseAudio or
+// PipeWire) or arecord (ALSA). Point the default source at a monitor device
+// to react to whatever is playing.
+type AudioReactor struct {
+	mu           sync.Mutex
+	bass, treble float64
+}
+
+func newAudioReactor() (Modulator, error) {
+	var cmd *exec.Cmd
+	if _, err := exec.LookPath("parec"); err == nil {
+		cmd = exec.Command("parec", "--format=s16le", fmt.Sprintf("--rate=%d", audioRate), "--channels=1", "--latency-msec=20")
+	} else if _, err := exec.LookPath("arecord"); err == nil {
+		cmd = exec.Command("arecord", "-q", "-t", "raw", "-f", "S16_LE", "-r", fmt.Sprint(audioRate), "-c", "1")
+	} else {
+		return nil, fmt.Errorf("audio capture needs parec or arecord on the PATH")
+	}
+	out, err := cmd.StdoutPipe()
+	if err != nil {
+		return nil, err
+	}
+	if err := cmd.Start(); err != nil {
+		return nil, fmt.Errorf("starting %s: %w", cmd.Path, err)
+	}
+	r := &AudioReactor{}
+	go r.capture(out)
+	return r, nil
+}
+
+// capture reads frames until the recorder goes away, tracking band energy
+// against a slowly decaying peak so the levels adapt to the volume.
+func (r *AudioReactor) capture(in io.Reader) {
+	samples := make([]int16, audioFrame)
+	spectrum := make([]complex128, audioFrame)
+	var bassPeak, treblePeak float64 = 1e-9, 1e-9
+	for binary.Read(in, binary.LittleEndian, samples) == nil {
+		for i, s := range samples {
+			window := 0.5 - 0.5*math.Cos(2*math.Pi*float64(i)/float64(audioFrame-1))
+			spectrum[i] = complex(float64(s)/math.MaxInt16*window, 0)
+		}
+		fft(spectrum)
+		bass := bandEnergy(spectrum, 20, 250)
+		treble := bandEnergy(spectrum, 4000, 16000)
+		bassPeak = max(bass, bassPeak*0.995)
+		treblePeak = max(treble, treblePeak*0.995)
+
+		r.mu.Lock()
+		r.bass += (bass/bassPeak - r.bass) * 0.5
+		r.treble += (treble/treblePeak - r.treble) * 0.5
+		r.mu.Unlock()
+	}
+}
+
+func bandEnergy(spectrum []complex128, lo, hi float64) float64 {
+	binHz := float64(audioRate) / float64(len(spectrum))
+	var sum float64
+	for bin := int(lo / binHz); bin <= int(hi/binHz) && bin < len(spectrum)/2; bin++ {
+		re, im := real(spectrum[bin]), imag(spectrum[bin])
+		sum += re*re + im*im
+	}
+	return sum
+}
+
+func (r *AudioReactor) modulate(p *Params) {
+	r.mu.Lock()
+	bass, treble := r.bass, r.treble
+	r.mu.Unlock()
+	p.chaos *= 0.3 + 2.7*bass
+	p.speed *= 0.5 + bass
+	p.brightness *= 0.4 + 0.6*treble
+}
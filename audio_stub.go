@@ -0,0 +1,9 @@
+//go:build !audio
+
+package main
+
+import "errors"
+
+func newAudioReactor() (Modulator, error) {
+	return nil, errors.New("built without audio support; rebuild with -tags audio")
+}
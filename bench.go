@@ -0,0 +1,100 @@
+package main
+
+import (
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"time"
+)
+
+type benchOptions struct {
+	width, height int
+	frames        int
+	terminal      bool
+	trace         string
+}
+
+func (o *benchOptions) register(fs *flag.FlagSet) {
+	fs.IntVar(&o.width, "width", 200, "frame width in cells")
+	fs.IntVar(&o.height, "height", 60, "frame height in rows")
+	fs.IntVar(&o.frames, "frames", 1000, "number of frames to render")
+	fs.BoolVar(&o.terminal, "terminal", false, "draw to this terminal, at its size, to find the frame rate it can keep up with")
+	fs.StringVar(&o.trace, "trace", "", "write a runtime trace of each frame's stages to this file, and a CPU profile to it with .pprof added (needs -tags profiling)")
+}
+
+// byteCounter counts what's written through it.
+type byteCounter struct {
+	w io.Writer
+	n int64
+}
+
+func (c *byteCounter) Write(p []byte) (int, error) {
+	n, err := c.w.Write(p)
+	c.n += int64(n)
+	return n, err
+}
+
+// runBench simulates and draws frames as fast as it can and reports how many
+// a second that comes to. The output is discarded, or with --terminal sent
+// to the terminal, so its rendering is timed too.
+func runBench(args []string) error {
+	fs := flag.NewFlagSet("bench", flag.ExitOnError)
+	var o runOptions
+	var b benchOptions
+	o.register(fs)
+	b.register(fs)
+	if err := parseFlags(fs, args); err != nil {
+		return err
+	}
+	if b.frames <= 0 {
+		return fmt.Errorf("--frames must be positive")
+	}
+	if err := checkSize(b.width, b.height); err != nil {
+		return err
+	}
+	sc, err := o.scene()
+	if err != nil {
+		return err
+	}
+
+	out := &byteCounter{w: io.Discard}
+	if b.terminal {
+		if !isTerminal(os.Stdout) {
+			return fmt.Errorf("--terminal needs stdout to be a terminal")
+		}
+		out.w = os.Stdout
+		b.width, b.height = terminalSize()
+	}
+	sim, scr := headless(sc.SimConfig, b.width, b.height, out)
+	if b.terminal {
+		scr.keep = false
+		scr.enter()
+	}
+	if b.trace != "" {
+		stop, err := startTrace(b.trace)
+		if err != nil {
+			return err
+		}
+		defer stop()
+	}
+	start := time.Now()
+	for range b.frames {
+		sim.update()
+		sim.draw(scr)
+		if err = scr.flush(); err != nil {
+			break
+		}
+	}
+	elapsed := time.Since(start)
+	if b.terminal {
+		scr.leave()
+	}
+	if err != nil {
+		return err
+	}
+	perFrame := elapsed / time.Duration(b.frames)
+	fmt.Printf("%d frames at %dx%d in %v: %v and %d bytes a frame, %.0f fps (the animation ticks at %.0f)\n",
+		b.frames, b.width, b.height, elapsed.Round(time.Millisecond), perFrame, out.n/int64(b.frames), float64(b.frames)/elapsed.Seconds(), time.Second.Seconds()/tickInterval.Seconds())
+	return nil
+}
@@ -0,0 +1,37 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// fakeSession is what the boss key leaves on the normal screen: a dull
+// build log ending at a shell prompt.
+func fakeSession() string {
+	user := os.Getenv("USER")
+	if user == "" {
+		user = "dev"
+	}
+	host, err := os.Hostname()
+	if err != nil {
+		host = "localhost"
+	}
+	host, _, _ = strings.Cut(host, ".")
+	dir := "project"
+	if wd, err := os.Getwd(); err == nil {
+		dir = filepath.Base(wd)
+	}
+	prompt := fmt.Sprintf("%s@%s:~/src/%s$ ", user, host, dir)
+
+	var sb strings.Builder
+	sb.WriteString(prompt + "make -j8\n")
+	for _, unit := range []string{"config", "parser", "lexer", "buffer", "index", "report", "main"} {
+		fmt.Fprintf(&sb, "cc -O2 -Wall -Iinclude -c src/%s.c -o build/%s.o\n", unit, unit)
+	}
+	sb.WriteString("cc -o build/" + dir + " build/*.o -lm\n")
+	sb.WriteString("make: Leaving directory '/home/" + user + "/src/" + dir + "'\n")
+	sb.WriteString(prompt)
+	return sb.String()
+}
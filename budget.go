@@ -0,0 +1,70 @@
+package main
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// ecoCPU is the budget --eco sets.
+const ecoCPU = "5%"
+
+// cpuBudget keeps drawing within a share of a CPU, for --max-cpu, by timing
+// every frame. While frames cost more than the share of the time between
+// them, it thins the streams out, and once they're as thin as they go it
+// lowers the frame rate; while they cost well under, it undoes both, the
+// frame rate first.
+type cpuBudget struct {
+	share float64
+
+	// cost is how long a frame takes, in seconds, smoothed.
+	cost float64
+
+	// thinning is the share of the streams cut, and interval the time
+	// between frames.
+	thinning float64
+	interval time.Duration
+}
+
+const (
+	// maxThinning is the most of the streams the budget cuts.
+	maxThinning = 0.8
+
+	// budgetSlowest is the longest the budget leaves between frames.
+	budgetSlowest = time.Second
+
+	// A budget eases off once frames cost under budgetSlack of it.
+	budgetSlack = 0.6
+)
+
+func newCPUBudget(share float64) *cpuBudget {
+	return &cpuBudget{share: share, interval: tickInterval}
+}
+
+// parseMaxCPU reads --max-cpu, a percentage of one CPU such as 5% or 5, as
+// a share.
+func parseMaxCPU(s string) (float64, error) {
+	num, _ := strings.CutSuffix(strings.TrimSpace(s), "%")
+	n, err := strconv.ParseFloat(num, 64)
+	if err != nil || n <= 0 || n > 100 {
+		return 0, fmt.Errorf("bad max-cpu %q (want a percentage of a CPU, such as 5%%)", s)
+	}
+	return n / 100, nil
+}
+
+// record adjusts the budget after a frame took cost.
+func (b *cpuBudget) record(cost time.Duration) {
+	b.cost += (cost.Seconds() - b.cost) * 0.1
+	use := b.cost / b.interval.Seconds()
+	switch {
+	case use > b.share && b.thinning < maxThinning:
+		b.thinning = min(maxThinning, b.thinning+0.02)
+	case use > b.share:
+		b.interval = min(budgetSlowest, b.interval*11/10)
+	case use < b.share*budgetSlack && b.interval > tickInterval:
+		b.interval = max(tickInterval, b.interval*10/11)
+	case use < b.share*budgetSlack:
+		b.thinning = max(0, b.thinning-0.01)
+	}
+}
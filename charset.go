@@ -0,0 +1,232 @@
+package main
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"os"
+	"strconv"
+	"strings"
+	"unicode"
+)
+
+type Charset = Weighted[string]
+
+var defaultCharset = newWeighted(
+	Choice[string]{"A", 5 * Common},
+	Choice[string]{"₳", Uncommon},
+	Choice[string]{"Ⱥ", Uncommon},
+	Choice[string]{"A̷", Uncommon},
+	Choice[string]{"A̲", Uncommon},
+	Choice[string]{"A̳", Uncommon},
+	Choice[string]{"A̾", Uncommon},
+	Choice[string]{"A͎", Uncommon},
+	Choice[string]{"𝔸", Uncommon},
+	Choice[string]{"ᴀ", Uncommon},
+	Choice[string]{"∀", Uncommon},
+	Choice[string]{"A҉", Rare},
+	Choice[string]{"A͓̽", Rare},
+	Choice[string]{"░A░", Rare},
+	Choice[string]{"A̵̦̦̓͌͗͛̕", Rare},
+)
+
+// charsets are the built-in charsets --charset can name instead of a file.
+var charsets = []struct {
+	name    string
+	charset *Charset
+}{
+	{"default", defaultCharset},
+	{"blocks", newWeighted(
+		Choice[string]{"█", Common},
+		Choice[string]{"▓", Common},
+		Choice[string]{"▒", Common},
+		Choice[string]{"░", Common},
+		Choice[string]{"▀", Uncommon},
+		Choice[string]{"▄", Uncommon},
+		Choice[string]{"▌", Uncommon},
+		Choice[string]{"▐", Uncommon},
+	)},
+	{"box", newWeighted(
+		Choice[string]{"─", Common},
+		Choice[string]{"│", Common},
+		Choice[string]{"┼", Uncommon},
+		Choice[string]{"┌", Uncommon},
+		Choice[string]{"┐", Uncommon},
+		Choice[string]{"└", Uncommon},
+		Choice[string]{"┘", Uncommon},
+		Choice[string]{"╱", Rare},
+		Choice[string]{"╲", Rare},
+		Choice[string]{"╳", Rare},
+	)},
+	{"math", newWeighted(
+		Choice[string]{"𝔸", Common},
+		Choice[string]{"𝒜", Common},
+		Choice[string]{"𝓐", Common},
+		Choice[string]{"𝕬", Common},
+		Choice[string]{"𝖠", Uncommon},
+		Choice[string]{"𝘈", Uncommon},
+		Choice[string]{"𝙰", Uncommon},
+		Choice[string]{"∀", Rare},
+		Choice[string]{"Λ", Rare},
+	)},
+	{"runes", newWeighted(
+		Choice[string]{"ᚨ", Common},
+		Choice[string]{"ᚠ", Uncommon},
+		Choice[string]{"ᚢ", Uncommon},
+		Choice[string]{"ᚦ", Uncommon},
+		Choice[string]{"ᚱ", Uncommon},
+		Choice[string]{"ᚲ", Uncommon},
+		Choice[string]{"ᛟ", Rare},
+		Choice[string]{"ᛉ", Rare},
+	)},
+	{"ascii", newWeighted(
+		Choice[string]{"A", 5 * Common},
+		Choice[string]{"a", Common},
+		Choice[string]{"4", Uncommon},
+		Choice[string]{"@", Uncommon},
+		Choice[string]{"^", Uncommon},
+		Choice[string]{"/", Rare},
+		Choice[string]{"\\", Rare},
+		Choice[string]{"#", Rare},
+	)},
+	{"scripts", newWeighted(
+		Choice[string]{"A", Common},
+		Choice[string]{"А", Common},
+		Choice[string]{"Α", Common},
+		Choice[string]{"Ꭺ", Common},
+		Choice[string]{"Д", Uncommon},
+		Choice[string]{"Λ", Uncommon},
+		Choice[string]{"ᗅ", Uncommon},
+		Choice[string]{"ꓮ", Uncommon},
+		Choice[string]{"Ａ", Uncommon},
+		Choice[string]{"ア", Rare},
+		Choice[string]{"ㅅ", Rare},
+		Choice[string]{"∀", Rare},
+	)},
+	{"fullwidth", newWeighted(
+		Choice[string]{"Ａ", 3 * Common},
+		Choice[string]{"ａ", Common},
+		Choice[string]{"ア", Uncommon},
+		Choice[string]{"人", Uncommon},
+		Choice[string]{"Ａ̷", Uncommon},
+		Choice[string]{"Ａ̲", Uncommon},
+		Choice[string]{"ㅅ", Rare},
+		Choice[string]{"＾", Rare},
+	)},
+}
+
+func charsetNames() []string {
+	names := make([]string, len(charsets))
+	for i, c := range charsets {
+		names[i] = c.name
+	}
+	return names
+}
+
+func lookupCharset(name string) *Charset {
+	for _, c := range charsets {
+		if c.name == name {
+			return c.charset
+		}
+	}
+	return nil
+}
+
+// runCharsets lists the built-in charsets with their glyphs.
+func runCharsets(args []string) error {
+	for _, c := range charsets {
+		glyphs := make([]string, c.charset.len())
+		for i, choice := range c.charset.choices {
+			glyphs[i] = choice.item
+		}
+		fmt.Printf("%-8s %s\n", c.name, strings.Join(glyphs, " "))
+	}
+	return nil
+}
+
+const maxStringLength = 20
+
+func loadCharset(path string) (*Charset, error) {
+	f, err := os.Open(path)
+	if err != nil {
+		return nil, err
+	}
+	defer f.Close()
+	cs, err := parseCharset(f)
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", path, err)
+	}
+	return cs, nil
+}
+
+// parseCharset reads one glyph per line, optionally followed by its weight:
+// rare, uncommon, common or a positive number. Glyphs default to common.
+// Blank lines and lines starting with # are skipped.
+func parseCharset(r io.Reader) (*Charset, error) {
+	cs := newWeighted[string]()
+	scanner := bufio.NewScanner(r)
+	line := 0
+	for scanner.Scan() {
+		line++
+		text := scanner.Text()
+		fields, at := fieldsAt(text)
+		if len(fields) == 0 || strings.HasPrefix(fields[0], "#") {
+			continue
+		}
+		weight := Common
+		switch len(fields) {
+		case 1:
+		case 2:
+			w, err := parseWeight(fields[1])
+			if err != nil {
+				return nil, errorAt(line, columnAt(text, at[1]), "%w", err)
+			}
+			weight = w
+		default:
+			return nil, errorAt(line, columnAt(text, at[2]), "want a glyph and an optional weight, got %d fields", len(fields))
+		}
+		cs.add(fields[0], weight)
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, err
+	}
+	if cs.len() == 0 {
+		return nil, fmt.Errorf("no glyphs")
+	}
+	return cs, nil
+}
+
+// fieldsAt splits text around runs of white space, as strings.Fields does,
+// and says where in text each field starts.
+func fieldsAt(text string) (fields []string, at []int) {
+	start := -1
+	for i, r := range text {
+		switch space := unicode.IsSpace(r); {
+		case space && start >= 0:
+			fields, at = append(fields, text[start:i]), append(at, start)
+			start = -1
+		case !space && start < 0:
+			start = i
+		}
+	}
+	if start >= 0 {
+		fields, at = append(fields, text[start:]), append(at, start)
+	}
+	return fields, at
+}
+
+func parseWeight(s string) (Weight, error) {
+	switch strings.ToLower(s) {
+	case "rare":
+		return Rare, nil
+	case "uncommon":
+		return Uncommon, nil
+	case "common":
+		return Common, nil
+	}
+	n, err := strconv.ParseUint(s, 10, 16)
+	if err != nil || n == 0 {
+		return 0, fmt.Errorf("bad weight %q (want rare, uncommon, common or a number from 1 to 65535)", s)
+	}
+	return Weight(n), nil
+}
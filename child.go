@@ -0,0 +1,126 @@
+//go:build lua || plugins
+
+package main
+
+import (
+	"bufio"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"os/exec"
+	"runtime"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// childTimeout is how long a --script or plugin gets to answer before it's
+// taken to be stuck, and childStartTimeout how long it gets for its first
+// answer, while wasmtime compiles a plugin say.
+const (
+	childTimeout      = time.Second
+	childStartTimeout = 10 * time.Second
+)
+
+// child is a --script or plugin running as a process of its own, taking
+// requests a line at a time on its stdin and answering them on its
+// stdout. An answer of error and a message says it has failed.
+type child struct {
+	name  string
+	cmd   *exec.Cmd
+	in    *bufio.Writer
+	stdin io.Closer
+	out   *bufio.Reader
+	pipe  *os.File
+
+	// timeout is how long it has for the next answer.
+	timeout time.Duration
+
+	// failed is set once it has stopped working.
+	failed bool
+}
+
+// startChild starts cmd, calling it name in errors.
+func startChild(name string, cmd *exec.Cmd) (*child, error) {
+	stdin, err := cmd.StdinPipe()
+	if err != nil {
+		return nil, err
+	}
+	stdout, err := cmd.StdoutPipe()
+	if err != nil {
+		return nil, err
+	}
+	if err := cmd.Start(); err != nil {
+		return nil, fmt.Errorf("starting %s: %w", name, err)
+	}
+	c := &child{name: name, cmd: cmd, in: bufio.NewWriter(stdin), stdin: stdin, out: bufio.NewReader(stdout), timeout: childStartTimeout}
+	c.pipe, _ = stdout.(*os.File)
+	// Closing its stdin once the scene is gone lets it exit.
+	runtime.SetFinalizer(c, (*child).close)
+	return c, nil
+}
+
+// readLine reads a line of its answer, failing if it doesn't answer in
+// time or answers with an error.
+func (c *child) readLine() (string, error) {
+	if c.pipe != nil {
+		c.pipe.SetReadDeadline(time.Now().Add(c.timeout))
+	}
+	line, err := c.out.ReadString('\n')
+	if errors.Is(err, os.ErrDeadlineExceeded) {
+		return "", fmt.Errorf("%s took longer than %v to answer", c.name, c.timeout)
+	}
+	if err != nil {
+		return "", fmt.Errorf("%s exited: %w", c.name, err)
+	}
+	c.timeout = childTimeout
+	line = strings.TrimSuffix(line, "\n")
+	if msg, ok := strings.CutPrefix(line, "error "); ok {
+		return "", fmt.Errorf("%s: %s", c.name, msg)
+	}
+	return line, nil
+}
+
+// readNumbers reads a line of n numbers.
+func (c *child) readNumbers(n int) ([]float64, error) {
+	line, err := c.readLine()
+	if err != nil {
+		return nil, err
+	}
+	fields := strings.Fields(line)
+	if len(fields) != n {
+		return nil, fmt.Errorf("%s answered %q, want %d numbers", c.name, line, n)
+	}
+	numbers := make([]float64, n)
+	for i, f := range fields {
+		if numbers[i], err = strconv.ParseFloat(f, 64); err != nil {
+			return nil, fmt.Errorf("%s answered %q, want %d numbers", c.name, line, n)
+		}
+	}
+	return numbers, nil
+}
+
+// ask sends a request and reads the one-line answer.
+func (c *child) ask(format string, args ...any) (string, error) {
+	fmt.Fprintf(c.in, format+"\n", args...)
+	if err := c.in.Flush(); err != nil {
+		return "", err
+	}
+	return c.readLine()
+}
+
+func (c *child) close() {
+	runtime.SetFinalizer(c, nil)
+	c.stdin.Close()
+	c.cmd.Process.Kill()
+	c.cmd.Wait()
+}
+
+// fail logs why it stopped working and stops it. The sim carries on
+// without it.
+func (c *child) fail(err error) {
+	logger.Error(c.name+" failed, carrying on without it", "err", err)
+	c.failed = true
+	c.close()
+}
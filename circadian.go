@@ -0,0 +1,86 @@
+package main
+
+import (
+	"fmt"
+	"math"
+	"strings"
+	"time"
+)
+
+// circadianEvery is how often the palette is blended again. The drift over
+// a minute is too slight to see, so nothing's lost between.
+const circadianEvery = time.Minute
+
+// Circadian drifts the palette from a day theme to a night theme and back
+// with the local time: all day at noon, all night at midnight. The streams
+// already out keep their colors, so the drift only shows as new ones
+// replace them.
+type Circadian struct {
+	day, night *Palette
+	last       time.Time
+}
+
+// parseCircadian reads --circadian, a day and a night theme such as
+// ice:amber, each turned into a palette by palette.
+func parseCircadian(s string, palette func(*Theme) *Palette) (*Circadian, error) {
+	day, night, ok := strings.Cut(s, ":")
+	if !ok {
+		return nil, fmt.Errorf("bad circadian %q (want day:night themes, such as ice:amber)", s)
+	}
+	dayTheme, err := lookupTheme(day)
+	if err != nil {
+		return nil, err
+	}
+	nightTheme, err := lookupTheme(night)
+	if err != nil {
+		return nil, err
+	}
+	return &Circadian{day: palette(dayTheme), night: palette(nightTheme)}, nil
+}
+
+// nightness is how far into the night t is, 0 at noon to 1 at midnight.
+func nightness(t time.Time) float64 {
+	h := float64(t.Hour()) + float64(t.Minute())/60
+	return (1 + math.Cos(2*math.Pi*h/24)) / 2
+}
+
+func (c *Circadian) update(sim *Sim) {
+	now := time.Now()
+	if now.Sub(c.last) < circadianEvery {
+		return
+	}
+	c.last = now
+	sim.palette = c.day.mix(c.night, nightness(now))
+}
+
+func (c *Circadian) draw(*Sim, *Screen) {}
+
+// mix blends from p towards other; t is how far, 0 to 1. The accents are
+// paired off in order, the shorter list wrapping around, and whatever
+// can't be blended comes from the nearer of the two.
+func (p *Palette) mix(other *Palette, t float64) *Palette {
+	near := p
+	if t >= 0.5 {
+		near = other
+	}
+	a, b := p.colors.choices[1:], other.colors.choices[1:]
+	var accents []Choice[Color]
+	for i := range max(len(a), len(b)) {
+		switch {
+		case len(a) == 0:
+			accents = append(accents, b[i])
+		case len(b) == 0:
+			accents = append(accents, a[i])
+		default:
+			x, y := a[i%len(a)], b[i%len(b)]
+			weight := x.weight
+			if near == other {
+				weight = y.weight
+			}
+			accents = append(accents, Choice[Color]{x.item.mix(y.item, t), weight})
+		}
+	}
+	mixed := newPalette(p.primary.mix(other.primary, t), accents...)
+	mixed.backgrounds, mixed.inverse, mixed.wash, mixed.gradient, mixed.attrs = near.backgrounds, near.inverse, near.wash, near.gradient, near.attrs
+	return mixed
+}
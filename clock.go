@@ -0,0 +1,42 @@
+package main
+
+import (
+	"fmt"
+	"time"
+)
+
+// Clock shows the current time in block digits built from charset glyphs.
+// Streams bounce off the digits instead of crossing them.
+type Clock struct {
+	layout string
+	shown  string
+	text   blockText
+}
+
+func newClock(hours int) (*Clock, error) {
+	switch hours {
+	case 24:
+		return &Clock{layout: "15:04:05"}, nil
+	case 12:
+		return &Clock{layout: "3:04:05 PM"}, nil
+	}
+	return nil, fmt.Errorf("clock format must be 12 or 24, not %d", hours)
+}
+
+// update re-renders the digits when the displayed time or the canvas changes.
+func (c *Clock) update(sim *Sim) {
+	now := time.Now().Format(c.layout)
+	if now != c.shown {
+		c.shown = now
+		c.text.rows = renderFont(now)
+	}
+	c.text = layoutBlockText(c.text.rows, sim.width, sim.height)
+}
+
+func (c *Clock) draw(sim *Sim, scr *Screen) {
+	c.text.draw(sim, scr, 0, func() string { return sim.charset.pick(sim.rng) })
+}
+
+func (c *Clock) blocks(x, y int) bool {
+	return c.text.lit(x, y)
+}
@@ -0,0 +1,61 @@
+package main
+
+import (
+	"fmt"
+	"slices"
+)
+
+type Collisions int
+
+const (
+	CollisionsOff Collisions = iota
+	CollisionsMerge
+	CollisionsBounce
+	CollisionsExplode
+)
+
+var collisionNames = []string{"off", "merge", "bounce", "explode"}
+
+func parseCollisions(name string) (Collisions, error) {
+	if i := slices.Index(collisionNames, name); i >= 0 {
+		return Collisions(i), nil
+	}
+	return CollisionsOff, fmt.Errorf("unknown collision reaction %q (want one of %v)", name, collisionNames)
+}
+
+const explosionParticles = 12
+
+// collide finds streams sharing a cell and reacts to each pair according to
+// the configured mode.
+func (sim *Sim) collide() {
+	if sim.collisions == CollisionsOff {
+		return
+	}
+	occupied := make(map[[2]int]*Stream, len(sim.streams))
+	dead := make(map[*Stream]bool)
+	for _, s := range sim.streams {
+		x, y := s.cell()
+		other, hit := occupied[[2]int{x, y}]
+		if !hit || dead[other] {
+			occupied[[2]int{x, y}] = s
+			continue
+		}
+		switch sim.collisions {
+		case CollisionsMerge:
+			other.vx, other.vy = (other.vx+s.vx)/2, (other.vy+s.vy)/2
+			dead[s] = true
+			if sim.deathBursts {
+				sim.shower(s)
+			}
+		case CollisionsBounce:
+			other.vx, s.vx = s.vx, other.vx
+			other.vy, s.vy = s.vy, other.vy
+		case CollisionsExplode:
+			sim.burst(s.x, s.y, explosionParticles)
+			dead[s], dead[other] = true, true
+		}
+	}
+	if len(dead) > 0 {
+		sim.streams = slices.DeleteFunc(sim.streams, func(s *Stream) bool { return dead[s] })
+	}
+}
@@ -0,0 +1,301 @@
+package main
+
+import "time"
+
+// Command is a change the input loop asks of the render goroutine, which
+// owns the sim and the screen and applies commands between frames.
+type Command interface {
+	apply(r *renderer)
+}
+
+// Reconfigure swaps in a whole new sim config, for a profile switch. With
+// the screen split, only the first pane is reconfigured.
+type Reconfigure struct{ cfg SimConfig }
+
+// SetParams sets the base params, for speed and chaos changes. It and the
+// commands below apply to every pane.
+type SetParams struct{ params Params }
+
+type SetPalette struct{ palette *Palette }
+
+type SetCharset struct{ charset *Charset }
+
+// ToggleBoss shows or hides the boss screen.
+type ToggleBoss struct{}
+
+// SpawnBurst throws a burst of streams printing glyph.
+type SpawnBurst struct{ glyph string }
+
+// PanicBurst throws every pane into a few seconds of flat-out chaos.
+type PanicBurst struct{}
+
+// ToggleHeatmap switches between the live streams and the heatmap of
+// where they've been.
+type ToggleHeatmap struct{}
+
+// ModeInput hands a key the input loop has no use for to the modes.
+type ModeInput struct{ key rune }
+
+// SetFocus throttles drawing to about a frame a second while the terminal
+// doesn't have focus, and back to full speed once it does.
+type SetFocus struct{ focused bool }
+
+// Suspend stops drawing and hands the terminal back, before the process is
+// stopped, closing done once it has. Resume takes it over again, whether
+// after a Suspend or on being continued after a stop it couldn't see
+// coming.
+type (
+	Suspend struct{ done chan struct{} }
+	Resume  struct{}
+)
+
+// Quit stops the render goroutine.
+type Quit struct{}
+
+func (c Reconfigure) apply(r *renderer) { r.sims[0].reconfigure(c.cfg) }
+func (Quit) apply(r *renderer)          {}
+
+func (c SetParams) apply(r *renderer) {
+	for _, sim := range r.sims {
+		sim.base = c.params
+	}
+}
+
+func (c SetPalette) apply(r *renderer) {
+	for _, sim := range r.sims {
+		sim.setPalette(c.palette)
+	}
+}
+
+func (c SetCharset) apply(r *renderer) {
+	for _, sim := range r.sims {
+		sim.setCharset(c.charset)
+	}
+}
+
+func (c SpawnBurst) apply(r *renderer) {
+	for _, sim := range r.sims {
+		sim.spawnBurst(c.glyph)
+	}
+	if r.host != nil {
+		r.host.burst(c.glyph)
+	}
+}
+
+func (PanicBurst) apply(r *renderer) {
+	for _, sim := range r.sims {
+		sim.boost(&panicBurst{})
+	}
+}
+
+func (ToggleHeatmap) apply(r *renderer) {
+	for _, sim := range r.sims {
+		sim.heatView = !sim.heatView
+	}
+}
+
+func (c ModeInput) apply(r *renderer) {
+	for _, sim := range r.sims {
+		sim.mode.handleInput(sim, c.key)
+	}
+}
+
+func (c SetFocus) apply(r *renderer) { r.unfocused = !c.focused }
+
+func (c Suspend) apply(r *renderer) {
+	r.paused = true
+	r.scr.leave()
+	close(c.done)
+}
+
+func (Resume) apply(r *renderer) {
+	r.paused = false
+	if r.boss {
+		r.scr.boss()
+	} else {
+		r.scr.enter()
+	}
+}
+
+func (ToggleBoss) apply(r *renderer) {
+	r.boss = !r.boss
+	if r.boss {
+		r.scr.boss()
+	} else {
+		r.scr.enter()
+	}
+}
+
+// renderer is the render goroutine's state: a sim for each pane of a cols
+// by rows split, usually just the one, drawn on a screen of the size that
+// size reports.
+type renderer struct {
+	sims       []*Sim
+	cols, rows int
+	scr        *Screen
+	size       func() (int, int)
+	boss       bool
+
+	// unfocused slows drawing down while the terminal doesn't have focus,
+	// and paused stops it while the process is suspended.
+	unfocused bool
+	paused    bool
+
+	// budget, with --max-cpu, times every frame to keep them within it.
+	budget *cpuBudget
+
+	// title, with --title-anim, glitches the window title too.
+	title *titleAnim
+
+	// frames counts the frames drawn, and peak the most streams out at
+	// once over every pane, for the stats on exit.
+	frames int
+	peak   int
+
+	// host, with --sync-host, is sent every tick. With --sync-join, feed
+	// is where the ticks come from instead, and syncErr is why they
+	// stopped, if they did.
+	host    *syncHost
+	feed    *syncFeed
+	syncErr error
+}
+
+// unfocusedInterval is the time between frames while the terminal doesn't
+// have focus.
+const unfocusedInterval = time.Second
+
+// interval is the time between frames.
+func (r *renderer) interval() time.Duration {
+	d := tickInterval
+	if r.budget != nil {
+		d = r.budget.interval
+	}
+	if r.unfocused {
+		d = max(d, unfocusedInterval)
+	}
+	return d
+}
+
+// frame advances the sims a tick and draws them, unless the boss screen is
+// up or the process suspended.
+func (r *renderer) frame() error {
+	if r.boss || r.paused {
+		return nil
+	}
+	defer stage("frame")()
+	start := time.Now()
+	width, height := r.scr.resize(r.size())
+	for i, sim := range r.sims {
+		x, y, w, h := paneRect(i%r.cols, i/r.cols, r.cols, r.rows, width, height)
+		r.scr.setWindow(x, y, w, h)
+		sim.resize(w, h)
+		if r.budget != nil {
+			sim.thinning = r.budget.thinning
+		}
+		sim.update()
+		if r.host != nil {
+			r.host.step(sim, w, h)
+		}
+		sim.draw(r.scr)
+	}
+	r.scr.setWindow(0, 0, width, height)
+	if r.title != nil {
+		r.title.update(r.sims[0], r.scr)
+	}
+	if len(r.sims) > 1 {
+		drawBorders(r.scr, r.cols, r.rows, width, height, r.sims[0].palette.primary.dim(0.4))
+	}
+	r.count()
+	err := r.scr.flush()
+	took := time.Since(start)
+	if r.budget != nil {
+		r.budget.record(took)
+	}
+	if took > r.interval() {
+		logger.Warn("frame overran its tick, dropping frames", "took", took, "interval", r.interval())
+	}
+	if err != nil {
+		logger.Error("writing a frame", "err", err)
+	}
+	return err
+}
+
+// count counts a frame for the stats.
+func (r *renderer) count() {
+	r.frames++
+	streams := 0
+	for _, sim := range r.sims {
+		streams += len(sim.streams)
+	}
+	r.peak = max(r.peak, streams)
+}
+
+// run draws a frame every tick, applying commands as they arrive, until it
+// gets Quit or writing a frame fails.
+func (r *renderer) run(commands <-chan Command) error {
+	if r.feed != nil {
+		return r.follow(commands)
+	}
+	interval := r.interval()
+	ticker := time.NewTicker(interval)
+	defer ticker.Stop()
+	// retime changes the ticker to the renderer's interval, if that has
+	// changed.
+	retime := func() {
+		if r.interval() != interval {
+			interval = r.interval()
+			ticker.Reset(interval)
+		}
+	}
+	for {
+		if err := r.frame(); err != nil {
+			return err
+		}
+		retime()
+		for waiting := true; waiting; {
+			select {
+			case c := <-commands:
+				if _, ok := c.(Quit); ok {
+					return nil
+				}
+				c.apply(r)
+				retime()
+			case <-ticker.C:
+				waiting = false
+			}
+		}
+	}
+}
+
+// follow draws a frame for every tick from a --sync-host until it gets
+// Quit, the host goes away or writing a frame fails. Any command that
+// would take the sim its own way is ignored, and with the boss screen up
+// the sim keeps up with the host without showing it.
+func (r *renderer) follow(commands <-chan Command) error {
+	sim := r.sims[0]
+	for {
+		select {
+		case c := <-commands:
+			switch c.(type) {
+			case Quit:
+				return nil
+			case ToggleBoss:
+				c.apply(r)
+			}
+		case msg, ok := <-r.feed.msgs:
+			if !ok {
+				r.syncErr = r.feed.err
+				return nil
+			}
+			r.scr.resize(r.size())
+			sim.step(msg)
+			sim.draw(r.scr)
+			r.count()
+			if !r.boss {
+				if err := r.scr.flush(); err != nil {
+					return err
+				}
+			}
+		}
+	}
+}
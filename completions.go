@@ -0,0 +1,265 @@
+package main
+
+import (
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+)
+
+var completionShells = []string{"bash", "zsh", "fish", "powershell"}
+
+// flagValues is what a flag's value completes to: a fixed set of choices,
+// and whether file names are offered as well.
+func flagValues(name string) (choices []string, files bool) {
+	switch name {
+	case "movement":
+		return movementNames, false
+	case "collisions":
+		return collisionNames, false
+	case "theme":
+		return themeNames(), false
+	case "background":
+		return backgroundNames, false
+	case "charset":
+		return charsetNames(), true
+	case "words":
+		return []string{"default"}, true
+	case "graphics":
+		return graphicsNames, false
+	case "gradient":
+		return append([]string{"theme"}, gradientNames...), false
+	case "log-level":
+		return logLevelNames, false
+	case "stats":
+		return statsNames, false
+	case "ascii":
+		return asciiNames, false
+	case "attrs":
+		return attrsNames, false
+	case "trails":
+		return trailsNames, false
+	case "image-style":
+		return imageStyleNames, false
+	case "orientation":
+		return orientationNames, false
+	case "mirror":
+		return mirrorNames, false
+	case "fx":
+		return fxNames, false
+	case "mode":
+		return modeNames(), false
+	case "clock-format":
+		return []string{"12", "24"}, false
+	case "react":
+		return []string{"cpu", "mem", "net", "audio", "keys"}, false
+	case "format":
+		return []string{"cast", "ansi"}, false
+	case "file", "o":
+		return nil, true
+	}
+	return nil, false
+}
+
+func isBoolFlag(f *flag.Flag) bool {
+	b, ok := f.Value.(interface{ IsBoolFlag() bool })
+	return ok && b.IsBoolFlag()
+}
+
+// commandFlags lists a command's flags, none if it has no flag set.
+func commandFlags(c command) []*flag.Flag {
+	if c.flags == nil {
+		return nil
+	}
+	var flags []*flag.Flag
+	c.flags().VisitAll(func(f *flag.Flag) {
+		flags = append(flags, f)
+	})
+	return flags
+}
+
+func runCompletions(args []string) error {
+	if len(args) != 1 {
+		return fmt.Errorf("want one shell: %s", strings.Join(completionShells, ", "))
+	}
+	switch args[0] {
+	case "bash":
+		bashCompletions(os.Stdout)
+	case "zsh":
+		zshCompletions(os.Stdout)
+	case "fish":
+		fishCompletions(os.Stdout)
+	case "powershell":
+		powershellCompletions(os.Stdout)
+	default:
+		return fmt.Errorf("unknown shell %q (want %s)", args[0], strings.Join(completionShells, ", "))
+	}
+	return nil
+}
+
+func commandNames() []string {
+	names := make([]string, len(commands))
+	for i, c := range commands {
+		names[i] = c.name
+	}
+	return names
+}
+
+// valuedFlags collects every flag that takes a value, across all commands,
+// once each.
+func valuedFlags() []*flag.Flag {
+	seen := map[string]bool{}
+	var flags []*flag.Flag
+	for _, c := range commands {
+		for _, f := range commandFlags(c) {
+			if !isBoolFlag(f) && !seen[f.Name] {
+				seen[f.Name] = true
+				flags = append(flags, f)
+			}
+		}
+	}
+	return flags
+}
+
+func bashCompletions(w io.Writer) {
+	fn := "_" + progName
+	fmt.Fprintf(w, "%s() {\n", fn)
+	fmt.Fprintf(w, "\tlocal cur=${COMP_WORDS[COMP_CWORD]} prev=${COMP_WORDS[COMP_CWORD-1]} cmd=run words\n")
+	fmt.Fprintf(w, "\tcase ${COMP_WORDS[1]} in\n\t%s) cmd=${COMP_WORDS[1]} ;;\n\tesac\n", strings.Join(commandNames(), "|"))
+	fmt.Fprintf(w, "\tcase ${prev#-} in\n")
+	for _, f := range valuedFlags() {
+		choices, files := flagValues(f.Name)
+		opt := ""
+		if files {
+			opt = "-f "
+		}
+		fmt.Fprintf(w, "\t-%s|%s) COMPREPLY=($(compgen %s-W %q -- \"$cur\")); return ;;\n", f.Name, f.Name, opt, strings.Join(choices, " "))
+	}
+	fmt.Fprintf(w, "\tesac\n\tcase $cmd in\n")
+	for _, c := range commands {
+		var words []string
+		for _, f := range commandFlags(c) {
+			words = append(words, "--"+f.Name)
+		}
+		words = append(words, c.args...)
+		fmt.Fprintf(w, "\t%s) words=%q ;;\n", c.name, strings.Join(words, " "))
+	}
+	fmt.Fprintf(w, "\tesac\n")
+	fmt.Fprintf(w, "\tif [[ $COMP_CWORD -eq 1 ]]; then\n\t\twords=\"$words %s\"\n\tfi\n", strings.Join(commandNames(), " "))
+	fmt.Fprintf(w, "\tCOMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))\n}\n")
+	fmt.Fprintf(w, "complete -F %s %s\n", fn, progName)
+}
+
+// zshQuote escapes s for a single-quoted _arguments spec.
+func zshQuote(s string) string {
+	return strings.NewReplacer("'", `'\''`, "[", `\[`, "]", `\]`, ":", `\:`).Replace(s)
+}
+
+func zshCompletions(w io.Writer) {
+	fmt.Fprintf(w, "#compdef %s\n\n_%s() {\n\tlocal -a commands\n\tcommands=(\n", progName, progName)
+	for _, c := range commands {
+		fmt.Fprintf(w, "\t\t'%s:%s'\n", c.name, zshQuote(c.summary))
+	}
+	fmt.Fprintf(w, "\t)\n\tlocal cmd=run\n")
+	fmt.Fprintf(w, "\tif (( CURRENT == 2 )) && [[ $words[2] != -* ]]; then\n\t\t_describe command commands\n\t\treturn\n\tfi\n")
+	fmt.Fprintf(w, "\tif [[ -n ${commands[(r)$words[2]:*]} ]]; then\n")
+	fmt.Fprintf(w, "\t\tcmd=$words[2]\n\t\tshift words\n\t\t(( CURRENT-- ))\n\tfi\n\tcase $cmd in\n")
+	for _, c := range commands {
+		fmt.Fprintf(w, "\t%s)\n\t\t_arguments", c.name)
+		for _, f := range commandFlags(c) {
+			spec := fmt.Sprintf("--%s[%s]", f.Name, zshQuote(f.Usage))
+			if !isBoolFlag(f) {
+				choices, files := flagValues(f.Name)
+				switch {
+				case len(choices) > 0 && files:
+					spec += fmt.Sprintf(":%s:{_files; compadd %s}", f.Name, strings.Join(choices, " "))
+				case len(choices) > 0:
+					spec += fmt.Sprintf(":%s:(%s)", f.Name, strings.Join(choices, " "))
+				case files:
+					spec += fmt.Sprintf(":%s:_files", f.Name)
+				default:
+					spec += fmt.Sprintf(":%s: ", f.Name)
+				}
+			}
+			fmt.Fprintf(w, " \\\n\t\t\t'%s'", spec)
+		}
+		if len(c.args) > 0 {
+			fmt.Fprintf(w, " \\\n\t\t\t'1:%s:(%s)'", c.name, strings.Join(c.args, " "))
+		}
+		fmt.Fprintf(w, "\n\t\t;;\n")
+	}
+	fmt.Fprintf(w, "\tesac\n}\n\n_%s \"$@\"\n", progName)
+}
+
+// fishQuote escapes s for a single-quoted fish string.
+func fishQuote(s string) string {
+	return strings.NewReplacer(`\`, `\\`, "'", `\'`).Replace(s)
+}
+
+func fishCompletions(w io.Writer) {
+	others := strings.Join(commandNames()[1:], " ")
+	fmt.Fprintf(w, "complete -c %s -f\n", progName)
+	for _, c := range commands {
+		fmt.Fprintf(w, "complete -c %s -n '__fish_use_subcommand' -a %s -d '%s'\n", progName, c.name, fishQuote(c.summary))
+	}
+	for i, c := range commands {
+		cond := "__fish_seen_subcommand_from " + c.name
+		if i == 0 {
+			cond = "not __fish_seen_subcommand_from " + others
+		}
+		for _, f := range commandFlags(c) {
+			opts := ""
+			if !isBoolFlag(f) {
+				choices, files := flagValues(f.Name)
+				opts = " -x"
+				if files {
+					opts = " -rF"
+				}
+				if len(choices) > 0 {
+					opts += fmt.Sprintf(" -a '%s'", strings.Join(choices, " "))
+				}
+			}
+			fmt.Fprintf(w, "complete -c %s -n '%s' -l %s%s -d '%s'\n", progName, cond, f.Name, opts, fishQuote(f.Usage))
+		}
+		if len(c.args) > 0 {
+			fmt.Fprintf(w, "complete -c %s -n '%s' -a '%s'\n", progName, cond, strings.Join(c.args, " "))
+		}
+	}
+}
+
+// psList formats words as a PowerShell array literal.
+func psList(words []string) string {
+	quoted := make([]string, len(words))
+	for i, word := range words {
+		quoted[i] = "'" + strings.ReplaceAll(word, "'", "''") + "'"
+	}
+	return "@(" + strings.Join(quoted, ", ") + ")"
+}
+
+func powershellCompletions(w io.Writer) {
+	fmt.Fprintf(w, "Register-ArgumentCompleter -Native -CommandName %s -ScriptBlock {\n", progName)
+	fmt.Fprintf(w, "\tparam($wordToComplete, $commandAst, $cursorPosition)\n")
+	fmt.Fprintf(w, "\t$words = @($commandAst.CommandElements | ForEach-Object { $_.ToString() })\n")
+	fmt.Fprintf(w, "\t$commands = @{\n")
+	for _, c := range commands {
+		var words []string
+		for _, f := range commandFlags(c) {
+			words = append(words, "--"+f.Name)
+		}
+		fmt.Fprintf(w, "\t\t'%s' = %s\n", c.name, psList(append(words, c.args...)))
+	}
+	fmt.Fprintf(w, "\t}\n\t$values = @{\n")
+	for _, f := range valuedFlags() {
+		if choices, _ := flagValues(f.Name); len(choices) > 0 {
+			fmt.Fprintf(w, "\t\t'--%s' = %s\n", f.Name, psList(choices))
+		}
+	}
+	fmt.Fprintf(w, "\t}\n")
+	fmt.Fprintf(w, "\t$cmd = 'run'\n\tif ($words.Count -gt 1 -and $commands.ContainsKey($words[1])) { $cmd = $words[1] }\n")
+	fmt.Fprintf(w, "\t$prev = if ($wordToComplete) { $words[-2] } else { $words[-1] }\n")
+	fmt.Fprintf(w, "\tif ($values.ContainsKey($prev)) {\n\t\t$candidates = $values[$prev]\n\t} else {\n\t\t$candidates = $commands[$cmd]\n")
+	fmt.Fprintf(w, "\t\tif ($words.Count -le 2) { $candidates += %s }\n\t}\n", psList(commandNames()))
+	fmt.Fprintf(w, "\t$candidates | Where-Object { $_ -like \"$wordToComplete*\" } | ForEach-Object {\n")
+	fmt.Fprintf(w, "\t\t[System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)\n\t}\n}\n")
+}
@@ -0,0 +1,413 @@
+package main
+
+import (
+	"bufio"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"slices"
+	"strconv"
+	"strings"
+	"time"
+	"unicode"
+	"unicode/utf8"
+)
+
+// setting is one key = value line of the config file, the value unquoted
+// and ready for flag.Set. profile names the [profile.name] table it's in
+// and season the [season.name] one, both empty for top-level settings.
+// line and column are where the value is, for error messages.
+type setting struct {
+	key, value      string
+	profile, season string
+	line, column    int
+}
+
+// configPath is where the config file lives: config.toml in the
+// per-user config directory.
+func configPath() (string, error) {
+	dir, err := os.UserConfigDir()
+	if err != nil {
+		return "", err
+	}
+	return filepath.Join(dir, progName, "config.toml"), nil
+}
+
+// loadConfig reads the config file. A missing file is no settings at all.
+func loadConfig() ([]setting, error) {
+	path, err := configPath()
+	if err != nil {
+		return nil, nil
+	}
+	f, err := os.Open(path)
+	if errors.Is(err, os.ErrNotExist) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	defer f.Close()
+	settings, err := parseConfig(f)
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", path, err)
+	}
+	return settings, nil
+}
+
+// firstRun reports whether there's no config file yet.
+func firstRun() bool {
+	path, err := configPath()
+	if err != nil {
+		return false
+	}
+	_, err = os.Stat(path)
+	return errors.Is(err, os.ErrNotExist)
+}
+
+// watchConfig polls the config file every interval and signals on the
+// returned channel whenever its modification time changes, including when
+// it's created or removed.
+func watchConfig(interval time.Duration) <-chan struct{} {
+	changed := make(chan struct{}, 1)
+	path, err := configPath()
+	if err != nil {
+		return changed
+	}
+	modTime := func() time.Time {
+		info, err := os.Stat(path)
+		if err != nil {
+			return time.Time{}
+		}
+		return info.ModTime()
+	}
+	go func() {
+		last := modTime()
+		for range time.Tick(interval) {
+			if t := modTime(); !t.Equal(last) {
+				last = t
+				select {
+				case changed <- struct{}{}:
+				default:
+				}
+			}
+		}
+	}()
+	return changed
+}
+
+// saveConfig writes text as the config file, creating its directory, and
+// returns where it went.
+func saveConfig(text string) (string, error) {
+	path, err := configPath()
+	if err != nil {
+		return "", err
+	}
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return "", err
+	}
+	return path, os.WriteFile(path, []byte(text), 0o644)
+}
+
+// parseConfig reads the small subset of TOML the config file uses: one
+// key = value per line, the value a quoted string, a number or a boolean,
+// and [profile.name] and [season.name] headers starting a profile's or a
+// season's table. Blank lines and lines starting with # are skipped.
+func parseConfig(r io.Reader) ([]setting, error) {
+	var settings []setting
+	scanner := bufio.NewScanner(r)
+	line := 0
+	profile, season := "", ""
+	for scanner.Scan() {
+		line++
+		raw := scanner.Text()
+		text := strings.TrimSpace(raw)
+		if text == "" || strings.HasPrefix(text, "#") {
+			continue
+		}
+		if strings.HasPrefix(text, "[") {
+			table, ok := strings.CutSuffix(text, "]")
+			kind, name, _ := strings.Cut(strings.TrimSpace(table[1:]), ".")
+			if !ok || kind != "profile" && kind != "season" || name == "" {
+				return nil, errorAt(line, indent(raw), "want a [profile.name] or [season.name] table, got %s", text)
+			}
+			profile, season = "", ""
+			if kind == "profile" {
+				profile = name
+			} else {
+				season = name
+			}
+			continue
+		}
+		key, value, column, err := parseKeyValue(raw, line)
+		if err != nil {
+			return nil, err
+		}
+		if key == "profile" && profile != "" {
+			return nil, errorAt(line, indent(raw), "a profile can't pick another profile")
+		}
+		settings = append(settings, setting{key, value, profile, season, line, column})
+	}
+	return settings, scanner.Err()
+}
+
+// parseKeyValue reads a key = value line of the config file or a timeline,
+// unquoting the value if it's a string, and says which column the value
+// starts at.
+func parseKeyValue(text string, line int) (key, value string, column int, err error) {
+	eq := strings.Index(text, "=")
+	if eq < 0 {
+		return "", "", 0, errorAt(line, indent(text), "want key = value")
+	}
+	key, value = strings.TrimSpace(text[:eq]), strings.TrimSpace(text[eq+1:])
+	if key == "" {
+		return "", "", 0, errorAt(line, columnAt(text, eq), "no key before =")
+	}
+	rest := text[eq+1:]
+	start := len(text) - len(strings.TrimLeftFunc(rest, unicode.IsSpace))
+	column = columnAt(text, start)
+	if strings.HasPrefix(value, `"`) {
+		quoted, err := strconv.QuotedPrefix(value)
+		if err != nil {
+			return "", "", 0, errorAt(line, column, "bad string %s", value)
+		}
+		// After the string there can only be a comment.
+		after := value[len(quoted):]
+		if trimmed := strings.TrimLeftFunc(after, unicode.IsSpace); trimmed != "" && !strings.HasPrefix(trimmed, "#") {
+			return "", "", 0, errorAt(line, columnAt(text, start+len(quoted)+len(after)-len(trimmed)), "want a comment or nothing after the string, got %s", trimmed)
+		}
+		value, _ = strconv.Unquote(quoted)
+	} else if i := strings.Index(value, "#"); i >= 0 {
+		value = strings.TrimSpace(value[:i])
+	}
+	return key, value, column, nil
+}
+
+// errorAt is an error in a file being parsed, at a line and column
+// counted from 1, the column in characters.
+func errorAt(line, column int, format string, args ...any) error {
+	return fmt.Errorf("line %d, column %d: "+format, append([]any{line, column}, args...)...)
+}
+
+// columnAt is the column the byte at i of text is in.
+func columnAt(text string, i int) int {
+	return utf8.RuneCountInString(text[:i]) + 1
+}
+
+// indent is the column of the first character on a line that isn't white
+// space.
+func indent(text string) int {
+	return columnAt(text, len(text)-len(strings.TrimLeftFunc(text, unicode.IsSpace)))
+}
+
+// parseFlags applies the config file to fs and then parses args on top, so
+// the command line wins. The top-level settings go first, then those of the
+// profile picked with --profile, then environment variables. With
+// --seasonal, the settings of the season it is go on top of the config
+// file's, still under the command line.
+func parseFlags(fs *flag.FlagSet, args []string) error {
+	return parseProfile(fs, args, "")
+}
+
+// parseProfile is parseFlags with the profile picked by name rather than by
+// --profile, unless name is empty. Settings for run flags fs doesn't have
+// are skipped; anything else is an error.
+func parseProfile(fs *flag.FlagSet, args []string, name string) error {
+	settings, err := loadConfig()
+	if err != nil {
+		return err
+	}
+	run := runFlagSet()
+	apply := func(profile string, settings []setting) error {
+		for _, s := range settings {
+			if s.profile != profile {
+				continue
+			}
+			if fs.Lookup(s.key) == nil {
+				if run.Lookup(s.key) != nil {
+					continue
+				}
+				return fmt.Errorf("config %w", errorAt(s.line, s.column, "unknown setting %q", s.key))
+			}
+			if err := fs.Set(s.key, s.value); err != nil {
+				return fmt.Errorf("config %w", errorAt(s.line, s.column, "%s: %w", s.key, err))
+			}
+		}
+		return nil
+	}
+	topLevel := slices.DeleteFunc(slices.Clone(settings), func(s setting) bool { return s.season != "" })
+	if err := apply("", topLevel); err != nil {
+		return err
+	}
+	if err := applyEnv(fs); err != nil {
+		return err
+	}
+	if err := fs.Parse(args); err != nil {
+		return err
+	}
+	if name == "" {
+		if f := fs.Lookup("profile"); f != nil {
+			name = f.Value.String()
+		}
+	}
+	if name != "" {
+		if !slices.Contains(profileNames(settings), name) {
+			return fmt.Errorf("no profile %q in the config file", name)
+		}
+		if err := apply(name, settings); err != nil {
+			return err
+		}
+		if err := applyEnv(fs); err != nil {
+			return err
+		}
+		if err := fs.Parse(args); err != nil {
+			return err
+		}
+	}
+	if f := fs.Lookup("seasonal"); f == nil || f.Value.String() != "true" {
+		return nil
+	}
+	s, err := currentSeason(settings, time.Now())
+	if s == nil || err != nil {
+		return err
+	}
+	if err := apply("", s.settings); err != nil {
+		return err
+	}
+	if err := applyEnv(fs); err != nil {
+		return err
+	}
+	return fs.Parse(args)
+}
+
+// envName is the environment variable overriding a flag: AAAA_MAX_STREAMS
+// for --max-streams.
+func envName(flag string) string {
+	return "AAAA_" + strings.ToUpper(strings.ReplaceAll(flag, "-", "_"))
+}
+
+// applyEnv sets every flag in fs that has its environment variable set.
+func applyEnv(fs *flag.FlagSet) error {
+	var err error
+	fs.VisitAll(func(f *flag.Flag) {
+		value, ok := os.LookupEnv(envName(f.Name))
+		if !ok || err != nil {
+			return
+		}
+		if e := fs.Set(f.Name, value); e != nil {
+			err = fmt.Errorf("%s: %w", envName(f.Name), e)
+		}
+	})
+	return err
+}
+
+// saveProfile appends o to the config file as a new profile, saved-1 or the
+// next number free, and returns its name. It writes the settings the
+// runtime keys change, plus any others that differ from their defaults.
+func saveProfile(o *runOptions) (string, error) {
+	settings, err := loadConfig()
+	if err != nil {
+		return "", err
+	}
+	names := profileNames(settings)
+	name := ""
+	for n := 1; name == "" || slices.Contains(names, name); n++ {
+		name = fmt.Sprintf("saved-%d", n)
+	}
+	var sb strings.Builder
+	fmt.Fprintf(&sb, "\n[profile.%s]\n", name)
+	o.flags().VisitAll(func(f *flag.Flag) {
+		tweakable := f.Name == "theme" || f.Name == "charset" || f.Name == "speed" || f.Name == "chaos"
+		if f.Name != "profile" && (tweakable || f.Value.String() != f.DefValue) {
+			fmt.Fprintf(&sb, "%s = %s\n", f.Name, tomlValue(f.Value.String()))
+		}
+	})
+
+	path, err := configPath()
+	if err != nil {
+		return "", err
+	}
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return "", err
+	}
+	f, err := os.OpenFile(path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0o644)
+	if err != nil {
+		return "", err
+	}
+	if _, err := f.WriteString(sb.String()); err != nil {
+		f.Close()
+		return "", err
+	}
+	return name, f.Close()
+}
+
+// profileNames lists the profiles settings define, in the order they
+// first appear.
+func profileNames(settings []setting) []string {
+	var names []string
+	for _, s := range settings {
+		if s.profile != "" && !slices.Contains(names, s.profile) {
+			names = append(names, s.profile)
+		}
+	}
+	return names
+}
+
+// tomlValue formats a flag value for the config file, quoting anything that
+// isn't a number or a boolean.
+func tomlValue(s string) string {
+	if s == "true" || s == "false" {
+		return s
+	}
+	if _, err := strconv.ParseFloat(s, 64); err == nil {
+		return s
+	}
+	return strconv.Quote(s)
+}
+
+// runFlagSet is the run command's flags, for listing and writing settings.
+func runFlagSet() *flag.FlagSet {
+	return commands[0].flags()
+}
+
+func runConfig(args []string) error {
+	action := "show"
+	if len(args) > 0 {
+		action = args[0]
+	}
+	path, err := configPath()
+	if err != nil {
+		return err
+	}
+	switch action {
+	case "path":
+		fmt.Println(path)
+	case "show":
+		fs := runFlagSet()
+		if err := parseFlags(fs, nil); err != nil {
+			return err
+		}
+		fmt.Printf("# %s\n", path)
+		fs.VisitAll(func(f *flag.Flag) {
+			fmt.Printf("%s = %s\n", f.Name, tomlValue(f.Value.String()))
+		})
+	case "init":
+		if !firstRun() {
+			return fmt.Errorf("%s already exists", path)
+		}
+		var sb strings.Builder
+		fmt.Fprintf(&sb, "# %s settings, named after the run command's flags.\n", progName)
+		runFlagSet().VisitAll(func(f *flag.Flag) {
+			fmt.Fprintf(&sb, "\n# %s\n# %s = %s\n", f.Usage, f.Name, tomlValue(f.DefValue))
+		})
+		if _, err := saveConfig(sb.String()); err != nil {
+			return err
+		}
+		fmt.Println("wrote", path)
+	default:
+		return fmt.Errorf("unknown action %q (want show, path or init)", action)
+	}
+	return nil
+}
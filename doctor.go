@@ -0,0 +1,150 @@
+package main
+
+import (
+	"flag"
+	"fmt"
+	"os"
+	"slices"
+	"strings"
+)
+
+// runDoctor reports what the terminal and environment support, and so what
+// the animation will look like here, then prints the charset as a test
+// pattern so missing glyphs in the font show up.
+func runDoctor(args []string) error {
+	fs := flag.NewFlagSet("doctor", flag.ExitOnError)
+	charsetName := fs.String("charset", "default", "charset to test, built-in or a file")
+	fs.Parse(args)
+	charset, err := (&runOptions{charset: *charsetName}).loadCharset()
+	if err != nil {
+		return err
+	}
+
+	report := func(ok bool, name, detail string) {
+		mark := "\x1b[32m✓\x1b[0m"
+		if !ok {
+			mark = "\x1b[31m✗\x1b[0m"
+		}
+		fmt.Printf("%s %-10s %s\n", mark, name, detail)
+	}
+
+	if isTerminal(os.Stdout) {
+		report(true, "terminal", "stdout is a terminal")
+	} else {
+		report(false, "terminal", "stdout isn't a terminal; run would draw into a pipe")
+	}
+	if isTerminal(os.Stdin) {
+		report(true, "keys", "stdin is a terminal, so keys work")
+	} else {
+		report(false, "keys", "stdin isn't a terminal; run filters it instead of animating")
+	}
+
+	width, height := terminalSize()
+	if width >= 40 && height >= 10 {
+		report(true, "size", fmt.Sprintf("%dx%d", width, height))
+	} else {
+		report(false, "size", fmt.Sprintf("%dx%d; banners and the clock need at least 40x10", width, height))
+	}
+
+	colorterm := os.Getenv("COLORTERM")
+	if colorterm == "truecolor" || colorterm == "24bit" {
+		report(true, "truecolor", "COLORTERM="+colorterm)
+	} else {
+		report(false, "truecolor", "COLORTERM isn't truecolor; colors may be approximated")
+	}
+
+	if term := os.Getenv("TERM"); attrsSupported(term) {
+		report(true, "attrs", "bold, underline and reverse video are sent")
+	} else {
+		report(false, "attrs", fmt.Sprintf("TERM=%s draws text attributes badly, so --attrs auto leaves them out", term))
+	}
+
+	if legacyConsole() {
+		report(false, "console", "the old Windows console gets ASCII, 16 colors and half the frames; Windows Terminal shows everything")
+	}
+
+	if l := locale(); localeUTF8(l) {
+		report(true, "locale", l)
+	} else {
+		report(false, "locale", fmt.Sprintf("%q isn't UTF-8, so --ascii auto draws in ASCII", l))
+	}
+
+	path, err := configPath()
+	if err != nil {
+		report(false, "config", err.Error())
+	} else if _, err := loadConfig(); err != nil {
+		report(false, "config", err.Error())
+	} else {
+		report(true, "config", path)
+	}
+
+	if isTerminal(os.Stdin) && isTerminal(os.Stdout) {
+		if p, err := newProbe(); err == nil {
+			doctorProbe(p, charset, report)
+			p.close()
+		}
+	}
+
+	fmt.Println("\ncharset test pattern; every glyph should be one cell wide with no boxes or question marks:")
+	for i, c := range charset.choices {
+		if i > 0 && i%16 == 0 {
+			fmt.Println()
+		}
+		fmt.Print(c.item + " ")
+	}
+	fmt.Println()
+	return nil
+}
+
+// doctorProbe runs the checks that need answers from the terminal itself.
+func doctorProbe(p *Probe, charset *Charset, report func(ok bool, name, detail string)) {
+	attrs := p.attributes()
+	if attrs == nil {
+		report(false, "probe", "the terminal didn't answer queries; skipping the rest")
+		return
+	}
+
+	switch w := p.width("A̷"); w {
+	case 1:
+		report(true, "combining", "combining marks stack on their letter, so --zalgo works")
+	case -1:
+		report(false, "combining", "couldn't measure")
+	default:
+		report(false, "combining", fmt.Sprintf("a letter with a mark took %d cells; use --zalgo 0 and a plain charset", w))
+	}
+	switch w := p.width("ｱ"); w {
+	case 2:
+		report(true, "wide", "double-width glyphs take two cells")
+	case -1:
+		report(false, "wide", "couldn't measure")
+	default:
+		report(false, "wide", fmt.Sprintf("a double-width glyph took %d cells; wide charsets will misalign", w))
+	}
+	var off []string
+	for _, c := range charset.choices {
+		if w := p.width(c.item); w >= 0 && w != cellCount(c.item) {
+			off = append(off, c.item)
+		}
+	}
+	if len(off) == 0 {
+		report(true, "glyphs", fmt.Sprintf("all %d glyphs are the width they should be", charset.len()))
+	} else {
+		report(false, "glyphs", fmt.Sprintf("these glyphs are the wrong width and will jitter: %s", strings.Join(off, " ")))
+	}
+
+	if p.mode(1006) {
+		report(true, "mouse", "SGR mouse reporting is supported")
+	} else {
+		report(false, "mouse", "SGR mouse reporting isn't supported; mouse input won't work")
+	}
+	if p.kittyGraphics() {
+		report(true, "kitty", "kitty graphics protocol is supported")
+	} else {
+		report(false, "kitty", "no kitty graphics protocol")
+	}
+	if slices.Contains(attrs, 4) {
+		report(true, "sixel", "sixel graphics are supported")
+	} else {
+		report(false, "sixel", "no sixel graphics")
+	}
+}
@@ -0,0 +1,66 @@
+package main
+
+import (
+	"fmt"
+	"math"
+	"slices"
+	"strings"
+	"time"
+)
+
+// Shape is the wave an --envelope follows.
+type Shape int
+
+const (
+	ShapeSine Shape = iota
+	ShapeRamp
+	ShapeSawtooth
+)
+
+var shapeNames = []string{"sine", "ramp", "sawtooth"}
+
+// The envelope scales chaos, speed and brightness between these, calm at
+// the bottom of the wave and frantic at the top.
+var (
+	envelopeCalm    = Params{chaos: 0.25, speed: 0.5, brightness: 0.6}
+	envelopeFrantic = Params{chaos: 2, speed: 1.5, brightness: 1}
+)
+
+// Envelope makes the animation breathe, swinging it between calm and
+// frantic over a long period.
+type Envelope struct {
+	shape  Shape
+	period time.Duration
+	start  time.Time
+}
+
+// parseEnvelope reads --envelope, a shape and its period such as sine:120s.
+func parseEnvelope(s string) (*Envelope, error) {
+	name, period, ok := strings.Cut(s, ":")
+	shape := slices.Index(shapeNames, name)
+	d, err := time.ParseDuration(period)
+	if !ok || shape < 0 || err != nil || d <= 0 {
+		return nil, fmt.Errorf("bad envelope %q (want shape:period, such as sine:120s, the shape one of %v)", s, shapeNames)
+	}
+	return &Envelope{shape: Shape(shape), period: d, start: time.Now()}, nil
+}
+
+// level is where the wave is, from 0 for calm to 1 for frantic, at phase
+// t of the way through a period. Each shape starts calm.
+func (s Shape) level(t float64) float64 {
+	switch s {
+	case ShapeRamp:
+		return 1 - math.Abs(2*t-1)
+	case ShapeSawtooth:
+		return t
+	}
+	return (1 - math.Cos(2*math.Pi*t)) / 2
+}
+
+func (e *Envelope) modulate(p *Params) {
+	t := math.Mod(float64(time.Since(e.start))/float64(e.period), 1)
+	l := e.shape.level(t)
+	p.chaos *= lerp(l, envelopeCalm.chaos, envelopeFrantic.chaos)
+	p.speed *= lerp(l, envelopeCalm.speed, envelopeFrantic.speed)
+	p.brightness *= lerp(l, envelopeCalm.brightness, envelopeFrantic.brightness)
+}
@@ -0,0 +1,47 @@
+package main
+
+import "math/rand"
+
+// With --evolve, a fork inherits its parent's traits, each nudged a little
+// at random, by up to these fractions either way: its speed, lifetime and
+// turn chance, and its color by up to evolveColor on each channel. Now and
+// then, with evolveGlyphChance, one of its glyphs is swapped for another.
+const (
+	evolveSpeed       = 0.1
+	evolveLifetime    = 0.1
+	evolveTurns       = 0.15
+	evolveColor       = 12
+	evolveGlyphChance = 0.2
+)
+
+// evolveForkChance scales a stream's chance of forking by its age against
+// the average lifetime, so the streams that live longest leave the most
+// children and long lives are selected for.
+func (sim *Sim) evolveForkChance(s *Stream) float64 {
+	return 2 * float64(s.age) / float64(sim.lifetime)
+}
+
+// inherit passes parent's traits on to child, mutated.
+func (sim *Sim) inherit(child, parent *Stream) {
+	rng := sim.rng
+	nudge := func(v, by float64) float64 {
+		return v * (1 + by*(2*rng.Float64()-1))
+	}
+	child.speed = clampFloat(nudge(parent.speed, evolveSpeed), 0.2, 2)
+	child.turns = clampFloat(nudge(parent.turns, evolveTurns), 0, 4)
+	child.lifetime = int(clampFloat(nudge(float64(parent.lifetime), evolveLifetime), float64(sim.lifetime)/4, float64(sim.lifetime)*4))
+	child.color = Color{
+		jitterChannel(rng, parent.color.R),
+		jitterChannel(rng, parent.color.G),
+		jitterChannel(rng, parent.color.B),
+	}
+	if rng.Float64() < evolveGlyphChance && len(parent.glyphs.choices) > 0 {
+		glyphs := newWeighted(parent.glyphs.choices...)
+		glyphs.choices[rng.Intn(len(glyphs.choices))].item = sim.charset.pick(rng)
+		child.glyphs = glyphs
+	}
+}
+
+func jitterChannel(rng *rand.Rand, c uint8) uint8 {
+	return uint8(clampFloat(float64(c)+float64(rng.Intn(2*evolveColor+1)-evolveColor), 0, 255))
+}
@@ -0,0 +1,106 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/json"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"time"
+)
+
+// headless sets up a sim and a screen of a fixed size with no terminal
+// behind them, the screen writing to w.
+func headless(cfg SimConfig, width, height int, w io.Writer) (*Sim, *Screen) {
+	scr := newScreen(w)
+	scr.keep = true
+	scr.resize(width, height)
+	sim := newSim(cfg)
+	sim.resize(width, height)
+	return sim, scr
+}
+
+// checkSize turns down a --width or --height with no cells to draw in.
+func checkSize(width, height int) error {
+	if width < 1 || height < 1 {
+		return fmt.Errorf("--width and --height must be positive")
+	}
+	return nil
+}
+
+type exportOptions struct {
+	width, height int
+	frames        int
+	format        string
+	output        string
+}
+
+func (o *exportOptions) register(fs *flag.FlagSet) {
+	fs.IntVar(&o.width, "width", 80, "frame width in cells")
+	fs.IntVar(&o.height, "height", 24, "frame height in rows")
+	fs.IntVar(&o.frames, "frames", 100, "number of frames to render")
+	fs.StringVar(&o.format, "format", "cast", "output format: cast (asciicast v2) or ansi")
+	fs.StringVar(&o.output, "o", "", "write to this file instead of stdout")
+}
+
+// runExport renders frames without a terminal, either as an asciicast v2
+// recording (one output event per tick) or as the raw escape stream.
+func runExport(args []string) error {
+	fs := flag.NewFlagSet("export", flag.ExitOnError)
+	var o runOptions
+	var e exportOptions
+	o.register(fs)
+	e.register(fs)
+	if err := parseFlags(fs, args); err != nil {
+		return err
+	}
+	if e.format != "cast" && e.format != "ansi" {
+		return fmt.Errorf("unknown format %q (want cast or ansi)", e.format)
+	}
+	if err := checkSize(e.width, e.height); err != nil {
+		return err
+	}
+	sc, err := o.scene()
+	if err != nil {
+		return err
+	}
+
+	out := io.Writer(os.Stdout)
+	if e.output != "" {
+		f, err := os.Create(e.output)
+		if err != nil {
+			return err
+		}
+		defer f.Close()
+		out = f
+	}
+	w := bufio.NewWriter(out)
+	if e.format == "cast" {
+		header, _ := json.Marshal(map[string]any{"version": 2, "width": e.width, "height": e.height})
+		fmt.Fprintf(w, "%s\n", header)
+	}
+
+	var frame bytes.Buffer
+	emit := func(i int) {
+		if e.format == "cast" {
+			data, _ := json.Marshal(frame.String())
+			fmt.Fprintf(w, "[%.3f, \"o\", %s]\n", (time.Duration(i) * tickInterval).Seconds(), data)
+		} else {
+			w.Write(frame.Bytes())
+		}
+		frame.Reset()
+	}
+	sim, scr := headless(sc.SimConfig, e.width, e.height, &frame)
+	scr.enter()
+	for i := range e.frames {
+		sim.update()
+		sim.draw(scr)
+		scr.flush()
+		emit(i)
+	}
+	scr.leave()
+	emit(e.frames)
+	return w.Flush()
+}
@@ -0,0 +1,150 @@
+//go:build ffi
+
+package main
+
+/*
+#include <stdint.h>
+#include <stdlib.h>
+
+// aaaa_cell is one lit cell of a frame: its text, a character and any
+// combining marks in UTF-8, and its colors.
+typedef struct {
+	int32_t x, y;
+	const char *text;
+	uint8_t fg_r, fg_g, fg_b;
+	uint8_t has_bg;
+	uint8_t bg_r, bg_g, bg_b;
+} aaaa_cell;
+*/
+import "C"
+
+import (
+	"io"
+	"runtime/cgo"
+	"unsafe"
+)
+
+// Built with -tags ffi -buildmode=c-shared, the program is also a library
+// for running animations from other programs, through a C ABI:
+//
+//	uintptr_t e = aaaa_engine_new(argc, argv, 80, 24);
+//	for (;;) {
+//		aaaa_engine_tick(e);
+//		int n;
+//		const aaaa_cell *cells = aaaa_engine_cells(e, &n);
+//		...draw cells[0] to cells[n-1]...
+//	}
+//	aaaa_engine_free(e);
+//
+// aaaa_engine_new takes run's flags, and returns 0 if they're bad. A tick
+// is frame's worth of time, a tenth of a second normally. The cells stay
+// valid until the next tick.
+
+// engine is an animation run for a library user.
+type engine struct {
+	sim *Sim
+	scr *Screen
+
+	// cells holds the frame's lit cells, count of them, in C memory with
+	// room for capacity.
+	cells    *C.aaaa_cell
+	count    int
+	capacity int
+
+	// texts holds C copies of the cells' texts.
+	texts map[string]*C.char
+}
+
+//export aaaa_engine_new
+func aaaa_engine_new(argc C.int, argv **C.char, width, height C.int) C.uintptr_t {
+	var args []string
+	if argc > 0 {
+		for _, arg := range unsafe.Slice(argv, argc) {
+			args = append(args, C.GoString(arg))
+		}
+	}
+	sc, err := embeddedScene(args)
+	if err != nil || width <= 0 || height <= 0 {
+		return 0
+	}
+	sim, scr := headless(sc.SimConfig, int(width), int(height), io.Discard)
+	return C.uintptr_t(cgo.NewHandle(&engine{sim: sim, scr: scr, texts: map[string]*C.char{}}))
+}
+
+//export aaaa_engine_resize
+func aaaa_engine_resize(h C.uintptr_t, width, height C.int) {
+	if width <= 0 || height <= 0 {
+		return
+	}
+	e := cgo.Handle(h).Value().(*engine)
+	e.scr.resize(int(width), int(height))
+	e.sim.resize(int(width), int(height))
+}
+
+//export aaaa_engine_tick
+func aaaa_engine_tick(h C.uintptr_t) {
+	e := cgo.Handle(h).Value().(*engine)
+	e.sim.update()
+	e.sim.draw(e.scr)
+	e.collect()
+	e.scr.next()
+}
+
+//export aaaa_engine_cells
+func aaaa_engine_cells(h C.uintptr_t, count *C.int) *C.aaaa_cell {
+	e := cgo.Handle(h).Value().(*engine)
+	*count = C.int(e.count)
+	return e.cells
+}
+
+//export aaaa_engine_free
+func aaaa_engine_free(h C.uintptr_t) {
+	e := cgo.Handle(h).Value().(*engine)
+	e.freeTexts()
+	C.free(unsafe.Pointer(e.cells))
+	cgo.Handle(h).Delete()
+}
+
+// collect copies the screen's lit cells out to C.
+func (e *engine) collect() {
+	// Zalgo makes endless new texts, so they're let go now and then,
+	// while no cells point at them.
+	if len(e.texts) > maxInterned {
+		e.freeTexts()
+	}
+	if n := len(e.scr.cells); n > e.capacity {
+		C.free(unsafe.Pointer(e.cells))
+		e.cells = (*C.aaaa_cell)(C.malloc(C.size_t(n) * C.size_t(unsafe.Sizeof(C.aaaa_cell{}))))
+		e.capacity = n
+	}
+	out := unsafe.Slice(e.cells, e.capacity)
+	e.count = 0
+	for i, c := range e.scr.cells {
+		if c.text == "" || c.text == wideTail {
+			continue
+		}
+		text, ok := e.texts[c.text]
+		if !ok {
+			text = C.CString(c.text)
+			e.texts[c.text] = text
+		}
+		out[e.count] = C.aaaa_cell{
+			x:    C.int32_t(i % e.scr.width),
+			y:    C.int32_t(i / e.scr.width),
+			text: text,
+			fg_r: C.uint8_t(c.fg.R), fg_g: C.uint8_t(c.fg.G), fg_b: C.uint8_t(c.fg.B),
+			bg_r: C.uint8_t(c.bg.R), bg_g: C.uint8_t(c.bg.G), bg_b: C.uint8_t(c.bg.B),
+		}
+		if c.hasBg {
+			out[e.count].has_bg = 1
+		}
+		e.count++
+	}
+}
+
+func (e *engine) freeTexts() {
+	for _, text := range e.texts {
+		C.free(unsafe.Pointer(text))
+	}
+	clear(e.texts)
+}
@@ -0,0 +1,38 @@
+package main
+
+import (
+	"bufio"
+	"io"
+)
+
+// defaultFilterGlitch is the share of characters swapped for glyphs when
+// filtering and --glitch isn't given.
+const defaultFilterGlitch = 0.05
+
+// runFilter copies in to out line by line, lolcat-style: every character
+// gets a color from a rainbow sweeping diagonally across the text, and is
+// swapped for a glyph with probability chance and then zalgo'd.
+func runFilter(in io.Reader, out io.Writer, charset *Charset, chance float64, zalgoIntensity int) error {
+	scanner := bufio.NewScanner(in)
+	scanner.Buffer(make([]byte, 64*1024), 1024*1024)
+	w := bufio.NewWriter(out)
+	rng := newRand(0)
+	defer w.Flush()
+	for line := 0; scanner.Scan(); line++ {
+		col := 0
+		for _, r := range scanner.Text() {
+			text := string(r)
+			if r != ' ' && r != '\t' && rng.Float64() < chance {
+				text = charset.pick(rng)
+			}
+			w.WriteString(rainbow(float64(line)*0.05 + float64(col)*0.02).fg())
+			w.WriteString(zalgo(rng, text, zalgoIntensity))
+			col++
+		}
+		w.WriteString("\x1b[0m\n")
+		if err := w.Flush(); err != nil {
+			return err
+		}
+	}
+	return scanner.Err()
+}
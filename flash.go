@@ -0,0 +1,54 @@
+package main
+
+import (
+	"strings"
+	"sync/atomic"
+	"time"
+)
+
+const flashFor = 1500 * time.Millisecond
+
+type flashMessage struct {
+	text string
+	at   time.Time
+}
+
+// Flash briefly shows a message along the bottom row, such as the name of a
+// profile just switched to. show is called from the input side; the sim
+// draws it, blanking it again once it has expired.
+type Flash struct {
+	message atomic.Pointer[flashMessage]
+
+	// drawnX and drawnWidth say where the last message was drawn.
+	drawnX, drawnWidth int
+}
+
+func (f *Flash) show(text string) {
+	logger.Info("flash", "text", text)
+	f.message.Store(&flashMessage{text, time.Now()})
+}
+
+func (f *Flash) update(sim *Sim) {}
+
+func (f *Flash) draw(sim *Sim, scr *Screen) {
+	m := f.message.Load()
+	if m == nil {
+		return
+	}
+	y := sim.height - 1
+	if time.Since(m.at) > flashFor {
+		if f.drawnWidth > 0 {
+			scr.put(f.drawnX, y, strings.Repeat(" ", f.drawnWidth), sim.palette.primary)
+			f.drawnWidth = 0
+		}
+		return
+	}
+	text := " " + m.text + " "
+	width := cellCount(text)
+	x := max(0, (sim.width-width)/2)
+	if f.drawnWidth > 0 && (x != f.drawnX || width != f.drawnWidth) {
+		scr.put(f.drawnX, y, strings.Repeat(" ", f.drawnWidth), sim.palette.primary)
+	}
+	scr.put(x, y, text, sim.palette.primary)
+	f.drawnX, f.drawnWidth = x, width
+}
@@ -0,0 +1,107 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+)
+
+var fxNames = []string{"scanlines", "vignette", "chromatic"}
+
+// parseFX reads --fx, a comma-separated list of post-processing passes to
+// run over every frame in turn.
+func parseFX(s string) ([]Pass, error) {
+	if s == "" {
+		return nil, nil
+	}
+	var passes []Pass
+	for _, name := range strings.Split(s, ",") {
+		switch strings.TrimSpace(name) {
+		case "scanlines":
+			passes = append(passes, scanlines{})
+		case "vignette":
+			passes = append(passes, vignette{})
+		case "chromatic":
+			passes = append(passes, &chromatic{})
+		default:
+			return nil, fmt.Errorf("unknown fx %q (want one or more of %s, comma-separated)", name, strings.Join(fxNames, ", "))
+		}
+	}
+	return passes, nil
+}
+
+// dimCell dims both of a cell's colors.
+func dimCell(c *cell, f float64) {
+	c.fg, c.bg = c.fg.dim(f), c.bg.dim(f)
+}
+
+// scanlines dims every other row, like the gaps between a CRT's lines.
+type scanlines struct{}
+
+const scanlineDim = 0.6
+
+func (scanlines) apply(sim *Sim, scr *Screen) {
+	for y := 1; y < scr.window.height; y += 2 {
+		row := scr.row(y)
+		for i := range row {
+			dimCell(&row[i], scanlineDim)
+		}
+	}
+}
+
+// vignette darkens the cells towards the edges of the window.
+type vignette struct{}
+
+// vignetteDepth is how much darker the corners are than the middle.
+const vignetteDepth = 0.7
+
+func (vignette) apply(sim *Sim, scr *Screen) {
+	w, h := float64(scr.window.width), float64(scr.window.height)
+	for y := range scr.window.height {
+		dy := (float64(y)+0.5)/h*2 - 1
+		row := scr.row(y)
+		for x := range row {
+			if row[x].text == "" {
+				continue
+			}
+			dx := (float64(x)+0.5)/w*2 - 1
+			dimCell(&row[x], 1-vignetteDepth*min(1, (dx*dx+dy*dy)/2))
+		}
+	}
+}
+
+// chromatic leaves faint ghosts of every glyph to either side, one in its
+// red and one in its blue, where nothing else is drawn, like a lens that
+// doesn't quite bring colors together.
+type chromatic struct {
+	// shown is a copy of the row being worked on.
+	shown []cell
+}
+
+const chromaticGhost = 0.5
+
+func (p *chromatic) apply(sim *Sim, scr *Screen) {
+	for y := range scr.window.height {
+		row := scr.row(y)
+		p.shown = append(p.shown[:0], row...)
+		for x, c := range p.shown {
+			if c.text == "" || c.text == " " || c.text == wideTail {
+				continue
+			}
+			n := cellWidth(c.wide)
+			// A wide glyph's ghost is wide too, and needs both its cells free.
+			ghost := func(x int, tint Color) {
+				if x < 0 || x+n > len(row) {
+					return
+				}
+				for _, s := range p.shown[x : x+n] {
+					if s.text != "" {
+						return
+					}
+				}
+				placeCell(row, x, cell{text: c.text, fg: tint.dim(chromaticGhost), wide: c.wide})
+			}
+			ghost(x-n, Color{R: c.fg.R})
+			ghost(x+n, Color{B: c.fg.B})
+		}
+	}
+}
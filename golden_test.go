@@ -0,0 +1,167 @@
+package main
+
+import (
+	"bytes"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"testing"
+)
+
+var update = flag.Bool("update", false, "rewrite the golden files in testdata/golden")
+
+const (
+	goldenWidth, goldenHeight = 80, 24
+	goldenFrames              = 50
+)
+
+// goldenModes are the modes whose frames depend on nothing but the seed:
+// clock mode shows the time, and tail and image modes need a file, which
+// TestGoldenWide gives tail mode.
+var goldenModes = []string{"streams", "maze", "typer", "ticker"}
+
+// TestGoldenModes renders each mode, with a fixed seed, and compares the
+// last frame with the one checked in.
+func TestGoldenModes(t *testing.T) {
+	for _, mode := range goldenModes {
+		t.Run(mode, func(t *testing.T) {
+			checkGolden(t, "mode-"+mode, func(o *runOptions) { o.mode = mode })
+		})
+	}
+}
+
+// goldenWideWords are words of wide glyphs, and a few mixed with narrow
+// ones, for the modes that lay out text a glyph after another.
+const goldenWideWords = "漢字 かな 한글\nＡＢＣ 🌲🌲 mixed漢字\na宽b 端末 文字列\n"
+
+// TestGoldenWide checks that typer mode typing, and tail mode following,
+// wide glyphs give each two columns.
+func TestGoldenWide(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "words")
+	if err := os.WriteFile(path, []byte(goldenWideWords), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	t.Run("typer", func(t *testing.T) {
+		checkGolden(t, "wide-typer", func(o *runOptions) { o.mode, o.words = "typer", path })
+	})
+	t.Run("tail", func(t *testing.T) {
+		checkGolden(t, "wide-tail", func(o *runOptions) { o.mode, o.file = "tail", path })
+	})
+}
+
+// TestGoldenThemes renders the streams in each theme.
+func TestGoldenThemes(t *testing.T) {
+	for _, theme := range themeNames() {
+		t.Run(theme, func(t *testing.T) {
+			checkGolden(t, "theme-"+theme, func(o *runOptions) { o.theme = theme })
+		})
+	}
+}
+
+// checkGolden renders goldenFrames frames of the test scene, as set tells
+// it, and compares a snapshot of the last with testdata/golden/name.txt, or
+// with -update writes it there.
+func checkGolden(t *testing.T, name string, set func(o *runOptions)) {
+	var o runOptions
+	o.register(flag.NewFlagSet("test", flag.ContinueOnError))
+	o.background, o.seed = "dark", 1
+	o.text = "AAAAAAAAA golden frame"
+	set(&o)
+	sc, err := o.scene()
+	if err != nil {
+		t.Fatal(err)
+	}
+	sim, scr := headless(sc.SimConfig, goldenWidth, goldenHeight, io.Discard)
+	for range goldenFrames {
+		sim.update()
+		sim.draw(scr)
+		if err := scr.flush(); err != nil {
+			t.Fatal(err)
+		}
+	}
+	got := snapshot(scr)
+
+	path := filepath.Join("testdata", "golden", name+".txt")
+	if *update {
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(path, got, 0o644); err != nil {
+			t.Fatal(err)
+		}
+		return
+	}
+	want, err := os.ReadFile(path)
+	if os.IsNotExist(err) {
+		t.Fatalf("no %s; run go test -run Golden -update to write it", path)
+	}
+	if err != nil {
+		t.Fatal(err)
+	}
+	if !bytes.Equal(got, want) {
+		t.Errorf("%s has changed; if that's meant, run go test -run Golden -update\ngot:\n%s\nwant:\n%s", name, got, want)
+	}
+}
+
+// goldenKeys stand for the colors in a snapshot, in the order they're
+// listed; any past the last share '*'.
+const goldenKeys = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"
+
+// snapshot is the cells the screen last sent, as text: the glyphs row by
+// row, then the foreground colors row by row, a key for each, with the
+// keys' colors after.
+func snapshot(scr *Screen) []byte {
+	var b bytes.Buffer
+	var colors []Color
+	for _, c := range scr.shown {
+		if c.text != "" && c.text != wideTail && !slices.Contains(colors, c.fg) {
+			colors = append(colors, c.fg)
+		}
+	}
+	slices.SortFunc(colors, func(a, b Color) int {
+		return strings.Compare(colorHex(a), colorHex(b))
+	})
+	for y := range scr.height {
+		for _, c := range scr.shown[y*scr.width : (y+1)*scr.width] {
+			switch c.text {
+			case wideTail:
+			case "":
+				b.WriteByte(' ')
+			default:
+				b.WriteString(c.text)
+			}
+		}
+		b.WriteByte('\n')
+	}
+	b.WriteByte('\n')
+	for y := range scr.height {
+		for _, c := range scr.shown[y*scr.width : (y+1)*scr.width] {
+			switch i := slices.Index(colors, c.fg); {
+			case c.text == "" || c.text == wideTail:
+				b.WriteByte('.')
+			case i < len(goldenKeys):
+				b.WriteByte(goldenKeys[i])
+			default:
+				b.WriteByte('*')
+			}
+		}
+		b.WriteByte('\n')
+	}
+	b.WriteByte('\n')
+	for i, c := range colors {
+		key := byte('*')
+		if i < len(goldenKeys) {
+			key = goldenKeys[i]
+		}
+		fmt.Fprintf(&b, "%c %s\n", key, colorHex(c))
+	}
+	return b.Bytes()
+}
+
+func colorHex(c Color) string {
+	return fmt.Sprintf("#%02x%02x%02x", c.R, c.G, c.B)
+}
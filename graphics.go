@@ -0,0 +1,232 @@
+package main
+
+import (
+	"bytes"
+	"compress/zlib"
+	"encoding/base64"
+	"fmt"
+	"os"
+	"slices"
+	"strconv"
+	"strings"
+	"sync"
+	"sync/atomic"
+)
+
+var graphicsNames = []string{"off", "auto", "sixel", "kitty"}
+
+// Graphics backends plot this many pixels to a cell, and leave scaling
+// them up to the cell's real size to the terminal.
+const graphicsScaleX, graphicsScaleY = 4, 8
+
+type graphicsSupport struct {
+	kitty, sixel bool
+
+	// cellWidth and cellHeight are a cell's size in pixels, 0 if the
+	// terminal didn't say.
+	cellWidth, cellHeight int
+}
+
+// terminalGraphics asks the terminal which graphics protocols it speaks.
+// Like terminalBackground, it only asks once, and the first call has to
+// come before anything else starts reading stdin.
+var terminalGraphics = sync.OnceValue(func() graphicsSupport {
+	var g graphicsSupport
+	if !isTerminal(os.Stdin) || !isTerminal(os.Stdout) {
+		return g
+	}
+	p, err := newProbe()
+	if err != nil {
+		return g
+	}
+	defer p.close()
+	g.kitty = p.kittyGraphics()
+	g.sixel = slices.Contains(p.attributes(), 4)
+	g.cellWidth, g.cellHeight = p.cellSize()
+	return g
+})
+
+// graphicsBackend returns the backend for --graphics, or nil for text cells:
+// when it's off, or auto finds no graphics protocol to use.
+func graphicsBackend(name string) (Backend, error) {
+	switch name {
+	case "off":
+		return nil, nil
+	case "kitty":
+		return newKittyBackend(), nil
+	case "sixel":
+		return newSixelBackend(terminalGraphics()), nil
+	case "auto":
+		switch g := terminalGraphics(); {
+		case g.kitty:
+			return newKittyBackend(), nil
+		case g.sixel:
+			return newSixelBackend(g), nil
+		}
+		return nil, nil
+	}
+	return nil, fmt.Errorf("unknown graphics %q (want %s)", name, strings.Join(graphicsNames, ", "))
+}
+
+// kittyImages numbers the images kitty backends transmit, one per pane.
+var kittyImages atomic.Int32
+
+// kittyBackend sends each frame as an image in the kitty graphics protocol,
+// zlib-compressed RGBA with unlit pixels transparent. It sits below the
+// text, so overlays still show.
+type kittyBackend struct {
+	id   int32
+	rgba []byte
+	z    bytes.Buffer
+	out  []byte
+}
+
+func newKittyBackend() *kittyBackend {
+	return &kittyBackend{id: kittyImages.Add(1)}
+}
+
+func (*kittyBackend) scale() (int, int) { return graphicsScaleX, graphicsScaleY }
+
+// kittyChunk is the most base64 a kitty graphics escape may carry.
+const kittyChunk = 4096
+
+func (b *kittyBackend) render(scr *Screen, r *Raster) {
+	b.rgba = b.rgba[:0]
+	for i, c := range r.pixels {
+		if r.lit[i] {
+			b.rgba = append(b.rgba, c.R, c.G, c.B, 255)
+		} else {
+			b.rgba = append(b.rgba, 0, 0, 0, 0)
+		}
+	}
+	b.z.Reset()
+	zw := zlib.NewWriter(&b.z)
+	zw.Write(b.rgba)
+	zw.Close()
+	data := base64.StdEncoding.EncodeToString(b.z.Bytes())
+
+	// q=2 keeps the terminal from replying, C=1 from moving the cursor.
+	b.out = fmt.Appendf(b.out[:0], "\x1b_Ga=T,f=32,o=z,s=%d,v=%d,c=%d,r=%d,i=%d,q=2,C=1,z=-1,",
+		r.width, r.height, r.width/graphicsScaleX, r.height/graphicsScaleY, b.id)
+	for len(data) > 0 {
+		n := min(len(data), kittyChunk)
+		more := 0
+		if n < len(data) {
+			more = 1
+		}
+		b.out = append(b.out, "m="...)
+		b.out = strconv.AppendInt(b.out, int64(more), 10)
+		b.out = append(b.out, ';')
+		b.out = append(b.out, data[:n]...)
+		b.out = append(b.out, "\x1b\\"...)
+		data = data[n:]
+		if more == 1 {
+			b.out = append(b.out, "\x1b_G"...)
+		}
+	}
+	scr.putImage(b.out, true)
+}
+
+// sixelBackend sends each frame as a sixel image in colors from a 6x6x6
+// cube, each pixel scaled up to fill its share of a cell. Unlit pixels are
+// filled with the terminal's background, wiping the last frame, and the
+// image covers any text, so the cells are drawn again on top.
+type sixelBackend struct {
+	scaleX, scaleY int
+	index          []int
+	out            []byte
+}
+
+func newSixelBackend(g graphicsSupport) *sixelBackend {
+	// Without the cell size, assume the common 8x16.
+	w, h := g.cellWidth, g.cellHeight
+	if w <= 0 || h <= 0 {
+		w, h = 8, 16
+	}
+	return &sixelBackend{scaleX: max(1, w/graphicsScaleX), scaleY: max(1, h/graphicsScaleY)}
+}
+
+func (*sixelBackend) scale() (int, int) { return graphicsScaleX, graphicsScaleY }
+
+// sixelLevel quantizes a color channel to one of the cube's six levels.
+func sixelLevel(v uint8) int {
+	return (int(v)*5 + 127) / 255
+}
+
+func (b *sixelBackend) render(scr *Screen, r *Raster) {
+	// index holds each pixel's color register, -1 for unlit.
+	b.index = slices.Grow(b.index[:0], len(r.pixels))[:len(r.pixels)]
+	var used [216]bool
+	for i, c := range r.pixels {
+		b.index[i] = -1
+		if r.lit[i] {
+			b.index[i] = sixelLevel(c.R)*36 + sixelLevel(c.G)*6 + sixelLevel(c.B)
+			used[b.index[i]] = true
+		}
+	}
+	width, height := r.width*b.scaleX, r.height*b.scaleY
+	b.out = fmt.Appendf(b.out[:0], "\x1bP0;0;0q\"1;1;%d;%d", width, height)
+	for i, ok := range used {
+		if ok {
+			b.out = fmt.Appendf(b.out, "#%d;2;%d;%d;%d", i, i/36*20, i/6%6*20, i%6*20)
+		}
+	}
+	for band := 0; band < height; band += 6 {
+		first := true
+		for color, ok := range used {
+			if !ok || !b.inBand(r, band, height, color) {
+				continue
+			}
+			if !first {
+				b.out = append(b.out, '$')
+			}
+			first = false
+			b.out = fmt.Appendf(b.out, "#%d", color)
+			run, runChar := 0, byte(0)
+			for x := range width {
+				bits := 0
+				for k := range min(6, height-band) {
+					if b.index[(band+k)/b.scaleY*r.width+x/b.scaleX] == color {
+						bits |= 1 << k
+					}
+				}
+				ch := byte(63 + bits)
+				if ch == runChar {
+					run++
+					continue
+				}
+				b.out = appendSixelRun(b.out, run, runChar)
+				run, runChar = 1, ch
+			}
+			b.out = appendSixelRun(b.out, run, runChar)
+		}
+		b.out = append(b.out, '-')
+	}
+	b.out = append(b.out, "\x1b\\"...)
+	scr.putImage(b.out, false)
+}
+
+// inBand reports whether color is used in the six pixel rows from band.
+func (b *sixelBackend) inBand(r *Raster, band, height, color int) bool {
+	for y := band / b.scaleY; y <= min(band+5, height-1)/b.scaleY; y++ {
+		if slices.Contains(b.index[y*r.width:(y+1)*r.width], color) {
+			return true
+		}
+	}
+	return false
+}
+
+func appendSixelRun(out []byte, run int, ch byte) []byte {
+	switch {
+	case run == 0:
+		return out
+	case run > 3:
+		out = append(out, '!')
+		out = strconv.AppendInt(out, int64(run), 10)
+		return append(out, ch)
+	}
+	for range run {
+		out = append(out, ch)
+	}
+	return out
+}
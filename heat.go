@@ -0,0 +1,54 @@
+package main
+
+import "math"
+
+// heatShades draw a heatmap cell, from barely visited to the most visited.
+var heatShades = []string{"░", "▒", "▓", "█"}
+
+// visit counts a stream passing through (x, y), for the heatmap.
+func (sim *Sim) visit(x, y int) {
+	if x < 0 || y < 0 || x >= sim.width || y >= sim.height {
+		return
+	}
+	i := y*sim.width + x
+	if sim.heat[i] == 0 {
+		sim.touched++
+	}
+	sim.heat[i]++
+	sim.hottest = max(sim.hottest, sim.heat[i])
+}
+
+// resetHeat forgets every visit, for a new size.
+func (sim *Sim) resetHeat() {
+	sim.heat = make([]uint32, sim.width*sim.height)
+	sim.hottest = 0
+}
+
+// heatStops runs from a faint primary through the palette's accents to the
+// primary itself.
+func (p *Palette) heatStops() []Color {
+	stops := []Color{p.primary.dim(0.15)}
+	for _, c := range p.colors.choices[1:] {
+		stops = append(stops, c.item)
+	}
+	return append(stops, p.primary)
+}
+
+// drawHeat draws how often streams have visited each cell instead of the
+// streams themselves, on a log scale, so long runs show where the walk
+// tends to go.
+func (sim *Sim) drawHeat(scr *Screen) {
+	if sim.hottest == 0 {
+		return
+	}
+	stops := sim.palette.heatStops()
+	top := math.Log1p(float64(sim.hottest))
+	for i, n := range sim.heat {
+		if n == 0 {
+			continue
+		}
+		t := math.Log1p(float64(n)) / top
+		shade := heatShades[min(len(heatShades)-1, int(t*float64(len(heatShades))))]
+		scr.put(i%sim.width, i/sim.width, shade, gradient(stops, t))
+	}
+}
@@ -0,0 +1,36 @@
+package main
+
+import (
+	"sync/atomic"
+	"time"
+)
+
+// Escalation ramps chaos up towards a peak once no key has been pressed for
+// a while, and drops straight back to calm on the next key press.
+type Escalation struct {
+	after, ramp time.Duration
+	peak        float64
+
+	// lastKey is the time of the latest key press in Unix nanoseconds; it is
+	// written by the input side and read by the sim.
+	lastKey atomic.Int64
+}
+
+func newEscalation(after time.Duration, peak float64) *Escalation {
+	e := &Escalation{after: after, ramp: time.Minute, peak: peak}
+	e.touch()
+	return e
+}
+
+func (e *Escalation) touch() {
+	e.lastKey.Store(time.Now().UnixNano())
+}
+
+func (e *Escalation) modulate(p *Params) {
+	idle := time.Since(time.Unix(0, e.lastKey.Load())) - e.after
+	if idle <= 0 {
+		return
+	}
+	f := min(1, float64(idle)/float64(e.ramp))
+	p.chaos += (e.peak - p.chaos) * f
+}
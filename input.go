@@ -0,0 +1,68 @@
+package main
+
+import (
+	"io"
+	"unicode/utf8"
+)
+
+const (
+	keyCtrlC  = 0x03
+	keyCtrlZ  = 0x1a
+	keyEscape = 0x1b
+
+	// keyFocusIn and keyFocusOut, from the private use area, stand for the
+	// terminal's reports of gaining and losing focus.
+	keyFocusIn  = 0xe000
+	keyFocusOut = 0xe001
+)
+
+// readKeys decodes key presses from a raw-mode terminal and sends one rune
+// per key: printable keys as themselves, control keys as their control code.
+// Escape sequences such as arrow keys are dropped, but for focus reports.
+// It returns when r fails.
+func readKeys(r io.Reader, keys chan<- rune) {
+	buf := make([]byte, 256)
+	for {
+		n, err := r.Read(buf)
+		if err != nil {
+			return
+		}
+		for b := buf[:n]; len(b) > 0; {
+			if b[0] == keyEscape && len(b) > 1 && (b[1] == '[' || b[1] == 'O') {
+				n := skipEscapeSequence(b)
+				switch string(b[:n]) {
+				case "\x1b[I":
+					keys <- keyFocusIn
+				case "\x1b[O":
+					keys <- keyFocusOut
+				}
+				b = b[n:]
+				continue
+			}
+			k, size := utf8.DecodeRune(b)
+			b = b[size:]
+			keys <- k
+		}
+	}
+}
+
+// skipEscapeSequence returns the length of the CSI or SS3 sequence at the
+// start of b: the introducer, any parameters and the final byte.
+func skipEscapeSequence(b []byte) int {
+	for i := 2; i < len(b); i++ {
+		if b[i] >= 0x40 && b[i] <= 0x7e {
+			return i + 1
+		}
+	}
+	return len(b)
+}
+
+func isQuitKey(k rune) bool {
+	return k == 'q' || k == keyCtrlC || k == keyEscape
+}
+
+// quits reports whether k ends a run: a quit key does, and with
+// --exit-on-key any key at all, before it can be taken as a hotkey.
+func quits(k rune, exitOnKey bool) bool {
+	return exitOnKey || isQuitKey(k)
+}
@@ -0,0 +1,20 @@
+package main
+
+import "testing"
+
+// runHotkeys are the keys run takes as hotkeys, rather than passing them
+// on to the mode.
+var runHotkeys = []rune{keyCtrlZ, 'b', '!', 'h', 'p', '+', '=', '-', ']', '[', 't', 'T', 'g', 'S'}
+
+// TestExitOnKey checks that with --exit-on-key every hotkey quits, and
+// that without it none of them do.
+func TestExitOnKey(t *testing.T) {
+	for _, k := range runHotkeys {
+		if !quits(k, true) {
+			t.Errorf("%q doesn't quit with --exit-on-key", k)
+		}
+		if quits(k, false) {
+			t.Errorf("%q quits without --exit-on-key", k)
+		}
+	}
+}
@@ -0,0 +1,113 @@
+package main
+
+import (
+	"bufio"
+	"fmt"
+	"os"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// cpuLoad returns the share of CPU time spent busy since the previous call,
+// from /proc/stat.
+func cpuLoad() loadSource {
+	var lastBusy, lastTotal uint64
+	return func() (float64, error) {
+		f, err := os.Open("/proc/stat")
+		if err != nil {
+			return 0, err
+		}
+		defer f.Close()
+		line, err := bufio.NewReader(f).ReadString('\n')
+		if err != nil {
+			return 0, err
+		}
+		fields := strings.Fields(line)
+		if len(fields) < 5 || fields[0] != "cpu" {
+			return 0, fmt.Errorf("unexpected /proc/stat line %q", line)
+		}
+		var busy, total uint64
+		for i, field := range fields[1:] {
+			n, err := strconv.ParseUint(field, 10, 64)
+			if err != nil {
+				return 0, err
+			}
+			total += n
+			// idle and iowait
+			if i != 3 && i != 4 {
+				busy += n
+			}
+		}
+		dBusy, dTotal := busy-lastBusy, total-lastTotal
+		lastBusy, lastTotal = busy, total
+		if dTotal == 0 {
+			return 0, nil
+		}
+		return float64(dBusy) / float64(dTotal), nil
+	}
+}
+
+// memLoad returns the share of memory in use, from /proc/meminfo.
+func memLoad() (float64, error) {
+	data, err := os.ReadFile("/proc/meminfo")
+	if err != nil {
+		return 0, err
+	}
+	var total, available float64
+	for _, line := range strings.Split(string(data), "\n") {
+		fields := strings.Fields(line)
+		if len(fields) < 2 {
+			continue
+		}
+		n, err := strconv.ParseFloat(fields[1], 64)
+		if err != nil {
+			continue
+		}
+		switch fields[0] {
+		case "MemTotal:":
+			total = n
+		case "MemAvailable:":
+			available = n
+		}
+	}
+	if total == 0 {
+		return 0, fmt.Errorf("no MemTotal in /proc/meminfo")
+	}
+	return 1 - available/total, nil
+}
+
+// netLoad returns network traffic since the previous call, from
+// /proc/net/dev, saturating around a megabyte a second.
+func netLoad() loadSource {
+	var lastBytes uint64
+	var lastTime time.Time
+	return func() (float64, error) {
+		data, err := os.ReadFile("/proc/net/dev")
+		if err != nil {
+			return 0, err
+		}
+		var bytes uint64
+		for _, line := range strings.Split(string(data), "\n") {
+			name, counters, ok := strings.Cut(line, ":")
+			if !ok || strings.TrimSpace(name) == "lo" {
+				continue
+			}
+			fields := strings.Fields(counters)
+			if len(fields) < 9 {
+				continue
+			}
+			rx, _ := strconv.ParseUint(fields[0], 10, 64)
+			tx, _ := strconv.ParseUint(fields[8], 10, 64)
+			bytes += rx + tx
+		}
+		now := time.Now()
+		prevBytes, prevTime := lastBytes, lastTime
+		lastBytes, lastTime = bytes, now
+		if prevTime.IsZero() || bytes < prevBytes {
+			return 0, nil
+		}
+		rate := float64(bytes-prevBytes) / now.Sub(prevTime).Seconds()
+		return saturate(rate, 1<<20), nil
+	}
+}
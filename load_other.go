@@ -0,0 +1,19 @@
+//go:build !linux
+
+package main
+
+import "errors"
+
+var errNoLoad = errors.New("system metrics are only supported on Linux")
+
+func cpuLoad() loadSource {
+	return func() (float64, error) { return 0, errNoLoad }
+}
+
+func memLoad() (float64, error) {
+	return 0, errNoLoad
+}
+
+func netLoad() loadSource {
+	return func() (float64, error) { return 0, errNoLoad }
+}
@@ -0,0 +1,73 @@
+package main
+
+import (
+	"slices"
+	"sync/atomic"
+	"time"
+)
+
+const (
+	keyBackspace = 0x7f
+	keyCtrlH     = 0x08
+
+	lockBurst = 2 * time.Second
+)
+
+// Lock keeps the animation running until its word is typed. It is fed keys
+// by the input side and read by the sim through atomics: typed keys are
+// echoed as glitch glyphs, and a wrong key throws a short chaos burst.
+// This is a toy, not security; signals still end the process.
+type Lock struct {
+	word  []rune
+	typed []rune
+
+	shown   atomic.Int32
+	wrongAt atomic.Int64
+}
+
+func newLock(word string) *Lock {
+	return &Lock{word: []rune(word)}
+}
+
+// feed takes one key press and reports whether the word is now complete.
+func (l *Lock) feed(k rune) bool {
+	switch {
+	case k == keyBackspace || k == keyCtrlH:
+		if len(l.typed) > 0 {
+			l.typed = l.typed[:len(l.typed)-1]
+		}
+	case len(l.typed) < len(l.word) && l.word[len(l.typed)] == k:
+		l.typed = append(l.typed, k)
+	default:
+		l.typed = l.typed[:0]
+		if l.word[0] == k {
+			l.typed = append(l.typed, k)
+		}
+		l.wrongAt.Store(time.Now().UnixNano())
+	}
+	l.shown.Store(int32(len(l.typed)))
+	return slices.Equal(l.typed, l.word)
+}
+
+func (l *Lock) modulate(p *Params) {
+	if time.Since(time.Unix(0, l.wrongAt.Load())) < lockBurst {
+		p.chaos = maxChaos
+	}
+}
+
+func (l *Lock) update(sim *Sim) {}
+
+// draw echoes the typed keys as glyphs in a field near the bottom, blanking
+// the rest of the field so deleted keys disappear.
+func (l *Lock) draw(sim *Sim, scr *Screen) {
+	width := len(l.word) + 2
+	x0, y := (sim.width-width)/2, sim.height-2
+	shown := int(l.shown.Load())
+	for i := range width {
+		if i < shown {
+			scr.put(x0+i, y, clip(sim.charset.pick(sim.rng), 1), sim.palette.primary)
+		} else {
+			scr.put(x0+i, y, " ", sim.palette.primary)
+		}
+	}
+}
@@ -0,0 +1,30 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"log/slog"
+	"os"
+	"strings"
+)
+
+var logLevelNames = []string{"debug", "info", "warn", "error"}
+
+// logger records what happens while the animation runs, for --log-file,
+// since nothing can be printed over it. Until openLog it discards it all.
+var logger = slog.New(slog.NewTextHandler(io.Discard, &slog.HandlerOptions{Level: slog.LevelError + 1}))
+
+// openLog points logger at the file at path, appending, keeping what's at
+// level or above, and returns a function closing it.
+func openLog(path, level string) (func(), error) {
+	var l slog.Level
+	if err := l.UnmarshalText([]byte(level)); err != nil {
+		return nil, fmt.Errorf("unknown log level %q (want one of %s)", level, strings.Join(logLevelNames, ", "))
+	}
+	f, err := os.OpenFile(path, os.O_WRONLY|os.O_CREATE|os.O_APPEND, 0o644)
+	if err != nil {
+		return nil, err
+	}
+	logger = slog.New(slog.NewTextHandler(f, &slog.HandlerOptions{Level: l}))
+	return func() { f.Close() }, nil
+}
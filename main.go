@@ -1,39 +1,77 @@
 package main
 
 import (
+	"flag"
 	"fmt"
-	"math/rand"
-	"os"
-	"os/signal"
-	"strings"
-	"syscall"
-	"time"
+	"io"
 )
 
-var charSet = []string{
-	"A̵̦̦̓͌͗͛̕", "A", "₳", "░A░", "A҉", "Ⱥ", "A̷", "A̲", "A̳", "A̾",
-	"A͎", "A͓̽", "𝔸", "ᴀ", "∀",
+const progName = "AAAAAAAAA"
+
+type command struct {
+	name    string
+	summary string
+	run     func(args []string) error
+
+	// flags builds the command's flag set and args lists the words it takes
+	// as arguments, both for shell completion.
+	flags func() *flag.FlagSet
+	args  []string
 }
 
-func randomString() string {
-	length := rand.Intn(20) + 1
-	var sb strings.Builder
-	for i := 0; i < length; i++ {
-		sb.WriteString(charSet[rand.Intn(len(charSet))])
+// commands lists the subcommands; the first is the default, run when the
+// first argument isn't a command name. It's filled in by init because the
+// completions command reads it.
+var commands []command
+
+func init() {
+	commands = []command{
+		{name: "run", summary: "run the animation (the default)", run: runAnimation,
+			flags: flagSet("run", new(runOptions).register, new(runOptions).registerTerminal)},
+		{name: "export", summary: "render frames without a terminal, as an asciicast or raw ANSI", run: runExport,
+			flags: flagSet("export", new(runOptions).register, new(exportOptions).register)},
+		{name: "motd", summary: "print a static glitch banner for /etc/motd or a shell rc file", run: runMotd,
+			flags: flagSet("motd", new(motdOptions).register)},
+		{name: "config", summary: "show, locate or create the config file", run: runConfig,
+			args: []string{"show", "path", "init"}},
+		{name: "themes", summary: "list the built-in color themes, or preview them in turn", run: runThemes,
+			args: []string{"preview"}},
+		{name: "charsets", summary: "list the built-in charsets", run: runCharsets},
+		{name: "doctor", summary: "check what the terminal supports", run: runDoctor},
+		{name: "bench", summary: "measure how fast frames can be simulated and drawn", run: runBench,
+			flags: flagSet("bench", new(runOptions).register, new(benchOptions).register)},
+		{name: "completions", summary: "print a shell completion script: bash, zsh, fish or powershell", run: runCompletions,
+			args: completionShells},
 	}
-	return sb.String()
 }
 
-func main() {
-	sigs := make(chan os.Signal, 1)
-	signal.Notify(sigs, syscall.SIGINT, syscall.SIGTERM)
+// flagSet returns a builder for a throwaway flag set with the given flags
+// registered, for listing them.
+func flagSet(name string, register ...func(*flag.FlagSet)) func() *flag.FlagSet {
+	return func() *flag.FlagSet {
+		fs := flag.NewFlagSet(name, flag.ContinueOnError)
+		for _, r := range register {
+			r(fs)
+		}
+		return fs
+	}
+}
 
-	go func() {
-		for {
-			fmt.Println(randomString())
-			time.Sleep(100 * time.Millisecond)
+func lookupCommand(name string) *command {
+	for i := range commands {
+		if commands[i].name == name {
+			return &commands[i]
 		}
-	}()
+	}
+	return nil
+}
 
-	<-sigs
+func usage(w io.Writer) {
+	fmt.Fprintf(w, "usage: %s [command] [flags]\n\ncommands:\n", progName)
+	for _, c := range commands {
+		fmt.Fprintf(w, "  %-11s %s\n", c.name, c.summary)
+	}
+	fmt.Fprintf(w, "\nrun '%s <command> -h' for a command's flags\n", progName)
+	fmt.Fprintf(w, "flags can also be set in the config file ('%s config path') or with AAAA_ variables, AAAA_THEME for --theme;\n", progName)
+	fmt.Fprintf(w, "the config file comes first, then a --profile, then the environment, then the command line\n")
 }
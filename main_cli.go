@@ -0,0 +1,26 @@
+//go:build !js || !wasm
+
+package main
+
+import (
+	"fmt"
+	"os"
+)
+
+func main() {
+	cmd, args := &commands[0], os.Args[1:]
+	if len(args) > 0 {
+		switch args[0] {
+		case "help", "-h", "-help", "--help":
+			usage(os.Stdout)
+			return
+		}
+		if c := lookupCommand(args[0]); c != nil {
+			cmd, args = c, args[1:]
+		}
+	}
+	if err := cmd.run(args); err != nil {
+		fmt.Fprintf(os.Stderr, "%s %s: %v\n", progName, cmd.name, err)
+		os.Exit(1)
+	}
+}
@@ -0,0 +1,92 @@
+//go:build js && wasm
+
+package main
+
+import (
+	"errors"
+	"syscall/js"
+)
+
+// In the browser, built with GOOS=js GOARCH=wasm and loaded with Go's
+// wasm_exec.js, the program doesn't run a command but sets a global
+// AAAAAAAAA with a start function, which runs an animation into anything
+// that takes escape sequences, such as an xterm.js terminal:
+//
+//	const anim = AAAAAAAAA.start({
+//		args: ["--theme", "matrix"],
+//		cols: term.cols, rows: term.rows,
+//		write: bytes => term.write(bytes),
+//	});
+//	term.onResize(({cols, rows}) => anim.resize(cols, rows));
+//	anim.stop();
+//
+// start takes run's flags, and returns an Error if they're bad.
+func main() {
+	js.Global().Set(progName, js.ValueOf(map[string]any{
+		"start": js.FuncOf(jsStart),
+	}))
+	select {}
+}
+
+func jsStart(this js.Value, args []js.Value) any {
+	if len(args) == 0 || args[0].Type() != js.TypeObject || args[0].Get("write").Type() != js.TypeFunction {
+		return jsError(errors.New("start needs an object with a write function"))
+	}
+	opts := args[0]
+	var flags []string
+	if a := opts.Get("args"); a.Truthy() {
+		for i := range a.Length() {
+			flags = append(flags, a.Index(i).String())
+		}
+	}
+	sc, err := embeddedScene(flags)
+	if err != nil {
+		return jsError(err)
+	}
+
+	size := &clientSize{width: 80, height: 24}
+	if cols, rows := opts.Get("cols"), opts.Get("rows"); cols.Truthy() && rows.Truthy() {
+		size.set(cols.Int(), rows.Int())
+	}
+	scr := newScreen(jsWriter{opts.Get("write")})
+	scr.enter()
+	commands := make(chan Command, 1)
+	r := &renderer{cols: 1, rows: 1, scr: scr, size: size.get, sims: []*Sim{newSim(sc.SimConfig)}}
+	go func() {
+		r.run(commands)
+		scr.leave()
+	}()
+
+	var stop js.Func
+	resize := js.FuncOf(func(this js.Value, args []js.Value) any {
+		if len(args) == 2 {
+			size.set(args[0].Int(), args[1].Int())
+		}
+		return nil
+	})
+	stop = js.FuncOf(func(this js.Value, args []js.Value) any {
+		select {
+		case commands <- Quit{}:
+		default:
+		}
+		resize.Release()
+		stop.Release()
+		return nil
+	})
+	return js.ValueOf(map[string]any{"resize": resize, "stop": stop})
+}
+
+func jsError(err error) js.Value {
+	return js.Global().Get("Error").New(err.Error())
+}
+
+// jsWriter hands what it's written to a JavaScript function as a
+// Uint8Array.
+type jsWriter struct{ fn js.Value }
+
+func (w jsWriter) Write(p []byte) (int, error) {
+	b := js.Global().Get("Uint8Array").New(len(p))
+	js.CopyBytesToJS(b, p)
+	w.fn.Invoke(b)
+	return len(p), nil
+}
@@ -0,0 +1,237 @@
+package main
+
+// mazeMode carves a maze by recursive backtracking, drawn as it goes, then
+// sends a solver glitching through it from the top left to the bottom
+// right. Once it's there the walls crumble away and a new maze is carved.
+// The n key starts a new one straight away.
+type mazeMode struct {
+	phase mazePhase
+
+	// The maze's rooms are the odd cells of a grid of cols by rows rooms,
+	// with the walls between them on the even ones, drawn with its top
+	// left corner at (x0, y0). open says which grid cells aren't walls,
+	// and walls counts the ones that are.
+	cols, rows int
+	x0, y0     int
+	open       []bool
+	walls      int
+
+	// path is the carver's or the solver's way from the start, in rooms,
+	// and visited the rooms either has been to.
+	path    [][2]int
+	visited []bool
+
+	// ticks counts the ticks in the current phase.
+	ticks int
+}
+
+type mazePhase int
+
+const (
+	mazeCarving mazePhase = iota
+	mazeSolving
+	mazeSolved
+	mazeDecaying
+)
+
+const (
+	// mazeTicks sets the pace: carving or solving takes about this many
+	// ticks at speed 1, whatever the maze's size.
+	mazeTicks = 150
+
+	// mazeHold is how long the solved maze stays up before it decays,
+	// and mazeDecay the chance per tick each wall crumbles.
+	mazeHold  = 30
+	mazeDecay = 0.04
+)
+
+// mazeBoxes are the walls' glyphs, by the walls next to them: up 1, down 2,
+// left 4 and right 8.
+var mazeBoxes = [16]string{
+	"·", "│", "│", "│", "─", "┘", "┐", "┤",
+	"─", "└", "┌", "├", "─", "┴", "┬", "┼",
+}
+
+func (m *mazeMode) init(sim *Sim) {
+	m.cols, m.rows = max(0, (sim.width-1)/2), max(0, (sim.height-1)/2)
+	w, h := 2*m.cols+1, 2*m.rows+1
+	m.x0, m.y0 = (sim.width-w)/2, (sim.height-h)/2
+	m.open = make([]bool, w*h)
+	m.walls = w * h
+	m.visited = make([]bool, m.cols*m.rows)
+	m.path = m.path[:0]
+	m.phase, m.ticks = mazeCarving, 0
+	if m.cols > 0 && m.rows > 0 {
+		m.path = append(m.path, [2]int{0, 0})
+		m.visit([2]int{0, 0})
+		m.carve([2]int{0, 0})
+	}
+}
+
+// grid is the grid cell of room r.
+func grid(r [2]int) (int, int) {
+	return 2*r[0] + 1, 2*r[1] + 1
+}
+
+func (m *mazeMode) gridWidth() int { return 2*m.cols + 1 }
+
+func (m *mazeMode) carve(r [2]int) {
+	x, y := grid(r)
+	m.carveCell(x, y)
+}
+
+func (m *mazeMode) carveCell(x, y int) {
+	if i := y*m.gridWidth() + x; !m.open[i] {
+		m.open[i] = true
+		m.walls--
+	}
+}
+
+func (m *mazeMode) visit(r [2]int) { m.visited[r[1]*m.cols+r[0]] = true }
+
+// next picks a room next to r, at random, that the current phase can go to
+// but hasn't been to: any for the carver, only ones with no wall between
+// for the solver. ok is false if there are none.
+func (m *mazeMode) next(sim *Sim, r [2]int) (next [2]int, ok bool) {
+	var choices [4][2]int
+	n := 0
+	for _, d := range [4][2]int{{0, -1}, {0, 1}, {-1, 0}, {1, 0}} {
+		c := [2]int{r[0] + d[0], r[1] + d[1]}
+		if c[0] < 0 || c[1] < 0 || c[0] >= m.cols || c[1] >= m.rows || m.visited[c[1]*m.cols+c[0]] {
+			continue
+		}
+		x, y := grid(r)
+		if m.phase == mazeSolving && !m.open[(y+d[1])*m.gridWidth()+x+d[0]] {
+			continue
+		}
+		choices[n] = c
+		n++
+	}
+	if n == 0 {
+		return r, false
+	}
+	return choices[sim.rng.Intn(n)], true
+}
+
+func (m *mazeMode) tick(sim *Sim) {
+	if m.cols == 0 || m.rows == 0 {
+		return
+	}
+	m.ticks++
+	steps := max(1, int(sim.speed*float64(2*m.cols*m.rows)/mazeTicks))
+	switch m.phase {
+	case mazeCarving:
+		for range steps {
+			if len(m.path) == 0 {
+				m.phase, m.ticks = mazeSolving, 0
+				clear(m.visited)
+				m.path = append(m.path, [2]int{0, 0})
+				m.visit([2]int{0, 0})
+				break
+			}
+			r := m.path[len(m.path)-1]
+			next, ok := m.next(sim, r)
+			if !ok {
+				m.path = m.path[:len(m.path)-1]
+				continue
+			}
+			x, y := grid(r)
+			nx, ny := grid(next)
+			m.carveCell((x+nx)/2, (y+ny)/2)
+			m.carve(next)
+			m.visit(next)
+			m.path = append(m.path, next)
+		}
+	case mazeSolving:
+		goal := [2]int{m.cols - 1, m.rows - 1}
+		for range steps {
+			r := m.path[len(m.path)-1]
+			if r == goal {
+				m.phase, m.ticks = mazeSolved, 0
+				break
+			}
+			if next, ok := m.next(sim, r); ok {
+				m.visit(next)
+				m.path = append(m.path, next)
+			} else {
+				m.path = m.path[:len(m.path)-1]
+			}
+		}
+	case mazeSolved:
+		if m.ticks >= mazeHold {
+			m.phase, m.ticks = mazeDecaying, 0
+		}
+	case mazeDecaying:
+		w := m.gridWidth()
+		for i, open := range m.open {
+			if !open && sim.rng.Float64() < mazeDecay*sim.chaos {
+				m.carveCell(i%w, i/w)
+			}
+		}
+		if m.walls == 0 || m.ticks > 10*mazeTicks {
+			m.init(sim)
+		}
+	}
+}
+
+func (m *mazeMode) render(sim *Sim, scr *Screen) {
+	if m.cols == 0 || m.rows == 0 {
+		return
+	}
+	w, h := m.gridWidth(), 2*m.rows+1
+	wall := func(x, y int) bool {
+		return x >= 0 && y >= 0 && x < w && y < h && !m.open[y*w+x]
+	}
+	for y := range h {
+		for x := range w {
+			if !wall(x, y) {
+				continue
+			}
+			bits := 0
+			for i, d := range [4][2]int{{0, -1}, {0, 1}, {-1, 0}, {1, 0}} {
+				if wall(x+d[0], y+d[1]) {
+					bits |= 1 << i
+				}
+			}
+			glyph, c := mazeBoxes[bits], sim.palette.primary.dim(0.6)
+			if m.phase == mazeDecaying && sim.rng.Float64() < 0.1 {
+				glyph, c = sim.charset.pick(sim.rng), sim.palette.randomColor(sim.rng)
+			}
+			scr.put(m.x0+x, m.y0+y, glyph, c)
+		}
+	}
+	if m.phase == mazeCarving {
+		if n := len(m.path); n > 0 {
+			x, y := grid(m.path[n-1])
+			scr.put(m.x0+x, m.y0+y, sim.charset.pick(sim.rng), sim.palette.primary)
+		}
+		return
+	}
+	if m.phase == mazeDecaying {
+		return
+	}
+	// The solver's path, through the rooms and the gaps between them,
+	// brightening towards its glitching head.
+	stops := sim.palette.heatStops()
+	for i, r := range m.path {
+		x, y := grid(r)
+		c := gradient(stops, float64(i+1)/float64(len(m.path)))
+		glyph := "•"
+		if i == len(m.path)-1 || sim.rng.Float64() < sim.glitch+0.05 {
+			glyph = sim.charset.pick(sim.rng)
+		}
+		scr.put(m.x0+x, m.y0+y, glyph, c)
+		if i > 0 {
+			px, py := grid(m.path[i-1])
+			scr.put(m.x0+(x+px)/2, m.y0+(y+py)/2, "•", c)
+		}
+	}
+}
+
+func (m *mazeMode) handleInput(sim *Sim, k rune) {
+	if k == 'n' {
+		m.init(sim)
+	}
+}
+
+func (m *mazeMode) onResize(sim *Sim, width, height int) { m.init(sim) }
@@ -0,0 +1,51 @@
+package main
+
+import (
+	"fmt"
+	"slices"
+)
+
+// Mirror is the symmetry --mirror reflects the streams with.
+type Mirror int
+
+const (
+	MirrorOff Mirror = iota
+	MirrorH
+	MirrorV
+	MirrorQuad
+	MirrorKaleido
+)
+
+var mirrorNames = []string{"off", "h", "v", "quad", "kaleido"}
+
+func parseMirror(name string) (Mirror, error) {
+	if i := slices.Index(mirrorNames, name); i >= 0 {
+		return Mirror(i), nil
+	}
+	return MirrorOff, fmt.Errorf("unknown mirror %q (want one of %v)", name, mirrorNames)
+}
+
+// reflections calls visit with every reflection of (x, y) in an area of the
+// given size other than (x, y) itself: across the vertical axis for h, the
+// horizontal one for v, both for quad, and for kaleido the diagonals too,
+// stretched to the area's shape, for eightfold symmetry.
+func (m Mirror) reflections(x, y, width, height int, visit func(x, y int)) {
+	right, bottom := width-1, height-1
+	switch m {
+	case MirrorH:
+		visit(right-x, y)
+	case MirrorV:
+		visit(x, bottom-y)
+	case MirrorQuad, MirrorKaleido:
+		visit(right-x, y)
+		visit(x, bottom-y)
+		visit(right-x, bottom-y)
+		if m == MirrorKaleido {
+			tx, ty := y*width/height, x*height/width
+			visit(tx, ty)
+			visit(right-tx, ty)
+			visit(tx, bottom-ty)
+			visit(right-tx, bottom-ty)
+		}
+	}
+}
@@ -0,0 +1,139 @@
+package main
+
+// Mode is what the sim animates. The sim keeps what every mode shares, the
+// params, random numbers, wash and overlays, and leaves the rest to its
+// mode. A mode with state of its own has to be a pointer.
+type Mode interface {
+	// init readies the mode for a sim it's starting on or taking over.
+	init(sim *Sim)
+
+	// tick advances the mode, once the sim's params for the tick are set.
+	tick(sim *Sim)
+
+	// render draws the mode, under the wash and the overlays.
+	render(sim *Sim, scr *Screen)
+
+	// handleInput takes a key the run loop has no use for itself.
+	handleInput(sim *Sim, k rune)
+
+	// onResize fits the mode to the sim's new size.
+	onResize(sim *Sim, width, height int)
+}
+
+// modes maps --mode names to constructors, which may add overlays to the
+// scene for what the mode draws over it.
+var modes = []struct {
+	name  string
+	build func(o *runOptions, sc *scene) (Mode, error)
+}{
+	{"streams", func(*runOptions, *scene) (Mode, error) {
+		return streamsMode{}, nil
+	}},
+	{"clock", func(o *runOptions, sc *scene) (Mode, error) {
+		clock, err := newClock(o.clockHours)
+		if err != nil {
+			return nil, err
+		}
+		sc.overlays = append(sc.overlays, clock)
+		return streamsMode{}, nil
+	}},
+	{"maze", func(*runOptions, *scene) (Mode, error) {
+		return &mazeMode{}, nil
+	}},
+	{"typer", func(o *runOptions, sc *scene) (Mode, error) {
+		typer, err := newTyperMode(o.file)
+		if err != nil {
+			return nil, err
+		}
+		return typer, nil
+	}},
+	{"ticker", func(o *runOptions, sc *scene) (Mode, error) {
+		ticker, err := newTicker(o.text, o.tickerRow)
+		if err != nil {
+			return nil, err
+		}
+		sc.overlays = append(sc.overlays, ticker)
+		return streamsMode{}, nil
+	}},
+	{"image", func(o *runOptions, sc *scene) (Mode, error) {
+		picture, err := newPicture(o.file, o.imageStyle)
+		if err != nil {
+			return nil, err
+		}
+		sc.overlays = append(sc.overlays, picture)
+		return streamsMode{}, nil
+	}},
+	{"tail", func(o *runOptions, sc *scene) (Mode, error) {
+		tail, err := newTail(o.file)
+		if err != nil {
+			return nil, err
+		}
+		sc.overlays = append(sc.overlays, tail)
+		return streamsMode{}, nil
+	}},
+}
+
+func modeNames() []string {
+	names := make([]string, len(modes))
+	for i, m := range modes {
+		names[i] = m.name
+	}
+	return names
+}
+
+func lookupMode(name string) func(o *runOptions, sc *scene) (Mode, error) {
+	for _, m := range modes {
+		if m.name == name {
+			return m.build
+		}
+	}
+	return nil
+}
+
+// streamsMode is the wandering streams, forking, fading and colliding,
+// that the other modes started out drawing over.
+type streamsMode struct{}
+
+func (streamsMode) init(*Sim) {}
+
+func (streamsMode) tick(sim *Sim) {
+	sim.populate()
+	sim.movement.steer(sim)
+	restitution := 1.0
+	if sim.gravity {
+		sim.applyGravity()
+		restitution = gravityRestitution
+	}
+	for _, s := range sim.streams {
+		x, y := s.x, s.y
+		s.integrate(sim.width, sim.height, sim.speed, restitution)
+		if sim.blocked(s.cell()) {
+			s.x, s.y = x, y
+			s.vx, s.vy = -s.vx, -s.vy
+		}
+		s.record()
+		sim.visit(s.cell())
+		if sim.mutate > 0 && s.word == "" {
+			sim.mutateText(s)
+		}
+	}
+	sim.collide()
+	sim.updateParticles()
+}
+
+func (streamsMode) render(sim *Sim, scr *Screen) {
+	if sim.backend != nil {
+		sim.drawRaster(scr)
+	} else {
+		sim.drawCells(scr)
+	}
+}
+
+func (streamsMode) handleInput(*Sim, rune) {}
+
+func (streamsMode) onResize(sim *Sim, width, height int) {
+	for _, s := range sim.streams {
+		s.x = clampFloat(s.x, 0, float64(width-1))
+		s.y = clampFloat(s.y, 0, float64(height-1))
+	}
+}
@@ -0,0 +1,93 @@
+package main
+
+import (
+	"bufio"
+	"flag"
+	"os"
+	"strings"
+)
+
+type motdOptions struct {
+	width, height int
+	message       string
+	hostname      bool
+	density       float64
+}
+
+func (o *motdOptions) register(fs *flag.FlagSet) {
+	fs.IntVar(&o.width, "width", 80, "banner width in cells")
+	fs.IntVar(&o.height, "height", 12, "banner height in rows")
+	fs.StringVar(&o.message, "message", "AAAAAAAAA", "text across the middle")
+	fs.BoolVar(&o.hostname, "hostname", false, "use the hostname as the message")
+	fs.Float64Var(&o.density, "density", 0.15, "share of cells filled with glyphs")
+}
+
+// runMotd prints one static glitch banner, suitable for /etc/motd or a
+// shell rc file: scattered glyphs with a message across the middle.
+func runMotd(args []string) error {
+	fs := flag.NewFlagSet("motd", flag.ExitOnError)
+	var o motdOptions
+	o.register(fs)
+	fs.Parse(args)
+	if err := checkSize(o.width, o.height); err != nil {
+		return err
+	}
+
+	text := o.message
+	if o.hostname {
+		name, err := os.Hostname()
+		if err != nil {
+			return err
+		}
+		text, _, _ = strings.Cut(name, ".")
+	}
+
+	type cell struct {
+		glyph string
+		color Color
+	}
+	rng := newRand(0)
+	grid := make([][]cell, o.height)
+	for y := range grid {
+		grid[y] = make([]cell, o.width)
+		for x := range grid[y] {
+			if rng.Float64() < o.density {
+				grid[y][x] = cell{clip(defaultCharset.pick(rng), 1), defaultPalette.randomColor(rng).dim(0.3 + rng.Float64()*0.7)}
+			}
+		}
+	}
+
+	rows := renderFont(text)
+	if len(rows[0]) <= o.width && fontHeight <= o.height {
+		block := layoutBlockText(rows, o.width, o.height)
+		for y := range grid {
+			for x := range grid[y] {
+				if block.lit(x, y) {
+					grid[y][x] = cell{clip(defaultCharset.pick(rng), 1), defaultPalette.primary}
+				}
+			}
+		}
+	} else {
+		runes := []rune(text)
+		x0, y := max(0, (o.width-len(runes))/2), o.height/2
+		for i, r := range runes {
+			if x0+i < o.width && y < o.height {
+				grid[y][x0+i] = cell{string(r), defaultPalette.primary}
+			}
+		}
+	}
+
+	w := bufio.NewWriter(os.Stdout)
+	defer w.Flush()
+	for _, row := range grid {
+		for _, c := range row {
+			if c.glyph == "" {
+				w.WriteByte(' ')
+				continue
+			}
+			w.WriteString(c.color.fg() + c.glyph)
+		}
+		w.WriteString("\x1b[0m\n")
+	}
+	return nil
+}
@@ -0,0 +1,129 @@
+package main
+
+import (
+	"fmt"
+	"math"
+)
+
+// Movement steers every stream by accumulating acceleration; the sim then
+// applies any global forces and integrates.
+type Movement interface {
+	steer(sim *Sim)
+}
+
+var movementNames = []string{"walk", "noise", "boids"}
+
+func newMovement(name string) (Movement, error) {
+	switch name {
+	case "walk":
+		return walkMovement{turnChance: 0.2, speed: 1, turnRate: 0.5}, nil
+	case "noise":
+		return &noiseMovement{scale: 0.08, drift: 0.02, speed: 0.8, turnRate: 0.3}, nil
+	case "boids":
+		return boidsMovement{
+			radius:     10,
+			separation: 2.5,
+			weights:    [3]float64{0.08, 0.05, 0.004},
+			minSpeed:   0.3,
+			maxSpeed:   1,
+		}, nil
+	}
+	return nil, fmt.Errorf("unknown movement %q (want one of %v)", name, movementNames)
+}
+
+// walkMovement is the original model: every tick each stream has a fixed
+// chance of picking a new compass direction, which it then turns into.
+type walkMovement struct {
+	turnChance      float64
+	speed, turnRate float64
+}
+
+func (m walkMovement) steer(sim *Sim) {
+	for _, s := range sim.streams {
+		if sim.rng.Float64() < m.turnChance*sim.chaos*s.turns {
+			s.heading = randomHeading(sim.rng)
+		}
+		s.turn(m.speed, m.turnRate)
+	}
+}
+
+// noiseMovement reads each stream's heading from a Perlin flow field that
+// slowly drifts over time, so neighbouring streams swirl together. The
+// field is laid out from the sim's generator the first time it steers, so
+// the same seed makes the same field.
+type noiseMovement struct {
+	field           *perlin
+	scale, drift    float64
+	speed, turnRate float64
+}
+
+func (m *noiseMovement) steer(sim *Sim) {
+	if m.field == nil {
+		m.field = newPerlin(sim.rng)
+	}
+	t := float64(sim.tick) * m.drift
+	for _, s := range sim.streams {
+		s.heading = m.field.noise(s.x*m.scale, s.y*m.scale, t) * 2 * math.Pi
+		s.turn(m.speed, m.turnRate)
+	}
+}
+
+// boidsMovement flocks streams with the classic separation, alignment and
+// cohesion rules applied to their velocities.
+type boidsMovement struct {
+	radius, separation float64
+	minSpeed, maxSpeed float64
+
+	// weights scales the separation, alignment and cohesion forces.
+	weights [3]float64
+}
+
+func (m boidsMovement) steer(sim *Sim) {
+	type force struct{ x, y float64 }
+	forces := make([]force, len(sim.streams))
+	for i, s := range sim.streams {
+		var sep, align, center force
+		neighbours := 0
+		for j, o := range sim.streams {
+			if i == j {
+				continue
+			}
+			dx, dy := s.x-o.x, s.y-o.y
+			dist := math.Hypot(dx, dy)
+			if dist > m.radius {
+				continue
+			}
+			neighbours++
+			if dist < m.separation && dist > 0 {
+				sep.x += dx / (dist * dist)
+				sep.y += dy / (dist * dist)
+			}
+			align.x += o.vx
+			align.y += o.vy
+			center.x += o.x
+			center.y += o.y
+		}
+		if neighbours == 0 {
+			continue
+		}
+		n := float64(neighbours)
+		forces[i] = force{
+			x: sep.x*m.weights[0] + (align.x/n-s.vx)*m.weights[1] + (center.x/n-s.x)*m.weights[2],
+			y: sep.y*m.weights[0] + (align.y/n-s.vy)*m.weights[1] + (center.y/n-s.y)*m.weights[2],
+		}
+	}
+	for i, s := range sim.streams {
+		vx, vy := s.vx+forces[i].x, s.vy+forces[i].y
+		speed := math.Hypot(vx, vy)
+		minSpeed, maxSpeed := m.minSpeed*s.speed, m.maxSpeed*s.speed
+		switch {
+		case speed == 0:
+			vx = minSpeed
+		case speed < minSpeed:
+			vx, vy = vx/speed*minSpeed, vy/speed*minSpeed
+		case speed > maxSpeed:
+			vx, vy = vx/speed*maxSpeed, vy/speed*maxSpeed
+		}
+		s.accelerate(vx-s.vx, vy-s.vy)
+	}
+}
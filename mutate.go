@@ -0,0 +1,28 @@
+package main
+
+import "slices"
+
+// mutateText evolves a stream's string: the first tick gives it one, and
+// after that, with probability mutate each tick, a glyph is inserted,
+// deleted or swapped for another from the stream's glyphs.
+func (sim *Sim) mutateText(s *Stream) {
+	rng := sim.rng
+	if s.text == nil {
+		for range 1 + rng.Intn(maxStringLength) {
+			s.text = append(s.text, s.glyphs.pick(rng))
+		}
+		return
+	}
+	if rng.Float64() >= sim.mutate {
+		return
+	}
+	i := rng.Intn(len(s.text))
+	switch op := rng.Intn(3); {
+	case op == 0 && len(s.text) < maxStringLength:
+		s.text = slices.Insert(s.text, i, s.glyphs.pick(rng))
+	case op == 1 && len(s.text) > 1:
+		s.text = slices.Delete(s.text, i, i+1)
+	default:
+		s.text[i] = s.glyphs.pick(rng)
+	}
+}
@@ -0,0 +1,53 @@
+package main
+
+import (
+	"fmt"
+	"math"
+	"math/rand"
+	"slices"
+)
+
+// Orientation is which way a stream's string runs from its head.
+type Orientation int
+
+const (
+	OrientHorizontal Orientation = iota
+	OrientVertical
+	// OrientTravel runs the string the way the stream is going, at the
+	// nearest of the eight compass directions.
+	OrientTravel
+	// OrientRandom gives each stream one of the others at random.
+	OrientRandom
+)
+
+var orientationNames = []string{"horizontal", "vertical", "travel", "random"}
+
+func parseOrientation(name string) (Orientation, error) {
+	if i := slices.Index(orientationNames, name); i >= 0 {
+		return Orientation(i), nil
+	}
+	return OrientHorizontal, fmt.Errorf("unknown orientation %q (want one of %v)", name, orientationNames)
+}
+
+// pick is the orientation for a new stream.
+func (o Orientation) pick(rng *rand.Rand) Orientation {
+	if o != OrientRandom {
+		return o
+	}
+	return Orientation(rng.Intn(int(OrientRandom)))
+}
+
+// direction is the step from one cell of s's string to the next.
+func (s *Stream) direction() step {
+	switch s.orientation {
+	case OrientVertical:
+		return step{0, 1}
+	case OrientTravel:
+		if speed := math.Hypot(s.vx, s.vy); speed > 0 {
+			if d := (step{int(math.Round(s.vx / speed)), int(math.Round(s.vy / speed))}); d != (step{}) {
+				return d
+			}
+		}
+	}
+	return step{1, 0}
+}
@@ -0,0 +1,90 @@
+package main
+
+// Overlay is updated with the sim every tick and drawn on top of the streams
+// every frame.
+type Overlay interface {
+	update(sim *Sim)
+	draw(sim *Sim, scr *Screen)
+}
+
+// Obstacle is an overlay that streams bounce off rather than cross.
+type Obstacle interface {
+	blocks(x, y int) bool
+}
+
+// blockText is text rendered in the block font and centred on the canvas,
+// with pixels doubled horizontally when there is room.
+type blockText struct {
+	rows          [fontHeight]string
+	x0, y0, scale int
+}
+
+func layoutBlockText(rows [fontHeight]string, width, height int) blockText {
+	scale := 1
+	if 2*len(rows[0]) <= width {
+		scale = 2
+	}
+	return blockText{
+		rows:  rows,
+		x0:    (width - len(rows[0])*scale) / 2,
+		y0:    (height - fontHeight) / 2,
+		scale: scale,
+	}
+}
+
+func (b blockText) lit(x, y int) bool {
+	row, col := y-b.y0, x-b.x0
+	if row < 0 || row >= fontHeight || col < 0 || col >= len(b.rows[row])*b.scale {
+		return false
+	}
+	return b.rows[row][col/b.scale] == '#'
+}
+
+// draw prints every lit pixel with glyph in the palette's primary color,
+// except that with probability corruption a pixel is dropped or replaced by a
+// random glyph in a random color.
+func (b blockText) draw(sim *Sim, scr *Screen, corruption float64, glyph func() string) {
+	for row, line := range b.rows {
+		for col := 0; col < len(line)*b.scale; col++ {
+			if line[col/b.scale] != '#' {
+				continue
+			}
+			x, y := b.x0+col, b.y0+row
+			switch {
+			case sim.rng.Float64() >= corruption:
+				scr.put(x, y, clip(glyph(), 1), sim.palette.primary)
+			case sim.rng.Intn(2) == 0:
+				scr.put(x, y, clip(sim.charset.pick(sim.rng), 1), sim.palette.randomColor(sim.rng))
+			}
+		}
+	}
+}
+
+// Banner shows a message in block letters. Each cycle it is revealed crisp,
+// then corrupts pixel by pixel until it stops being drawn at all, leaving the
+// streams to wipe it away before it is revealed again.
+type Banner struct {
+	rows   [fontHeight]string
+	period int
+}
+
+func newBanner(text string) *Banner {
+	return &Banner{rows: renderFont(text), period: 150}
+}
+
+func (b *Banner) update(sim *Sim) {}
+
+func (b *Banner) draw(sim *Sim, scr *Screen) {
+	text := layoutBlockText(b.rows, sim.width, sim.height)
+	phase := float64(sim.tick%b.period) / float64(b.period)
+	switch {
+	case phase < 0.4:
+		text.draw(sim, scr, 0, bannerPixel)
+	case phase < 0.8:
+		text.draw(sim, scr, (phase-0.4)/0.4, bannerPixel)
+	}
+}
+
+func bannerPixel() string {
+	return "█"
+}
@@ -0,0 +1,178 @@
+package main
+
+import (
+	"fmt"
+	"math"
+	"math/rand"
+	"slices"
+)
+
+type Color struct {
+	R, G, B uint8
+}
+
+func (c Color) fg() string {
+	return fmt.Sprintf("\x1b[38;2;%d;%d;%dm", c.R, c.G, c.B)
+}
+
+// dim scales the color towards black; f is the brightness kept, 0 to 1.
+func (c Color) dim(f float64) Color {
+	return Color{uint8(float64(c.R) * f), uint8(float64(c.G) * f), uint8(float64(c.B) * f)}
+}
+
+// mix blends from c towards other; t is how far, 0 to 1.
+func (c Color) mix(other Color, t float64) Color {
+	return Color{
+		uint8(lerp(t, float64(c.R), float64(other.R))),
+		uint8(lerp(t, float64(c.G), float64(other.G))),
+		uint8(lerp(t, float64(c.B), float64(other.B))),
+	}
+}
+
+// gradient returns the color t of the way along stops, 0 to 1.
+func gradient(stops []Color, t float64) Color {
+	if len(stops) == 1 {
+		return stops[0]
+	}
+	t = clampFloat(t, 0, 1) * float64(len(stops)-1)
+	i := min(int(t), len(stops)-2)
+	return stops[i].mix(stops[i+1], t-float64(i))
+}
+
+// rainbow returns a fully saturated hue; t wraps around the color wheel
+// every 1.
+func rainbow(t float64) Color {
+	t -= math.Floor(t)
+	h := t * 6
+	x := uint8(255 * (1 - math.Abs(math.Mod(h, 2)-1)))
+	switch int(h) {
+	case 0:
+		return Color{255, x, 0}
+	case 1:
+		return Color{x, 255, 0}
+	case 2:
+		return Color{0, 255, x}
+	case 3:
+		return Color{0, x, 255}
+	case 4:
+		return Color{x, 0, 255}
+	}
+	return Color{255, 0, x}
+}
+
+// Weight is how often a choice is picked relative to its siblings.
+type Weight uint16
+
+const (
+	Rare     Weight = 1
+	Uncommon Weight = 3
+	Common   Weight = 6
+)
+
+// Palette is a bright primary color plus weighted accents. The primary is
+// also one of the colors picked at random, as a Common choice.
+type Palette struct {
+	primary Color
+	colors  *Weighted[Color]
+
+	// backgrounds, if set, gives streams a solid block of color behind
+	// their head; a nil choice leaves the background alone.
+	backgrounds *Weighted[*Color]
+
+	// inverse draws burst particles in reverse video.
+	inverse bool
+
+	// wash, if set, is swept down the screen now and then as a band of
+	// background color, wiping what it passes over.
+	wash *Color
+
+	// gradient colors each cell of a stream's head on its own, rather than
+	// the whole head in the primary.
+	gradient Gradient
+
+	// attrs, if set, gives streams text attributes, bold or reverse video
+	// say, for texture.
+	attrs *Weighted[Attr]
+}
+
+// Gradient is how the cells of a stream's head are colored.
+type Gradient int
+
+const (
+	// GradientOff draws every cell in the primary.
+	GradientOff Gradient = iota
+	// GradientFade fades from the primary at the front of the head to the
+	// stream's own color, darkened, at the back.
+	GradientFade
+	// GradientRainbow runs round the color wheel along the head, turning
+	// as it goes.
+	GradientRainbow
+)
+
+var gradientNames = []string{"off", "fade", "rainbow"}
+
+func parseGradient(name string) (Gradient, error) {
+	if i := slices.Index(gradientNames, name); i >= 0 {
+		return Gradient(i), nil
+	}
+	return GradientOff, fmt.Errorf("unknown gradient %q (want theme or one of %v)", name, gradientNames)
+}
+
+func newPalette(primary Color, accents ...Choice[Color]) *Palette {
+	return &Palette{
+		primary: primary,
+		colors:  newWeighted(append([]Choice[Color]{{primary, Common}}, accents...)...),
+	}
+}
+
+var defaultPalette = newPalette(Color{235, 235, 245},
+	Choice[Color]{Color{255, 40, 90}, Common},
+	Choice[Color]{Color{200, 60, 255}, Common},
+	Choice[Color]{Color{60, 220, 255}, Uncommon},
+	Choice[Color]{Color{255, 200, 40}, Uncommon},
+	Choice[Color]{Color{40, 255, 120}, Rare},
+)
+
+// lightPalette is defaultPalette for light backgrounds.
+var lightPalette = newPalette(Color{25, 20, 40},
+	Choice[Color]{Color{200, 0, 60}, Common},
+	Choice[Color]{Color{120, 0, 200}, Common},
+	Choice[Color]{Color{0, 120, 170}, Uncommon},
+	Choice[Color]{Color{180, 110, 0}, Uncommon},
+	Choice[Color]{Color{0, 140, 60}, Rare},
+)
+
+// forLight adapts a palette meant for dark backgrounds to light ones: the
+// primary is inverted, since it's usually close to white, and the accents
+// are darkened until they stand out against white.
+func (p *Palette) forLight() *Palette {
+	primary := Color{255 - p.primary.R, 255 - p.primary.G, 255 - p.primary.B}
+	var accents []Choice[Color]
+	for _, c := range p.colors.choices[1:] {
+		accents = append(accents, Choice[Color]{c.item.dim(0.65), c.weight})
+	}
+	light := newPalette(primary, accents...)
+	light.backgrounds, light.inverse, light.wash, light.gradient, light.attrs = p.backgrounds, p.inverse, p.wash, p.gradient, p.attrs
+	return light
+}
+
+func (p *Palette) randomColor(rng *rand.Rand) Color {
+	return p.colors.pick(rng)
+}
+
+// randomAttrs picks a stream's text attributes, none if the palette has
+// no attrs.
+func (p *Palette) randomAttrs(rng *rand.Rand) Attr {
+	if p.attrs == nil {
+		return 0
+	}
+	return p.attrs.pick(rng)
+}
+
+// randomBackground picks a stream's background, nil for none.
+func (p *Palette) randomBackground(rng *rand.Rand) *Color {
+	if p.backgrounds == nil {
+		return nil
+	}
+	return p.backgrounds.pick(rng)
+}
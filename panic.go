@@ -0,0 +1,42 @@
+package main
+
+// A panic burst, thrown by the ! key, runs flat out for panicTicks, flashing
+// the screen in inverse video every panicFlashEvery ticks, then decays back
+// to normal over panicDecayTicks.
+const (
+	panicTicks      = 30
+	panicDecayTicks = 20
+	panicFlashEvery = 4
+	panicSpeedup    = 3
+)
+
+type panicBurst struct {
+	age int
+}
+
+// level is how far the burst pushes the params, 1 flat out, 0 once it's
+// over.
+func (b *panicBurst) level() float64 {
+	if b.age < panicTicks {
+		return 1
+	}
+	return max(0, 1-float64(b.age-panicTicks)/panicDecayTicks)
+}
+
+func (b *panicBurst) modulate(p *Params) {
+	f := b.level()
+	p.chaos = lerp(f, p.chaos, maxChaos)
+	p.speed = lerp(f, p.speed, min(p.speed*panicSpeedup, maxSpeed))
+}
+
+func (b *panicBurst) update(*Sim) { b.age++ }
+
+func (b *panicBurst) draw(sim *Sim, scr *Screen) {
+	if b.age < panicTicks && b.age%panicFlashEvery == 0 {
+		scr.invert(sim.palette.primary)
+	}
+}
+
+func (b *panicBurst) over() bool {
+	return b.age >= panicTicks+panicDecayTicks
+}
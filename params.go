@@ -0,0 +1,43 @@
+package main
+
+// Params are the knobs that can change while the animation runs. Each tick
+// the sim starts from its base params and lets every modulator adjust them.
+type Params struct {
+	// chaos scales how busy the canvas is: spawning, forking, turning and
+	// the stream cap.
+	chaos float64
+
+	// speed scales how far streams travel per tick.
+	speed float64
+
+	// brightness scales every color drawn, 0 to 1.
+	brightness float64
+}
+
+var defaultParams = Params{chaos: 1, speed: 1, brightness: 1}
+
+// Modulator adjusts the params for the current tick.
+type Modulator interface {
+	modulate(p *Params)
+}
+
+// Boost is a modulator pushed onto the sim for a while. It adjusts the
+// params after the sim's own modulators, and is updated and drawn like an
+// overlay, until it's over.
+type Boost interface {
+	Modulator
+	Overlay
+	over() bool
+}
+
+func (sim *Sim) modulate() {
+	sim.Params = sim.base
+	for _, m := range sim.modulators {
+		m.modulate(&sim.Params)
+	}
+	for _, b := range sim.boosts {
+		b.modulate(&sim.Params)
+	}
+	sim.chaos = clampFloat(sim.chaos, 0, maxChaos)
+	sim.brightness = clampFloat(sim.brightness, 0, 1)
+}
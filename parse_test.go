@@ -0,0 +1,153 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"unicode/utf8"
+)
+
+// FuzzParseConfig feeds the config parser anything at all: it may turn the
+// text down, but mustn't panic, and must say where the trouble is.
+func FuzzParseConfig(f *testing.F) {
+	f.Add("theme = \"fire\"\nspeed = 1.5 # faster\n\n[profile.calm]\nchaos = 0\n")
+	f.Add("[season.spooky]\ndates = \"10-20..10-31\"\n")
+	f.Add("[profile.]\n")
+	f.Add("  = 1\n")
+	f.Add("text = \"unterminated\n")
+	f.Add("\tcharset = \"\\u00e9\" ")
+	f.Add("theme = \"fire\"  # warm\ntext = \"a\" b\n")
+	f.Fuzz(func(t *testing.T, text string) {
+		settings, err := parseConfig(strings.NewReader(text))
+		if err != nil {
+			checkPosition(t, text, err)
+			return
+		}
+		for _, s := range settings {
+			if s.line < 1 || s.column < 1 {
+				t.Errorf("setting %s is at line %d, column %d", s.key, s.line, s.column)
+			}
+		}
+	})
+}
+
+// FuzzParseCharset does the same for charset files.
+func FuzzParseCharset(f *testing.F) {
+	f.Add("A\n₳ rare\n∀ 3\n# a comment\n\nΛ uncommon\n")
+	f.Add("A common extra\n")
+	f.Add("A 0\n")
+	f.Add("  Д  65536")
+	f.Fuzz(func(t *testing.T, text string) {
+		cs, err := parseCharset(strings.NewReader(text))
+		if err != nil {
+			checkPosition(t, text, err)
+			return
+		}
+		if cs.len() == 0 {
+			t.Error("parsed a charset with no glyphs")
+		}
+	})
+}
+
+// FuzzParseTimeline does the same for timelines.
+func FuzzParseTimeline(f *testing.F) {
+	f.Add("loop = true\n\n[[phase]]\nlength = \"30s\"\nchaos = 0.3\n\n[[phase]]\nlength = \"10s\"\ntransition = \"2s\"\ntheme = \"fire\"\n")
+	f.Add("chaos = 1\n")
+	f.Add("[[phase]]\nlength = \"-1s\"\n")
+	f.Add("[[phase]]\nlength = 1s\nnope = 1\n")
+	f.Add("[phase]\n")
+	f.Fuzz(func(t *testing.T, text string) {
+		tl, err := parseTimeline(strings.NewReader(text))
+		if err != nil {
+			checkPosition(t, text, err)
+			return
+		}
+		if len(tl.phases) == 0 {
+			t.Error("parsed a timeline with no phases")
+		}
+	})
+}
+
+// checkPosition fails t if err says it's at a line and column that aren't
+// in text. Errors about the whole file, such as one having no glyphs, don't
+// say where they are.
+func checkPosition(t *testing.T, text string, err error) {
+	t.Helper()
+	if !strings.HasPrefix(err.Error(), "line ") {
+		return
+	}
+	var line, column int
+	if _, scanErr := fmt.Sscanf(err.Error(), "line %d, column %d:", &line, &column); scanErr != nil {
+		t.Fatalf("error %q has no column", err)
+	}
+	lines := strings.Split(text, "\n")
+	if line < 1 || line > len(lines) {
+		t.Fatalf("error %q is past the %d lines", err, len(lines))
+	}
+	if n := utf8.RuneCountInString(strings.TrimSuffix(lines[line-1], "\r")); column < 1 || column > n+1 {
+		t.Fatalf("error %q is past the end of line %d, %d characters long", err, line, n)
+	}
+}
+
+// TestConfigSettingErrors checks that settings the config file parses but
+// the flags turn down say where they are too.
+func TestConfigSettingErrors(t *testing.T) {
+	t.Setenv("HOME", t.TempDir())
+	t.Setenv("XDG_CONFIG_HOME", t.TempDir())
+	path, err := configPath()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		t.Fatal(err)
+	}
+	for _, tc := range []struct {
+		text string
+		args []string
+	}{
+		{"nope = 1\n", nil},
+		{"speed = fast\n", nil},
+		{"chaos = 0\n  bogus =\n", nil},
+		{"[profile.calm]\nspeed = \"\"\n", []string{"--profile", "calm"}},
+	} {
+		if err := os.WriteFile(path, []byte(tc.text), 0o644); err != nil {
+			t.Fatal(err)
+		}
+		err := parseFlags(runFlagSet(), tc.args)
+		if err == nil {
+			t.Errorf("%q: no error", tc.text)
+			continue
+		}
+		msg, ok := strings.CutPrefix(err.Error(), "config ")
+		if !ok || !strings.HasPrefix(msg, "line ") {
+			t.Errorf("%q: error %q doesn't say where it is", tc.text, err)
+			continue
+		}
+		checkPosition(t, tc.text, errors.New(msg))
+	}
+}
+
+// TestParseKeyValue checks values with comments after them.
+func TestParseKeyValue(t *testing.T) {
+	for _, tc := range []struct{ text, value string }{
+		{`theme = "fire"  # warm`, "fire"},
+		{`theme = "fire"#warm`, "fire"},
+		{`text = "a # b" # c`, "a # b"},
+		{`text = "say \"hi\"" # quoted`, `say "hi"`},
+		{`speed = 1.5 # faster`, "1.5"},
+	} {
+		if _, value, _, err := parseKeyValue(tc.text, 1); err != nil || value != tc.value {
+			t.Errorf("%s: got %q, %v; want %q", tc.text, value, err, tc.value)
+		}
+	}
+	for _, text := range []string{`text = "a" b`, `text = "a`, `text = "a" "b"`} {
+		if _, _, _, err := parseKeyValue(text, 1); err == nil {
+			t.Errorf("%s: no error", text)
+		} else {
+			checkPosition(t, text, err)
+		}
+	}
+}
@@ -0,0 +1,87 @@
+package main
+
+import "math"
+
+// Particle is a single short-lived glyph thrown off by an event such as a
+// collision. It flies in a straight line (or falls, under gravity) until its
+// life runs out or it leaves the canvas.
+type Particle struct {
+	x, y   float64
+	vx, vy float64
+	glyph  string
+	color  Color
+	life   int
+
+	// fade, if set, is the life the particle started with, and it dims as
+	// the life runs out.
+	fade int
+}
+
+// deathParticles is how many glyphs a stream sheds when it dies, with
+// --death-bursts.
+const deathParticles = 5
+
+// shade is the particle's color as it is now, faded if it fades.
+func (p *Particle) shade() Color {
+	if p.fade == 0 {
+		return p.color
+	}
+	return p.color.dim(float64(p.life) / float64(p.fade))
+}
+
+// burst throws n particles outwards from (x, y) in random directions.
+func (sim *Sim) burst(x, y float64, n int) {
+	for range n {
+		angle := sim.rng.Float64() * 2 * math.Pi
+		speed := 0.4 + sim.rng.Float64()*1.2
+		vx, vy := headingVector(angle, speed)
+		sim.particles = append(sim.particles, &Particle{
+			x:     x,
+			y:     y,
+			vx:    vx,
+			vy:    vy,
+			glyph: sim.charset.pick(sim.rng),
+			color: sim.palette.randomColor(sim.rng),
+			life:  5 + sim.rng.Intn(10),
+		})
+	}
+}
+
+// shower scatters a dying stream's glyphs from where it was, in its color,
+// slower than a burst and fading out.
+func (sim *Sim) shower(s *Stream) {
+	for range deathParticles {
+		angle := sim.rng.Float64() * 2 * math.Pi
+		speed := 0.2 + sim.rng.Float64()*0.6
+		vx, vy := headingVector(angle, speed)
+		life := 4 + sim.rng.Intn(6)
+		sim.particles = append(sim.particles, &Particle{
+			x:     s.x,
+			y:     s.y,
+			vx:    s.vx/2 + vx,
+			vy:    s.vy/2 + vy,
+			glyph: s.glyphs.pick(sim.rng),
+			color: s.color,
+			life:  life,
+			fade:  life,
+		})
+	}
+}
+
+func (sim *Sim) updateParticles() {
+	alive := sim.particles[:0]
+	for _, p := range sim.particles {
+		if sim.gravity {
+			p.vy += gravityStrength
+		}
+		p.x += p.vx
+		p.y += p.vy
+		p.life--
+		x, y := int(math.Round(p.x)), int(math.Round(p.y))
+		if p.life > 0 && x >= 0 && y >= 0 && x < sim.width && y < sim.height {
+			alive = append(alive, p)
+		}
+	}
+	clear(sim.particles[len(alive):])
+	sim.particles = alive
+}
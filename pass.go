@@ -0,0 +1,115 @@
+package main
+
+import "slices"
+
+// Pass is a post-processing pass over the cells a frame has drawn, run on
+// the window's cells in place.
+type Pass interface {
+	apply(sim *Sim, scr *Screen)
+}
+
+// shiftRows slides height rows from y sideways by dx, wrapping around.
+type shiftRows struct{ y, height, dx int }
+
+func (p shiftRows) apply(sim *Sim, scr *Screen) {
+	for y := p.y; y < p.y+p.height; y++ {
+		row := scr.row(y)
+		if len(row) == 0 {
+			continue
+		}
+		// Rotating right by dx is three reversals.
+		dx := (p.dx%len(row) + len(row)) % len(row)
+		slices.Reverse(row)
+		slices.Reverse(row[:dx])
+		slices.Reverse(row[dx:])
+	}
+}
+
+// block is a rectangle of the window, clipped to it by cells.
+type block struct{ x, y, width, height int }
+
+// cells calls visit with each row of the block's cells.
+func (b block) cells(scr *Screen, visit func(row []cell)) {
+	for y := b.y; y < b.y+b.height; y++ {
+		row := scr.row(y)
+		lo, hi := max(0, b.x), min(len(row), b.x+b.width)
+		if lo < hi {
+			visit(row[lo:hi])
+		}
+	}
+}
+
+// invertBlock swaps the colors of a block's cells.
+type invertBlock struct{ block }
+
+func (p invertBlock) apply(sim *Sim, scr *Screen) {
+	c := sim.palette.primary.dim(scr.brightness)
+	p.cells(scr, func(row []cell) { invertCells(row, c) })
+}
+
+// noiseBlock fills a block with random glyphs in random colors, fresh every
+// frame.
+type noiseBlock struct{ block }
+
+func (p noiseBlock) apply(sim *Sim, scr *Screen) {
+	for y := p.y; y < p.y+p.height; y++ {
+		row := scr.row(y)
+		hi := min(len(row), p.x+p.width)
+		for x := max(0, p.x); x < hi; x++ {
+			c := cell{text: sim.charset.pick(sim.rng), fg: sim.palette.randomColor(sim.rng).dim(scr.brightness)}
+			c.wide = glyphWidth(c.text) == 2 && !scr.ascii
+			if c.wide && x+1 >= hi {
+				// It would spill out of the block.
+				continue
+			}
+			placeCell(row, x, c)
+			if c.wide {
+				x++
+			}
+		}
+	}
+}
+
+// glitchEvent is a brief corruption of the whole screen: a few passes run
+// over every frame for a few ticks, then it recovers.
+type glitchEvent struct {
+	passes []Pass
+	left   int
+}
+
+// newGlitchEvent picks the passes for an event, more and bigger the greater
+// strength, from 0 to 1.
+func newGlitchEvent(sim *Sim, strength float64) *glitchEvent {
+	rng := sim.rng
+	e := &glitchEvent{left: 2 + rng.Intn(4)}
+	randomBlock := func() block {
+		w := max(1, int(float64(sim.width)*strength*rng.Float64()))
+		h := max(1, int(float64(sim.height)*strength*rng.Float64()))
+		return block{rng.Intn(max(1, sim.width-w+1)), rng.Intn(max(1, sim.height-h+1)), w, h}
+	}
+	for range 1 + rng.Intn(1+int(strength*4)) {
+		switch rng.Intn(3) {
+		case 0:
+			h := max(1, int(float64(sim.height)*strength*rng.Float64()/2))
+			dx := int(float64(sim.width) * strength * (rng.Float64() - 0.5))
+			e.passes = append(e.passes, shiftRows{rng.Intn(max(1, sim.height-h+1)), h, dx})
+		case 1:
+			e.passes = append(e.passes, invertBlock{randomBlock()})
+		case 2:
+			e.passes = append(e.passes, noiseBlock{randomBlock()})
+		}
+	}
+	return e
+}
+
+func (e *glitchEvent) modulate(*Params) {}
+
+func (e *glitchEvent) update(*Sim) { e.left-- }
+
+func (e *glitchEvent) draw(sim *Sim, scr *Screen) {
+	for _, p := range e.passes {
+		p.apply(sim, scr)
+	}
+}
+
+func (e *glitchEvent) over() bool { return e.left <= 0 }
@@ -0,0 +1,178 @@
+package main
+
+import (
+	"fmt"
+	"image"
+	"os"
+	"slices"
+
+	// The formats image mode can read.
+	_ "image/gif"
+	_ "image/jpeg"
+	_ "image/png"
+)
+
+var imageStyleNames = []string{"blocks", "glyphs"}
+
+const (
+	// pictureHits is how many times a cell's hit before it's gone, the
+	// first corrupting it.
+	pictureHits = 3
+
+	// pictureDecay is the chance per tick, scaled by chaos, of a random
+	// cell being hit with no stream near it.
+	pictureDecay = 0.5
+
+	// pictureReload is the share of the picture gone when it's loaded
+	// afresh.
+	pictureReload = 0.95
+
+	// pictureDark is the brightness, out of 255, below which the glyphs
+	// style leaves a cell blank.
+	pictureDark = 24
+)
+
+// Picture shows an image over the streams, in half blocks of color or as
+// charset glyphs in the image's colors. Stream heads passing over it
+// corrupt it a cell at a time, and the cells they hit most dissolve, until
+// it's nearly all gone and is loaded again from the file.
+type Picture struct {
+	path   string
+	img    image.Image
+	glyphs bool
+
+	// cells are the picture at the size it was last fitted to, row by
+	// row, and left counts the ones still showing.
+	width, height int
+	cells         []pictureCell
+	left, total   int
+}
+
+type pictureCell struct {
+	text   string
+	fg, bg Color
+	hasBg  bool
+	hits   uint8
+}
+
+func newPicture(path, style string) (*Picture, error) {
+	if path == "" {
+		return nil, fmt.Errorf("image mode needs --file")
+	}
+	if !slices.Contains(imageStyleNames, style) {
+		return nil, fmt.Errorf("unknown image style %q (want one of %v)", style, imageStyleNames)
+	}
+	p := &Picture{path: path, glyphs: style == "glyphs"}
+	if err := p.load(); err != nil {
+		return nil, err
+	}
+	return p, nil
+}
+
+func (p *Picture) load() error {
+	f, err := os.Open(p.path)
+	if err != nil {
+		return err
+	}
+	defer f.Close()
+	img, _, err := image.Decode(f)
+	if err != nil {
+		return fmt.Errorf("%s: %w", p.path, err)
+	}
+	p.img = img
+	return nil
+}
+
+// pixel is the image's color at (x, y), as 8-bit channels.
+func (p *Picture) pixel(x, y int) Color {
+	r, g, b, _ := p.img.At(x, y).RGBA()
+	return Color{uint8(r >> 8), uint8(g >> 8), uint8(b >> 8)}
+}
+
+// fit lays the picture out over width by height cells, as big as fits with
+// its shape kept, centred. Cells are about twice as tall as they're wide,
+// so a half block is square.
+func (p *Picture) fit(sim *Sim, width, height int) {
+	p.width, p.height = width, height
+	p.cells = make([]pictureCell, width*height)
+	p.left = 0
+	bounds := p.img.Bounds()
+	iw, ih := float64(bounds.Dx()), float64(bounds.Dy())
+	if iw == 0 || ih == 0 || width == 0 || height == 0 {
+		return
+	}
+	// scale is image pixels per cell across; down, a cell is two of them.
+	scale := max(iw/float64(width), ih/float64(2*height))
+	cols, rows := int(iw/scale), int(ih/scale/2)
+	x0, y0 := (width-cols)/2, (height-rows)/2
+	at := func(fx, fy float64) Color {
+		return p.pixel(bounds.Min.X+min(int(fx*scale), bounds.Dx()-1), bounds.Min.Y+min(int(fy*scale), bounds.Dy()-1))
+	}
+	for y := range rows {
+		for x := range cols {
+			c := &p.cells[(y0+y)*width+x0+x]
+			if p.glyphs {
+				fg := at(float64(x)+0.5, 2*float64(y)+1)
+				if max(fg.R, fg.G, fg.B) < pictureDark {
+					continue
+				}
+				c.text, c.fg = clip(sim.charset.pick(sim.rng), 1), fg
+			} else {
+				c.text, c.fg, c.bg, c.hasBg = "▀", at(float64(x)+0.5, 2*float64(y)+0.5), at(float64(x)+0.5, 2*float64(y)+1.5), true
+			}
+			p.left++
+		}
+	}
+	p.total = p.left
+}
+
+// hit corrupts cell (x, y) with glyph in color tint, or on its last hit
+// dissolves it.
+func (p *Picture) hit(x, y int, glyph string, tint Color) {
+	if x < 0 || y < 0 || x >= p.width || y >= p.height {
+		return
+	}
+	c := &p.cells[y*p.width+x]
+	if c.text == "" {
+		return
+	}
+	c.hits++
+	if c.hits >= pictureHits {
+		*c = pictureCell{}
+		p.left--
+		return
+	}
+	c.text, c.fg, c.hasBg = clip(glyph, 1), tint, false
+}
+
+func (p *Picture) update(sim *Sim) {
+	if p.width != sim.width || p.height != sim.height {
+		p.fit(sim, sim.width, sim.height)
+	}
+	for _, s := range sim.streams {
+		x, y := s.cell()
+		p.hit(x, y, s.glyphs.pick(sim.rng), s.color)
+	}
+	if len(p.cells) > 0 && sim.rng.Float64() < pictureDecay*sim.chaos {
+		i := sim.rng.Intn(len(p.cells))
+		p.hit(i%p.width, i/p.width, sim.charset.pick(sim.rng), sim.palette.randomColor(sim.rng))
+	}
+	if p.total > 0 && float64(p.left) < (1-pictureReload)*float64(p.total) {
+		// Failing to load again, say if the file's gone, leaves the image
+		// as it was.
+		p.load()
+		p.fit(sim, sim.width, sim.height)
+	}
+}
+
+func (p *Picture) draw(sim *Sim, scr *Screen) {
+	for i, c := range p.cells {
+		switch {
+		case c.text == "":
+		case c.hasBg:
+			scr.putBg(i%p.width, i/p.width, c.text, c.fg, c.bg)
+		default:
+			scr.put(i%p.width, i/p.width, c.text, c.fg)
+		}
+	}
+}
@@ -0,0 +1,150 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"net"
+	"os"
+	"sync/atomic"
+	"time"
+)
+
+// pipeBacklog is how many frames --output-pipe holds for a slow reader
+// before it starts dropping them.
+const pipeBacklog = 16
+
+// outputPipe copies everything written to the terminal to --output-pipe,
+// a named pipe or a Unix socket, from a goroutine of its own so a slow or
+// missing reader never holds up the screen. When it drops a frame, or a
+// new reader turns up, the next frame has to be drawn whole.
+type outputPipe struct {
+	path   string
+	socket bool
+	frames chan []byte
+	done   chan struct{}
+
+	// resync is set when whatever the reader has seen is no use for
+	// drawing the next frame over.
+	resync atomic.Bool
+
+	// writing is set while there's a reader to write to.
+	writing atomic.Bool
+}
+
+func openOutputPipe(path string) (*outputPipe, error) {
+	info, err := os.Stat(path)
+	if err != nil {
+		return nil, err
+	}
+	mode := info.Mode()
+	if mode&(os.ModeNamedPipe|os.ModeSocket) == 0 {
+		return nil, fmt.Errorf("--output-pipe %s isn't a named pipe or a Unix socket", path)
+	}
+	p := &outputPipe{
+		path:   path,
+		socket: mode&os.ModeSocket != 0,
+		frames: make(chan []byte, pipeBacklog),
+		done:   make(chan struct{}),
+	}
+	go p.run()
+	return p, nil
+}
+
+// send queues a copy of b for the reader, reporting false if it had to be
+// dropped.
+func (p *outputPipe) send(b []byte) bool {
+	if p.resync.Swap(false) {
+		return false
+	}
+	select {
+	case p.frames <- append([]byte(nil), b...):
+		return true
+	default:
+		return false
+	}
+}
+
+// run writes the frames to the pipe, opening it again whenever its reader
+// goes away.
+func (p *outputPipe) run() {
+	defer close(p.done)
+	for {
+		w, err := p.open()
+		if err != nil {
+			// The socket may not be listening yet: drop frames for a
+			// second and try again.
+			if !p.idle(time.Second) {
+				return
+			}
+			continue
+		}
+		// Whatever is queued was meant for the last reader, if any.
+		if !p.drain() {
+			w.Close()
+			return
+		}
+		p.resync.Store(true)
+		p.writing.Store(true)
+		for b := range p.frames {
+			if _, err = w.Write(b); err != nil {
+				break
+			}
+		}
+		p.writing.Store(false)
+		w.Close()
+		if err == nil {
+			return
+		}
+	}
+}
+
+// drain drops the frames queued, reporting false if the pipe was closed.
+func (p *outputPipe) drain() bool {
+	for {
+		select {
+		case _, ok := <-p.frames:
+			if !ok {
+				return false
+			}
+		default:
+			return true
+		}
+	}
+}
+
+// idle drops frames for d, reporting false if the pipe was closed
+// meanwhile.
+func (p *outputPipe) idle(d time.Duration) bool {
+	timeout := time.After(d)
+	for {
+		select {
+		case _, ok := <-p.frames:
+			if !ok {
+				return false
+			}
+		case <-timeout:
+			return true
+		}
+	}
+}
+
+func (p *outputPipe) open() (io.WriteCloser, error) {
+	if p.socket {
+		return net.Dial("unix", p.path)
+	}
+	// This waits for a reader to open the other end.
+	return os.OpenFile(p.path, os.O_WRONLY, 0)
+}
+
+// close stops copying, giving the reader, if there is one, a moment to take
+// what's queued.
+func (p *outputPipe) close() {
+	close(p.frames)
+	if !p.writing.Load() {
+		return
+	}
+	select {
+	case <-p.done:
+	case <-time.After(time.Second):
+	}
+}
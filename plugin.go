@@ -0,0 +1,189 @@
+//go:build plugins
+
+package main
+
+import (
+	"fmt"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"unicode"
+)
+
+// Plugins are modes compiled to WASM as WASI commands, dropped into the
+// plugins directory next to the config file, each picked with --mode and
+// the name of its file without the .wasm. They run in wasmtime, with no
+// files, environment or network of the host's to get at, and their only way
+// out is the Mode interface the built-in modes implement, spoken a line at a
+// time over stdin and stdout. A plugin first says
+//
+//	aaaa-plugin 1
+//
+// and then answers each request with ok, or error and a message:
+//
+//	init <width> <height> <seed>
+//	resize <width> <height>
+//	tick <tick> <chaos> <speed>
+//	key <rune>
+//
+// except render, which it answers with a line of <x> <y> <r> <g> <b>
+// <glyph> for each cell it draws, then end.
+const pluginABI = "aaaa-plugin 1"
+
+// pluginsDir is where plugins are looked for.
+func pluginsDir() (string, error) {
+	dir, err := os.UserConfigDir()
+	if err != nil {
+		return "", err
+	}
+	return filepath.Join(dir, progName, "plugins"), nil
+}
+
+// The plugins are found at startup and added to the modes, after the
+// built-in ones, which they can't replace.
+func init() {
+	dir, err := pluginsDir()
+	if err != nil {
+		return
+	}
+	paths, _ := filepath.Glob(filepath.Join(dir, "*.wasm"))
+	for _, path := range paths {
+		name := strings.TrimSuffix(filepath.Base(path), ".wasm")
+		if lookupMode(name) != nil {
+			continue
+		}
+		modes = append(modes, struct {
+			name  string
+			build func(o *runOptions, sc *scene) (Mode, error)
+		}{name, func(*runOptions, *scene) (Mode, error) {
+			return newPluginMode(name, path)
+		}})
+	}
+}
+
+// pluginMode is a mode run by a plugin. If the plugin fails, streams mode
+// takes over.
+type pluginMode struct {
+	*child
+	fallback streamsMode
+}
+
+func newPluginMode(name, path string) (*pluginMode, error) {
+	wasmtime, err := exec.LookPath("wasmtime")
+	if err != nil {
+		return nil, fmt.Errorf("plugin modes need wasmtime on the PATH")
+	}
+	c, err := startChild(name+" plugin", exec.Command(wasmtime, "run", path))
+	if err != nil {
+		return nil, err
+	}
+	line, err := c.readLine()
+	if err != nil {
+		c.close()
+		return nil, err
+	}
+	if line != pluginABI {
+		c.close()
+		return nil, fmt.Errorf("%s: said %q, not %q", path, line, pluginABI)
+	}
+	return &pluginMode{child: c}, nil
+}
+
+// call sends a request that should be answered ok, and fails over to
+// streams mode if it isn't.
+func (m *pluginMode) call(sim *Sim, format string, args ...any) {
+	if m.failed {
+		return
+	}
+	answer, err := m.ask(format, args...)
+	if err == nil && answer != "ok" {
+		err = fmt.Errorf("%s answered %q, want ok", m.name, answer)
+	}
+	if err != nil {
+		m.fail(err)
+		m.fallback.init(sim)
+	}
+}
+
+func (m *pluginMode) init(sim *Sim) {
+	if m.failed {
+		m.fallback.init(sim)
+		return
+	}
+	m.call(sim, "init %d %d %d", sim.width, sim.height, sim.seed)
+}
+
+func (m *pluginMode) tick(sim *Sim) {
+	if m.failed {
+		m.fallback.tick(sim)
+		return
+	}
+	m.call(sim, "tick %d %g %g", sim.tick, sim.chaos, sim.speed)
+}
+
+func (m *pluginMode) render(sim *Sim, scr *Screen) {
+	if m.failed {
+		m.fallback.render(sim, scr)
+		return
+	}
+	if err := m.draw(scr); err != nil {
+		m.fail(err)
+		m.fallback.init(sim)
+	}
+}
+
+// draw puts the cells the plugin answers render with. Control characters
+// in a glyph are put as spaces, so a plugin can't send the terminal escapes
+// of its own.
+func (m *pluginMode) draw(scr *Screen) error {
+	fmt.Fprintln(m.in, "render")
+	if err := m.in.Flush(); err != nil {
+		return err
+	}
+	for {
+		line, err := m.readLine()
+		if err != nil {
+			return err
+		}
+		if line == "end" {
+			return nil
+		}
+		f := strings.SplitN(line, " ", 6)
+		if len(f) != 6 || f[5] == "" {
+			return fmt.Errorf("%s drew %q", m.name, line)
+		}
+		var n [5]int
+		for i := range n {
+			if n[i], err = strconv.Atoi(f[i]); err != nil || i >= 2 && (n[i] < 0 || n[i] > 255) {
+				return fmt.Errorf("%s drew %q", m.name, line)
+			}
+		}
+		scr.put(n[0], n[1], strings.Map(printable, f[5]), Color{uint8(n[2]), uint8(n[3]), uint8(n[4])})
+	}
+}
+
+func (m *pluginMode) handleInput(sim *Sim, k rune) {
+	if m.failed {
+		m.fallback.handleInput(sim, k)
+		return
+	}
+	m.call(sim, "key %d", k)
+}
+
+func (m *pluginMode) onResize(sim *Sim, width, height int) {
+	if m.failed {
+		m.fallback.onResize(sim, width, height)
+		return
+	}
+	m.call(sim, "resize %d %d", width, height)
+}
+
+// printable maps a control character to a space.
+func printable(r rune) rune {
+	if unicode.IsControl(r) {
+		return ' '
+	}
+	return r
+}
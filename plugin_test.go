@@ -0,0 +1,33 @@
+//go:build plugins
+
+package main
+
+import (
+	"bufio"
+	"io"
+	"strings"
+	"testing"
+	"unicode"
+)
+
+// TestPluginGlyphs checks that a plugin can't get escapes to the terminal
+// through the glyphs it draws.
+func TestPluginGlyphs(t *testing.T) {
+	answer := "0 0 255 255 255 \x1b]52;c;aGVsbG8=\a\n1 0 255 255 255 \x9b2J\nend\n"
+	m := &pluginMode{child: &child{
+		name:    "test plugin",
+		in:      bufio.NewWriter(io.Discard),
+		out:     bufio.NewReader(strings.NewReader(answer)),
+		timeout: childTimeout,
+	}}
+	scr := newScreen(io.Discard)
+	scr.resize(40, 2)
+	if err := m.draw(scr); err != nil {
+		t.Fatal(err)
+	}
+	for _, c := range scr.cells {
+		if strings.ContainsFunc(c.text, unicode.IsControl) {
+			t.Errorf("put %q", c.text)
+		}
+	}
+}
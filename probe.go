@@ -0,0 +1,126 @@
+package main
+
+import (
+	"os"
+	"regexp"
+	"strconv"
+	"time"
+)
+
+const probeTimeout = 500 * time.Millisecond
+
+// deviceAttributes matches the terminal's reply to a primary device
+// attributes request (DA1), which every terminal answers.
+var deviceAttributes = regexp.MustCompile(`\x1b\[\?([\d;]*)c`)
+
+// Probe asks the terminal questions by writing queries to it and reading the
+// replies that come back on stdin, in raw mode so they aren't echoed. Reads
+// time out rather than block, so nothing is left reading stdin afterwards.
+type Probe struct {
+	restore func()
+}
+
+func newProbe() (*Probe, error) {
+	restore, err := makeRawTimed(os.Stdin, 1)
+	if err != nil {
+		return nil, err
+	}
+	return &Probe{restore: restore}, nil
+}
+
+func (p *Probe) close() {
+	p.restore()
+}
+
+// query writes q followed by a DA1 request and returns everything the
+// terminal sent back up to the DA1 reply. A terminal that doesn't understand
+// q just answers DA1, so unsupported queries come back quickly rather than
+// timing out.
+func (p *Probe) query(q string) string {
+	os.Stdout.WriteString(q + "\x1b[c")
+	var reply []byte
+	buf := make([]byte, 256)
+	deadline := time.Now().Add(probeTimeout)
+	for time.Now().Before(deadline) {
+		// A timed-out read comes back empty, as io.EOF.
+		n, _ := os.Stdin.Read(buf)
+		reply = append(reply, buf[:n]...)
+		if deviceAttributes.Match(reply) {
+			break
+		}
+	}
+	return string(reply)
+}
+
+// attributes returns the DA1 parameters: 4 means sixel graphics, for one.
+func (p *Probe) attributes() []int {
+	m := deviceAttributes.FindStringSubmatch(p.query(""))
+	if m == nil {
+		return nil
+	}
+	var attrs []int
+	for _, s := range regexp.MustCompile(`\d+`).FindAllString(m[1], -1) {
+		n, _ := strconv.Atoi(s)
+		attrs = append(attrs, n)
+	}
+	return attrs
+}
+
+var cursorReport = regexp.MustCompile(`\x1b\[(\d+);(\d+)R`)
+
+// width prints s at the start of the current line and reports how many
+// cells the terminal advanced the cursor by, or -1 if it didn't say.
+func (p *Probe) width(s string) int {
+	m := cursorReport.FindStringSubmatch(p.query("\r\x1b[K" + s + "\x1b[6n"))
+	os.Stdout.WriteString("\r\x1b[K")
+	if m == nil {
+		return -1
+	}
+	col, _ := strconv.Atoi(m[2])
+	return col - 1
+}
+
+// mode asks whether a private mode is known (DECRQM), such as 1006 for SGR
+// mouse reporting.
+func (p *Probe) mode(n int) bool {
+	m := regexp.MustCompile(`\x1b\[\?` + strconv.Itoa(n) + `;(\d)\$y`).FindStringSubmatch(p.query("\x1b[?" + strconv.Itoa(n) + "$p"))
+	return m != nil && m[1] != "0"
+}
+
+// kittyGraphics asks whether the kitty graphics protocol is supported, with
+// a query that never draws anything, passed through tmux to the terminal
+// outside.
+func (p *Probe) kittyGraphics() bool {
+	q := "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\"
+	if inTmux() {
+		q = string(appendPassthrough(nil, []byte(q)))
+	}
+	return regexp.MustCompile(`\x1b_Gi=31;OK`).MatchString(p.query(q))
+}
+
+// cellSize asks for the size of a cell in pixels, returning zeros if the
+// terminal didn't say.
+func (p *Probe) cellSize() (width, height int) {
+	m := regexp.MustCompile(`\x1b\[6;(\d+);(\d+)t`).FindStringSubmatch(p.query("\x1b[16t"))
+	if m == nil {
+		return 0, 0
+	}
+	height, _ = strconv.Atoi(m[1])
+	width, _ = strconv.Atoi(m[2])
+	return width, height
+}
+
+// backgroundColor asks for the terminal's background color (OSC 11) and
+// returns its luminance from 0 to 1, or -1 if there was no answer.
+func (p *Probe) backgroundColor() float64 {
+	m := regexp.MustCompile(`\x1b\]11;rgb:([0-9a-fA-F]+)/([0-9a-fA-F]+)/([0-9a-fA-F]+)`).FindStringSubmatch(p.query("\x1b]11;?\x1b\\"))
+	if m == nil {
+		return -1
+	}
+	var rgb [3]float64
+	for i, hex := range m[1:] {
+		n, _ := strconv.ParseUint(hex, 16, 64)
+		rgb[i] = float64(n) / float64(uint64(1)<<(4*len(hex))-1)
+	}
+	return 0.2126*rgb[0] + 0.7152*rgb[1] + 0.0722*rgb[2]
+}
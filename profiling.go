@@ -0,0 +1,49 @@
+//go:build profiling
+
+package main
+
+import (
+	"context"
+	"os"
+	"runtime/pprof"
+	"runtime/trace"
+)
+
+// stage marks the start of a stage of a frame, as a region of the runtime
+// trace, returning the function that marks its end.
+func stage(name string) func() {
+	return trace.StartRegion(context.Background(), name).End
+}
+
+// startTrace writes a runtime trace to path, for go tool trace to show the
+// spans of every frame, and a CPU profile beside it at path.pprof, for go
+// tool pprof -http to draw as a flame graph. It returns the function
+// finishing both.
+func startTrace(path string) (func(), error) {
+	tf, err := os.Create(path)
+	if err != nil {
+		return nil, err
+	}
+	pf, err := os.Create(path + ".pprof")
+	if err != nil {
+		tf.Close()
+		return nil, err
+	}
+	if err := trace.Start(tf); err != nil {
+		tf.Close()
+		pf.Close()
+		return nil, err
+	}
+	if err := pprof.StartCPUProfile(pf); err != nil {
+		trace.Stop()
+		tf.Close()
+		pf.Close()
+		return nil, err
+	}
+	return func() {
+		pprof.StopCPUProfile()
+		trace.Stop()
+		pf.Close()
+		tf.Close()
+	}, nil
+}
@@ -0,0 +1,15 @@
+//go:build !profiling
+
+package main
+
+import "errors"
+
+// stage marks the start of a stage of a frame, returning the function that
+// marks its end. Built without the profiling tag it does nothing.
+func stage(name string) func() { return endStage }
+
+func endStage() {}
+
+func startTrace(path string) (func(), error) {
+	return nil, errors.New("built without profiling support; rebuild with -tags profiling")
+}
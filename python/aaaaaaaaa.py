@@ -0,0 +1,109 @@
+"""Python bindings for AAAAAAAAA, over its C library.
+
+Build the library with
+
+    go build -tags ffi -buildmode=c-shared -o libaaaaaaaaa.so
+
+and put it next to this file, or name it with $AAAAAAAAA_LIBRARY. Then
+
+    import aaaaaaaaa
+
+    with aaaaaaaaa.Engine("--theme", "matrix", width=80, height=24) as e:
+        while True:
+            e.tick()
+            for x, y, text, rgb in e.frame():
+                ...
+
+where the arguments are run's flags, and each tick is a frame's worth of
+time, a tenth of a second normally.
+"""
+
+import ctypes
+import os
+import sys
+
+
+class _Cell(ctypes.Structure):
+    _fields_ = [
+        ("x", ctypes.c_int32),
+        ("y", ctypes.c_int32),
+        ("text", ctypes.c_char_p),
+        ("fg_r", ctypes.c_uint8),
+        ("fg_g", ctypes.c_uint8),
+        ("fg_b", ctypes.c_uint8),
+        ("has_bg", ctypes.c_uint8),
+        ("bg_r", ctypes.c_uint8),
+        ("bg_g", ctypes.c_uint8),
+        ("bg_b", ctypes.c_uint8),
+    ]
+
+
+def _load():
+    path = os.environ.get("AAAAAAAAA_LIBRARY")
+    if not path:
+        name = {"darwin": "libaaaaaaaaa.dylib", "win32": "aaaaaaaaa.dll"}.get(sys.platform, "libaaaaaaaaa.so")
+        path = os.path.join(os.path.dirname(os.path.abspath(__file__)), name)
+    lib = ctypes.CDLL(path)
+    lib.aaaa_engine_new.argtypes = [ctypes.c_int, ctypes.POINTER(ctypes.c_char_p), ctypes.c_int, ctypes.c_int]
+    lib.aaaa_engine_new.restype = ctypes.c_size_t
+    lib.aaaa_engine_resize.argtypes = [ctypes.c_size_t, ctypes.c_int, ctypes.c_int]
+    lib.aaaa_engine_tick.argtypes = [ctypes.c_size_t]
+    lib.aaaa_engine_cells.argtypes = [ctypes.c_size_t, ctypes.POINTER(ctypes.c_int)]
+    lib.aaaa_engine_cells.restype = ctypes.POINTER(_Cell)
+    lib.aaaa_engine_free.argtypes = [ctypes.c_size_t]
+    return lib
+
+
+_lib = None
+
+
+class Engine:
+    """An animation, width by height cells, set up by run's flags."""
+
+    def __init__(self, *args, width=80, height=24):
+        global _lib
+        self._handle = 0
+        if _lib is None:
+            _lib = _load()
+        self.width, self.height = width, height
+        self.configure(*args)
+
+    def configure(self, *args):
+        """Start again with new flags."""
+        argv = (ctypes.c_char_p * len(args))(*(a.encode() for a in args))
+        handle = _lib.aaaa_engine_new(len(args), argv, self.width, self.height)
+        if not handle:
+            raise ValueError("bad options: %s" % " ".join(args))
+        self.close()
+        self._handle = handle
+
+    def resize(self, width, height):
+        self.width, self.height = width, height
+        _lib.aaaa_engine_resize(self._handle, width, height)
+
+    def tick(self):
+        """Advance the animation a frame."""
+        _lib.aaaa_engine_tick(self._handle)
+
+    def frame(self):
+        """The lit cells of the last tick, as (x, y, text, (r, g, b))."""
+        n = ctypes.c_int()
+        cells = _lib.aaaa_engine_cells(self._handle, ctypes.byref(n))
+        return [
+            (c.x, c.y, c.text.decode(), (c.fg_r, c.fg_g, c.fg_b))
+            for c in cells[: n.value]
+        ]
+
+    def close(self):
+        if self._handle:
+            _lib.aaaa_engine_free(self._handle)
+            self._handle = 0
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *exc):
+        self.close()
+
+    def __del__(self):
+        self.close()
@@ -0,0 +1,137 @@
+package main
+
+// Raster is a grid of pixels, finer than the screen's cells, that the sim
+// plots streams and particles into when a backend other than plain cells is
+// in use. Unlit pixels are left as they are on screen.
+type Raster struct {
+	width, height int
+	pixels        []Color
+	lit           []bool
+}
+
+// reset sizes the raster and unlights every pixel.
+func (r *Raster) reset(width, height int) {
+	if width*height != len(r.pixels) {
+		r.pixels = make([]Color, width*height)
+		r.lit = make([]bool, width*height)
+	} else {
+		clear(r.lit)
+	}
+	r.width, r.height = width, height
+}
+
+func (r *Raster) plot(x, y int, c Color) {
+	if x < 0 || y < 0 || x >= r.width || y >= r.height {
+		return
+	}
+	r.pixels[y*r.width+x] = c
+	r.lit[y*r.width+x] = true
+}
+
+func (r *Raster) at(x, y int) (Color, bool) {
+	return r.pixels[y*r.width+x], r.lit[y*r.width+x]
+}
+
+// line plots a straight line from (x0, y0) to (x1, y1).
+func (r *Raster) line(x0, y0, x1, y1 int, c Color) {
+	steps := max(abs(x1-x0), abs(y1-y0))
+	for i := range steps + 1 {
+		t := 0.0
+		if steps > 0 {
+			t = float64(i) / float64(steps)
+		}
+		r.plot(x0+int(float64(x1-x0)*t+0.5), y0+int(float64(y1-y0)*t+0.5), c)
+	}
+}
+
+func abs(n int) int {
+	return max(n, -n)
+}
+
+// Backend turns a raster into cells on the screen, scaleX by scaleY pixels
+// to a cell.
+type Backend interface {
+	scale() (scaleX, scaleY int)
+	render(scr *Screen, r *Raster)
+}
+
+// brailleBackend draws each cell's 2x4 pixels as the dots of a braille
+// character, in the color most of them share.
+type brailleBackend struct{}
+
+// brailleDots is the bit each pixel of a cell sets in a braille character,
+// by row and column.
+var brailleDots = [4][2]int{{0x01, 0x08}, {0x02, 0x10}, {0x04, 0x20}, {0x40, 0x80}}
+
+// brailleGlyphs holds every braille character by its dots, so rendering
+// doesn't build strings.
+var brailleGlyphs = func() (glyphs [256]string) {
+	for i := range glyphs {
+		glyphs[i] = string(rune(0x2800 + i))
+	}
+	return glyphs
+}()
+
+func (brailleBackend) scale() (int, int) { return 2, 4 }
+
+func (brailleBackend) render(scr *Screen, r *Raster) {
+	var colors [8]Color
+	for cy := range r.height / 4 {
+		for cx := range r.width / 2 {
+			dots, n := 0, 0
+			for dy := range 4 {
+				for dx := range 2 {
+					if c, ok := r.at(cx*2+dx, cy*4+dy); ok {
+						dots |= brailleDots[dy][dx]
+						colors[n] = c
+						n++
+					}
+				}
+			}
+			if dots != 0 {
+				scr.put(cx, cy, brailleGlyphs[dots], dominant(colors[:n]))
+			}
+		}
+	}
+}
+
+// halfBlockBackend draws each cell's two pixels, one above the other, as a
+// half block: the top pixel in its foreground, the bottom in its background.
+type halfBlockBackend struct{}
+
+func (halfBlockBackend) scale() (int, int) { return 1, 2 }
+
+func (halfBlockBackend) render(scr *Screen, r *Raster) {
+	for cy := range r.height / 2 {
+		for x := range r.width {
+			top, topLit := r.at(x, cy*2)
+			bottom, bottomLit := r.at(x, cy*2+1)
+			switch {
+			case topLit && bottomLit:
+				scr.putBg(x, cy, "▀", top, bottom)
+			case topLit:
+				scr.put(x, cy, "▀", top)
+			case bottomLit:
+				scr.put(x, cy, "▄", bottom)
+			}
+		}
+	}
+}
+
+// dominant returns the color that appears most often in colors, the first
+// of them on a tie.
+func dominant(colors []Color) Color {
+	best, bestCount := colors[0], 0
+	for i, c := range colors {
+		count := 0
+		for _, other := range colors[i:] {
+			if other == c {
+				count++
+			}
+		}
+		if count > bestCount {
+			best, bestCount = c, count
+		}
+	}
+	return best
+}
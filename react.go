@@ -0,0 +1,57 @@
+package main
+
+import (
+	"fmt"
+	"math"
+	"time"
+)
+
+// loadSource reports a system metric as a load between 0 and 1.
+type loadSource func() (float64, error)
+
+// Reactor drives chaos, speed and brightness from a live system metric,
+// sampled once a second and eased between samples.
+type Reactor struct {
+	source  loadSource
+	target  float64
+	level   float64
+	sampled time.Time
+}
+
+func newReactor(metric string) (Modulator, error) {
+	var source loadSource
+	switch metric {
+	case "audio":
+		return newAudioReactor()
+	case "cpu":
+		source = cpuLoad()
+	case "mem":
+		source = memLoad
+	case "net":
+		source = netLoad()
+	default:
+		return nil, fmt.Errorf("unknown metric %q (want cpu, mem, net or audio)", metric)
+	}
+	if _, err := source(); err != nil {
+		return nil, fmt.Errorf("can't read %s load: %w", metric, err)
+	}
+	return &Reactor{source: source}, nil
+}
+
+func (r *Reactor) modulate(p *Params) {
+	if time.Since(r.sampled) >= time.Second {
+		if load, err := r.source(); err == nil {
+			r.target = load
+		}
+		r.sampled = time.Now()
+	}
+	r.level += (r.target - r.level) * 0.1
+	p.chaos *= 0.3 + 2.7*r.level
+	p.speed *= 0.5 + r.level
+	p.brightness *= 0.4 + 0.6*r.level
+}
+
+// saturate maps a rate onto 0 to 1, reaching about 0.63 at scale.
+func saturate(rate, scale float64) float64 {
+	return 1 - math.Exp(-rate/scale)
+}
@@ -0,0 +1,56 @@
+package main
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+)
+
+// Region is the rectangle of the terminal the animation is confined to by
+// --region, each side in cells or as a percentage of the terminal's size.
+type Region struct {
+	x, y, width, height extent
+}
+
+type extent struct {
+	n       int
+	percent bool
+}
+
+func (e extent) of(size int) int {
+	if e.percent {
+		return size * e.n / 100
+	}
+	return e.n
+}
+
+// parseRegion reads x,y,w,h, such as 0,0,40,10 or 50%,0,50%,100%.
+func parseRegion(s string) (*Region, error) {
+	parts := strings.Split(s, ",")
+	if len(parts) != 4 {
+		return nil, fmt.Errorf("bad region %q (want x,y,w,h, each in cells or a percentage)", s)
+	}
+	var e [4]extent
+	for i, part := range parts {
+		num, percent := strings.CutSuffix(strings.TrimSpace(part), "%")
+		n, err := strconv.Atoi(num)
+		if err != nil || n < 0 || percent && n > 100 {
+			return nil, fmt.Errorf("bad region %q: %q isn't a number of cells or a percentage", s, part)
+		}
+		e[i] = extent{n, percent}
+	}
+	if e[2].n == 0 || e[3].n == 0 {
+		return nil, fmt.Errorf("bad region %q: it needs a width and a height", s)
+	}
+	return &Region{e[0], e[1], e[2], e[3]}, nil
+}
+
+// rect places the region on a terminal of the given size, cut down to fit
+// but always at least a cell.
+func (r *Region) rect(width, height int) (x, y, w, h int) {
+	x = max(0, min(r.x.of(width), width-1))
+	y = max(0, min(r.y.of(height), height-1))
+	w = max(1, min(r.width.of(width), width-x))
+	h = max(1, min(r.height.of(height), height-y))
+	return x, y, w, h
+}
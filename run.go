@@ -0,0 +1,976 @@
+package main
+
+import (
+	"flag"
+	"fmt"
+	"io"
+	"math/rand"
+	"os"
+	"os/signal"
+	"slices"
+	"strings"
+	"syscall"
+	"time"
+	"unicode"
+)
+
+// minSpeed and maxSpeed bound the speed keys.
+const (
+	minSpeed = 0.1
+	maxSpeed = 10
+)
+
+// runOptions holds the flags shared by every command that runs the
+// animation: run itself, plus export and bench, which drive it headless.
+type runOptions struct {
+	movement      string
+	script        string
+	gravity       bool
+	collisions    string
+	maxStreams    int
+	density       float64
+	lifetime      time.Duration
+	chaos         float64
+	speed         float64
+	zalgo         int
+	profile       string
+	theme         string
+	background    string
+	circadian     string
+	gradient      string
+	seasonal      bool
+	charset       string
+	words         string
+	glitch        float64
+	mutate        float64
+	linkChance    float64
+	evolve        bool
+	deathBursts   bool
+	trails        string
+	orientation   string
+	banner        string
+	mode          string
+	file          string
+	clockHours    int
+	text          string
+	tickerRow     int
+	imageStyle    string
+	timer         time.Duration
+	bell          bool
+	react         string
+	escalateAfter time.Duration
+	escalatePeak  float64
+	envelope      string
+	glitchEvents  float64
+	glitchPower   float64
+	fx            string
+	seed          int64
+	persist       bool
+	mirror        string
+	braille       bool
+	pixels        bool
+	graphics      string
+
+	// The rest only make sense with a terminal.
+	duration    time.Duration
+	exitOnKey   bool
+	lock        string
+	noClear     bool
+	attrs       string
+	ascii       string
+	linkURL     string
+	daemon      bool
+	tmuxPopup   bool
+	titleAnim   bool
+	stats       string
+	trace       string
+	logFile     string
+	logLevel    string
+	idle        time.Duration
+	maxCPU      string
+	eco         bool
+	filter      bool
+	noWizard    bool
+	singleLoop  bool
+	region      string
+	split       string
+	splitThemes string
+	splitModes  string
+	serveTelnet string
+	serveWeb    string
+	syncHost    string
+	syncJoin    string
+	outputPipe  string
+	timeline    string
+}
+
+func (o *runOptions) register(fs *flag.FlagSet) {
+	fs.StringVar(&o.movement, "movement", "walk", "stream movement model: walk, noise or boids")
+	fs.StringVar(&o.script, "script", "", "Lua script whose on_tick(engine) and on_spawn(stream) steer the streams and whose cell(x, y, t) recolors each cell (needs -tags lua and lua on the PATH)")
+	fs.BoolVar(&o.gravity, "gravity", false, "pull streams downwards so they arc, fall and bounce")
+	fs.StringVar(&o.collisions, "collisions", "off", "what streams do when they meet: merge, bounce, explode or off")
+	fs.IntVar(&o.maxStreams, "max-streams", 0, "maximum number of streams alive at once; 0 to scale it with the terminal's size by --density")
+	fs.Float64Var(&o.density, "density", 10, "streams per 1000 cells of terminal, unless --max-streams is set")
+	fs.DurationVar(&o.lifetime, "lifetime", 10*time.Second, "average time a stream lives before fading out")
+	fs.Float64Var(&o.chaos, "chaos", 1, "how busy the screen is: spawning, forking and turning, from 0 to 5")
+	fs.Float64Var(&o.speed, "speed", 1, "how fast streams move, as a multiple of normal")
+	fs.IntVar(&o.zalgo, "zalgo", 0, "attach random combining marks to glyphs, from 0 (none) to 10")
+	fs.StringVar(&o.profile, "profile", "", "apply a [profile.name] table from the config file")
+	fs.StringVar(&o.theme, "theme", "glitch", "color theme (see the themes command)")
+	fs.StringVar(&o.background, "background", "auto", "terminal background the theme has to suit: auto, dark or light")
+	fs.StringVar(&o.circadian, "circadian", "", "drift the colors with the local time between a day and a night theme, such as ice:amber, in place of --theme")
+	fs.StringVar(&o.gradient, "gradient", "theme", "color each cell of a stream's head on its own: fade (bright to dark), rainbow, off, or theme for whatever the theme does")
+	fs.BoolVar(&o.seasonal, "seasonal", false, "dress up for the time of year, with the theme and charset of the season it is (see [season.name] in the config file)")
+	fs.StringVar(&o.charset, "charset", "default", "built-in charset (see the charsets command) or a file of glyphs, one per line with an optional weight")
+	fs.StringVar(&o.words, "words", "", `file of words for streams to print instead of glyphs ("default" for AAAAAAAAA)`)
+	fs.Float64Var(&o.glitch, "glitch", 0, "percentage of letters in words swapped for glyphs")
+	fs.Float64Var(&o.mutate, "mutate", 0, "give each stream a string of its own that evolves a glyph at a time, inserted, deleted or swapped, with this chance per tick, from 0 to 1")
+	fs.Float64Var(&o.linkChance, "link-chance", 0, "chance, from 0 to 1, of a new stream's head being a hyperlink to --link-url, for terminals that show them")
+	fs.BoolVar(&o.evolve, "evolve", false, "have forks inherit their parent's speed, lifetime, turning, color and glyphs, slightly mutated, and old streams fork the most, so long runs drift")
+	fs.BoolVar(&o.deathBursts, "death-bursts", false, "have streams shed a shower of fading glyphs when they die, rather than vanish")
+	fs.StringVar(&o.trails, "trails", "glyphs", "draw the cells behind each stream as random glyphs, or as lines joining them up like a circuit trace (lines)")
+	fs.StringVar(&o.orientation, "orientation", "horizontal", "which way the strings at the streams' heads run: horizontal, vertical, travel (the way the stream is going) or random, for each stream")
+	fs.StringVar(&o.banner, "banner", "", "show this text in big block letters in the middle of the screen")
+	fs.StringVar(&o.mode, "mode", "streams", "what to show: streams, clock, maze, typer, ticker, image or tail, or with -tags plugins a plugin from the plugins directory next to the config file")
+	fs.StringVar(&o.file, "file", "", "file to follow in tail mode, of lines to type in typer mode, or the PNG, JPEG or GIF to show in image mode")
+	fs.StringVar(&o.text, "text", "", `text to scroll in ticker mode, or "-" to read it from stdin`)
+	fs.IntVar(&o.tickerRow, "ticker-row", -1, "row the ticker runs along, from 0 at the top, or counting up from -1 at the bottom")
+	fs.StringVar(&o.imageStyle, "image-style", "blocks", "how image mode draws the image: half blocks of color (blocks) or charset glyphs in its colors (glyphs)")
+	fs.IntVar(&o.clockHours, "clock-format", 24, "clock mode hour format: 12 or 24")
+	fs.DurationVar(&o.timer, "timer", 0, "overlay a countdown of this length, then erupt when it runs out")
+	fs.BoolVar(&o.bell, "bell", false, "ring the terminal bell when the --timer runs out")
+	fs.StringVar(&o.react, "react", "", "scale chaos, speed and brightness with system load (cpu, mem or net) or sound (audio), or spawn streams from typed keys (keys)")
+	fs.DurationVar(&o.escalateAfter, "escalate-after", 0, "ramp chaos up once no key has been pressed for this long")
+	fs.Float64Var(&o.escalatePeak, "escalate-max", maxChaos, "chaos reached by --escalate-after")
+	fs.StringVar(&o.envelope, "envelope", "", "swing between calm and frantic over a period, as sine, ramp (up and down) or sawtooth (up, then drop) and the period, such as sine:120s")
+	fs.Float64Var(&o.glitchEvents, "glitch-events", 0, "average number of times a minute the whole screen glitches for a moment")
+	fs.Float64Var(&o.glitchPower, "glitch-strength", 0.5, "how badly --glitch-events corrupt the screen, from 0 to 1")
+	fs.StringVar(&o.fx, "fx", "", "comma-separated post-processing for every frame: scanlines, vignette or chromatic")
+	fs.Int64Var(&o.seed, "seed", 0, "seed for the random numbers, to play the same animation again; 0 picks one")
+	fs.BoolVar(&o.persist, "persist", false, "never erase: leave every glyph on screen until something is drawn over it")
+	fs.StringVar(&o.mirror, "mirror", "off", "reflect the streams for symmetric patterns: h, v, quad, kaleido or off")
+	fs.BoolVar(&o.braille, "braille", false, "draw streams as lines of braille dots, at eight times the resolution")
+	fs.BoolVar(&o.pixels, "pixels", false, "draw streams as lines of half-block pixels, in full color at twice the resolution")
+	fs.StringVar(&o.graphics, "graphics", "off", "draw streams as real pixels with sixel or kitty graphics, or auto for whichever the terminal supports, falling back to glyphs")
+}
+
+// registerTerminal adds the flags only run itself has.
+func (o *runOptions) registerTerminal(fs *flag.FlagSet) {
+	fs.DurationVar(&o.duration, "duration", 0, "exit automatically after this long")
+	fs.BoolVar(&o.exitOnKey, "exit-on-key", false, "exit on any key press, not just q")
+	fs.StringVar(&o.lock, "lock", "", "keep running until this word is typed")
+	fs.BoolVar(&o.noClear, "no-clear", false, "leave the last frame on the terminal when quitting")
+	fs.StringVar(&o.attrs, "attrs", "auto", "send the bold, underline and reverse video some themes give streams: on, off, or auto to leave them out on terminals that draw them badly")
+	fs.StringVar(&o.ascii, "ascii", "auto", "print only ASCII, with an ASCII charset and no box drawing: on, off, or auto for when the locale isn't UTF-8")
+	fs.StringVar(&o.linkURL, "link-url", defaultLinkURL, "where the heads --link-chance makes hyperlinks go")
+	fs.StringVar(&o.stats, "stats", "text", "sum the run up on quitting, from its length to the streams it spawned: as text, json or off")
+	fs.StringVar(&o.trace, "trace", "", "write a runtime trace of each frame's stages to this file, and a CPU profile to it with .pprof added (needs -tags profiling)")
+	fs.StringVar(&o.logFile, "log-file", "", "append a log of resizes, slow frames, errors and switches of mode, profile and theme to this file")
+	fs.StringVar(&o.logLevel, "log-level", "info", "least severe --log-file entries to keep: debug, info, warn or error")
+	fs.BoolVar(&o.titleAnim, "title-anim", false, "glitch the window title too, putting the old one back on quitting")
+	fs.BoolVar(&o.tmuxPopup, "tmux-popup", false, "inside tmux, run in a popup over the current window that closes on quitting")
+	fs.BoolVar(&o.daemon, "daemon", false, "wait as a screensaver, taking over the terminal once there's been no input for --idle and giving it back at the next")
+	fs.DurationVar(&o.idle, "idle", 10*time.Minute, "how long without input before --daemon shows the animation")
+	fs.StringVar(&o.maxCPU, "max-cpu", "", "keep to this share of a CPU, such as 5%, thinning the streams out and lowering the frame rate when drawing costs more")
+	fs.BoolVar(&o.eco, "eco", false, "save power: the same as --max-cpu "+ecoCPU)
+	fs.BoolVar(&o.filter, "filter", false, "corrupt lines read from stdin and write them to stdout (the default when stdin isn't a terminal)")
+	fs.BoolVar(&o.noWizard, "no-wizard", false, "don't offer the setup wizard on first run")
+	fs.BoolVar(&o.singleLoop, "single-loop", false, "draw frames from the input loop instead of a render goroutine of their own")
+	fs.StringVar(&o.region, "region", "", "confine the animation to x,y,w,h of the terminal, each in cells or a percentage, leaving the rest alone")
+	fs.StringVar(&o.split, "split", "", "split the terminal into columns x rows panes, such as 2x2, each running its own animation")
+	fs.StringVar(&o.splitThemes, "split-themes", "", `comma-separated themes for the --split panes in turn, "random" for any`)
+	fs.StringVar(&o.splitModes, "split-modes", "", `comma-separated modes for the --split panes in turn, "random" for any`)
+	fs.StringVar(&o.serveTelnet, "serve-telnet", "", "instead of drawing here, listen on this address, such as 0.0.0.0:2323, and stream an animation to each telnet client")
+	fs.StringVar(&o.serveWeb, "serve-web", "", "instead of drawing here, serve a web page on this address, such as 127.0.0.1:8080, that streams an animation to each visitor")
+	fs.StringVar(&o.syncHost, "sync-host", "", "listen on this address for other terminals to --sync-join, and keep them showing the same animation")
+	fs.StringVar(&o.syncJoin, "sync-join", "", "show the same animation as the --sync-host at this host:port, which has to be run with the same options")
+	fs.StringVar(&o.timeline, "timeline", "", "play the phases of this timeline file in turn, each overriding some of the flags for a while")
+	fs.StringVar(&o.outputPipe, "output-pipe", "", "also write the frames to this named pipe or Unix socket, for other programs to read")
+}
+
+// flags returns a flag set bound to o's current values, for reading them
+// back by name. Registering resets every field to its default, hence the
+// copy.
+func (o *runOptions) flags() *flag.FlagSet {
+	saved := *o
+	fs := flag.NewFlagSet("run", flag.ContinueOnError)
+	o.register(fs)
+	*o = saved
+	return fs
+}
+
+func (o *runOptions) params() Params {
+	p := defaultParams
+	p.chaos, p.speed = o.chaos, o.speed
+	return p
+}
+
+// palette returns t's palette for o's background.
+func (o *runOptions) palette(t *Theme) *Palette {
+	background, _ := resolveBackground(o.background)
+	p := t.variant(background)
+	if o.gradient == "theme" {
+		return p
+	}
+	gradient, _ := parseGradient(o.gradient)
+	custom := *p
+	custom.gradient = gradient
+	return &custom
+}
+
+// backend returns the backend the options ask for, nil for glyphs.
+func (o *runOptions) backend() (Backend, error) {
+	switch {
+	case o.braille:
+		return brailleBackend{}, nil
+	case o.pixels:
+		return halfBlockBackend{}, nil
+	}
+	return graphicsBackend(o.graphics)
+}
+
+// cpuBudget is the budget --max-cpu or --eco sets, if either does.
+func (o *runOptions) cpuBudget() (*cpuBudget, error) {
+	s := o.maxCPU
+	if s == "" && o.eco {
+		s = ecoCPU
+	}
+	if s == "" {
+		return nil, nil
+	}
+	share, err := parseMaxCPU(s)
+	if err != nil {
+		return nil, err
+	}
+	return newCPUBudget(share), nil
+}
+
+func (o *runOptions) loadCharset() (*Charset, error) {
+	if cs := lookupCharset(o.charset); cs != nil {
+		return cs, nil
+	}
+	return loadCharset(o.charset)
+}
+
+// scene is everything the options set up before the terminal is taken over:
+// the simulation config plus the handles the input loop needs.
+type scene struct {
+	SimConfig
+	escalation *Escalation
+	reactKeys  bool
+}
+
+func (o *runOptions) scene() (*scene, error) {
+	movement, err := newMovement(o.movement)
+	if err != nil {
+		return nil, err
+	}
+	collisions, err := parseCollisions(o.collisions)
+	if err != nil {
+		return nil, err
+	}
+	mirror, err := parseMirror(o.mirror)
+	if err != nil {
+		return nil, err
+	}
+	if o.braille && o.pixels || (o.braille || o.pixels) && o.graphics != "off" {
+		return nil, fmt.Errorf("only one of --braille, --pixels and --graphics can be used")
+	}
+	backend, err := o.backend()
+	if err != nil {
+		return nil, err
+	}
+	trails, err := parseTrails(o.trails)
+	if err != nil {
+		return nil, err
+	}
+	orientation, err := parseOrientation(o.orientation)
+	if err != nil {
+		return nil, err
+	}
+	fx, err := parseFX(o.fx)
+	if err != nil {
+		return nil, err
+	}
+	if o.chaos < 0 || o.chaos > maxChaos {
+		return nil, fmt.Errorf("--chaos must be between 0 and %d", maxChaos)
+	}
+	if o.speed <= 0 {
+		return nil, fmt.Errorf("--speed must be positive")
+	}
+	if o.maxStreams < 0 {
+		return nil, fmt.Errorf("--max-streams can't be negative")
+	}
+	if o.density <= 0 {
+		return nil, fmt.Errorf("--density must be positive")
+	}
+	if o.glitchEvents < 0 {
+		return nil, fmt.Errorf("--glitch-events can't be negative")
+	}
+	if o.glitchPower < 0 || o.glitchPower > 1 {
+		return nil, fmt.Errorf("--glitch-strength must be between 0 and 1")
+	}
+	if o.mutate < 0 || o.mutate > 1 {
+		return nil, fmt.Errorf("--mutate must be between 0 and 1")
+	}
+	if o.linkChance < 0 || o.linkChance > 1 {
+		return nil, fmt.Errorf("--link-chance must be between 0 and 1")
+	}
+	if o.zalgo < 0 || o.zalgo > maxZalgo {
+		return nil, fmt.Errorf("--zalgo must be between 0 and %d", maxZalgo)
+	}
+	theme, err := lookupTheme(o.theme)
+	if err != nil {
+		return nil, err
+	}
+	if _, err := resolveBackground(o.background); err != nil {
+		return nil, err
+	}
+	if o.gradient != "theme" {
+		if _, err := parseGradient(o.gradient); err != nil {
+			return nil, err
+		}
+	}
+	charset, err := o.loadCharset()
+	if err != nil {
+		return nil, err
+	}
+	var words []string
+	if o.words != "" {
+		if words, err = loadWords(o.words); err != nil {
+			return nil, err
+		}
+	}
+	sc := &scene{SimConfig: SimConfig{
+		palette:    o.palette(theme),
+		charset:    charset,
+		movement:   movement,
+		gravity:    o.gravity,
+		collisions: collisions,
+		maxStreams: o.maxStreams,
+		density:    o.density,
+		lifetime:   max(1, int(o.lifetime/tickInterval)),
+		zalgo:      o.zalgo,
+		words:      words,
+		glitch:     o.glitch / 100,
+		mutate:     o.mutate,
+		params:     o.params(),
+		seed:       o.seed,
+		persist:    o.persist,
+		mirror:     mirror,
+		backend:    backend,
+
+		fx:             fx,
+		glitchEvents:   o.glitchEvents / float64(time.Minute/tickInterval),
+		glitchStrength: o.glitchPower,
+		evolve:         o.evolve,
+		deathBursts:    o.deathBursts,
+		trails:         trails,
+		orientation:    orientation,
+		linkChance:     o.linkChance,
+	}}
+	if o.banner != "" {
+		sc.overlays = append(sc.overlays, newBanner(o.banner))
+	}
+	if o.timer > 0 {
+		t := newTimer(o.timer, o.bell)
+		sc.overlays = append(sc.overlays, t)
+		sc.modulators = append(sc.modulators, t)
+	}
+	if o.escalateAfter > 0 {
+		sc.escalation = newEscalation(o.escalateAfter, o.escalatePeak)
+		sc.modulators = append(sc.modulators, sc.escalation)
+	}
+	if o.circadian != "" {
+		circadian, err := parseCircadian(o.circadian, o.palette)
+		if err != nil {
+			return nil, err
+		}
+		sc.overlays = append(sc.overlays, circadian)
+	}
+	if o.envelope != "" {
+		envelope, err := parseEnvelope(o.envelope)
+		if err != nil {
+			return nil, err
+		}
+		sc.modulators = append(sc.modulators, envelope)
+	}
+	sc.reactKeys = o.react == "keys"
+	if o.react != "" && !sc.reactKeys {
+		reactor, err := newReactor(o.react)
+		if err != nil {
+			return nil, err
+		}
+		sc.modulators = append(sc.modulators, reactor)
+	}
+	if o.script != "" {
+		if err := loadScript(o.script, sc); err != nil {
+			return nil, err
+		}
+	}
+	build := lookupMode(o.mode)
+	if build == nil {
+		return nil, fmt.Errorf("unknown mode %q (want %s)", o.mode, strings.Join(modeNames(), ", "))
+	}
+	if sc.mode, err = build(o, sc); err != nil {
+		return nil, err
+	}
+	return sc, nil
+}
+
+// embeddedScene builds a scene from run's flags, without any of the flags
+// only a terminal has, for programs running animations of their own: the
+// browser build and the C library. The terminal, if there is one, isn't
+// theirs to ask about its background, so it's dark unless they say.
+func embeddedScene(args []string) (*scene, error) {
+	fs := flag.NewFlagSet("run", flag.ContinueOnError)
+	fs.SetOutput(io.Discard)
+	var o runOptions
+	o.register(fs)
+	if err := parseFlags(fs, args); err != nil {
+		return nil, err
+	}
+	if o.background == "auto" {
+		o.background = "dark"
+	}
+	return o.scene()
+}
+
+// profileOptions parses args again with the named profile applied, for
+// switching profiles or reloading the config while running.
+func profileOptions(args []string, profile string) (*runOptions, error) {
+	fs := flag.NewFlagSet("run", flag.ContinueOnError)
+	var o runOptions
+	o.register(fs)
+	o.registerTerminal(fs)
+	if err := parseProfile(fs, args, profile); err != nil {
+		return nil, err
+	}
+	return &o, nil
+}
+
+// runAnimation is the default command: it takes over the terminal and runs
+// the animation until quit.
+func runAnimation(args []string) error {
+	fs := flag.NewFlagSet("run", flag.ExitOnError)
+	var o runOptions
+	o.register(fs)
+	o.registerTerminal(fs)
+	if err := parseFlags(fs, args); err != nil {
+		return err
+	}
+
+	if err := o.checkSync(); err != nil {
+		return err
+	}
+	if o.tmuxPopup {
+		return runTmuxPopup(args)
+	}
+	if o.trace != "" {
+		stop, err := startTrace(o.trace)
+		if err != nil {
+			return err
+		}
+		defer stop()
+	}
+	if o.logFile != "" {
+		closeLog, err := openLog(o.logFile, o.logLevel)
+		if err != nil {
+			return err
+		}
+		defer closeLog()
+		logger.Info("start", "args", args, "term", os.Getenv("TERM"), "colorterm", os.Getenv("COLORTERM"), "locale", locale(), "tmux", inTmux(), "legacy_console", legacyConsole())
+	}
+	if o.serveTelnet != "" {
+		return serveTelnet(&o)
+	}
+	if o.serveWeb != "" {
+		return serveWeb(&o)
+	}
+	if !slices.Contains(asciiNames, o.ascii) {
+		return fmt.Errorf("unknown ascii %q (want one of %v)", o.ascii, asciiNames)
+	}
+	if err := prepareConsole(); err != nil {
+		return err
+	}
+	ascii := useASCII(o.ascii)
+	if ascii {
+		o.charset, o.zalgo = "ascii", 0
+	}
+	if o.filter || !isTerminal(os.Stdin) && !(o.mode == "ticker" && o.text == "-") {
+		charset, err := o.loadCharset()
+		if err != nil {
+			return err
+		}
+		chance := defaultFilterGlitch
+		if isSet(fs, "glitch") {
+			chance = o.glitch / 100
+		}
+		return runFilter(os.Stdin, os.Stdout, charset, chance, o.zalgo)
+	}
+	// Ask the terminal for its background, and what graphics it supports
+	// if they're wanted, now, before readKeys takes over stdin.
+	terminalBackground()
+	if o.graphics != "off" {
+		terminalGraphics()
+	}
+
+	keys := make(chan rune, 64)
+	raw := false
+	// unraw takes the terminal out of raw mode, changing as the process is
+	// stopped and continued.
+	unraw := func() {}
+	if restore, err := makeRaw(os.Stdin); err == nil {
+		unraw = restore
+		go readKeys(os.Stdin, keys)
+		raw = true
+	}
+	defer func() { unraw() }()
+	if raw && !o.noWizard && !o.daemon && firstRun() {
+		if err := runWizard(&o, keys); err != nil {
+			return err
+		}
+	}
+
+	cols, rows := 1, 1
+	if o.split != "" {
+		c, r, err := parseSplit(o.split)
+		if err != nil {
+			return err
+		}
+		cols, rows = c, r
+	}
+	if o.syncHost != "" && o.seed == 0 {
+		// Joiners need the seed, so it can't be left to the sim.
+		o.seed = time.Now().UnixNano()
+	}
+	panes, err := o.splitScenes(cols * rows)
+	if err != nil {
+		return err
+	}
+	var timeline *Timeline
+	if o.timeline != "" {
+		if timeline, err = loadTimeline(o.timeline); err != nil {
+			return err
+		}
+	}
+	// The first pane is the one profiles, reloads, timelines and overlays
+	// such as the flash apply to.
+	sc := panes[0]
+	var lock *Lock
+	if o.lock != "" {
+		lock = newLock(o.lock)
+	}
+	flash := &Flash{}
+	// attach adds what belongs to the run rather than the scene, so it
+	// survives switching profiles.
+	attach := func(sc *scene) {
+		sc.overlays = append(sc.overlays, flash)
+		if lock != nil {
+			sc.overlays = append(sc.overlays, lock)
+			sc.modulators = append(sc.modulators, lock)
+		}
+	}
+	attach(sc)
+
+	var profiles []string
+	if settings, err := loadConfig(); err == nil {
+		profiles = profileNames(settings)
+	}
+	profile := o.profile
+	// loaded holds the charsets read from files, in loadedNames order, so
+	// g can cycle back to them after the built-in ones.
+	var loadedNames []string
+	loaded := map[string]*Charset{}
+	remember := func(name string, cs *Charset) {
+		if lookupCharset(name) == nil && loaded[name] == nil {
+			loadedNames = append(loadedNames, name)
+			loaded[name] = cs
+		}
+	}
+	remember(o.charset, sc.charset)
+
+	reloads := watchConfig(time.Second)
+
+	sigs := make(chan os.Signal, 1)
+	signal.Notify(sigs, syscall.SIGINT, syscall.SIGTERM)
+	ignoreBrokenPipe()
+
+	if !slices.Contains(attrsNames, o.attrs) {
+		return fmt.Errorf("unknown attrs %q (want one of %v)", o.attrs, attrsNames)
+	}
+	if !slices.Contains(statsNames, o.stats) {
+		return fmt.Errorf("unknown stats %q (want one of %v)", o.stats, statsNames)
+	}
+	// report writes the stats once the terminal has been put back, if the
+	// run gets that far.
+	var report func()
+	defer func() {
+		if report != nil {
+			report()
+		}
+	}()
+	scr := newScreen(os.Stdout)
+	scr.keep = o.noClear
+	scr.noAttrs = !sendAttrs(o.attrs)
+	scr.ascii = ascii
+	scr.focus = raw
+	scr.tmux = inTmux()
+	scr.titles = o.titleAnim
+	scr.linkURL = o.linkURL
+	if legacyConsole() {
+		scr.colors16, scr.flushEvery = true, 2
+	}
+	if o.region != "" {
+		if scr.region, err = parseRegion(o.region); err != nil {
+			return err
+		}
+	}
+	if o.outputPipe != "" {
+		if scr.pipe, err = openOutputPipe(o.outputPipe); err != nil {
+			return err
+		}
+		defer scr.pipe.close()
+	}
+	// With --daemon the terminal's left alone until it's been idle.
+	var watcher *Watcher
+	if o.daemon {
+		if watcher, err = newWatcher(o.idle, raw); err != nil {
+			return err
+		}
+	} else {
+		scr.enter()
+	}
+	defer scr.leave()
+
+	// The render goroutine owns the sim and the screen from here on; the
+	// loop below only talks to it through commands. done is closed when it
+	// returns, either because it was told to quit or, with drawErr set,
+	// because writing a frame failed. send waits for room for a command;
+	// offer drops it if the goroutine is behind.
+	commands := make(chan Command, 64)
+	done := make(chan struct{})
+	var drawErr error
+	send := func(c Command) {
+		select {
+		case commands <- c:
+		case <-done:
+		}
+	}
+	offer := func(c Command) {
+		select {
+		case commands <- c:
+		default:
+		}
+	}
+	r := &renderer{cols: cols, rows: rows, scr: scr, size: terminalSize, paused: watcher != nil}
+	if r.budget, err = o.cpuBudget(); err != nil {
+		return err
+	}
+	if o.titleAnim {
+		r.title = newTitleAnim()
+	}
+	started := time.Now()
+	for _, pane := range panes {
+		r.sims = append(r.sims, newSim(pane.SimConfig))
+	}
+	if o.syncHost != "" {
+		if r.host, err = hostSync(o.syncHost, o.seed); err != nil {
+			return err
+		}
+		defer r.host.close()
+	}
+	if o.syncJoin != "" {
+		if r.sims[0], r.feed, err = joinSync(o.syncJoin, sc.SimConfig); err != nil {
+			return err
+		}
+	}
+	// With --single-loop there is no render goroutine: commands are applied
+	// on the spot and frames drawn by the loop below, between keys.
+	var frames <-chan time.Time
+	// retime changes the frame ticker, with --single-loop, to the
+	// renderer's interval if that has changed.
+	retime := func() {}
+	if o.singleLoop {
+		send = func(c Command) { c.apply(r) }
+		offer = send
+		interval := r.interval()
+		ticker := time.NewTicker(interval)
+		defer ticker.Stop()
+		frames = ticker.C
+		retime = func() {
+			if r.interval() != interval {
+				interval = r.interval()
+				ticker.Reset(interval)
+			}
+		}
+	} else {
+		go func() {
+			defer close(done)
+			drawErr = r.run(commands)
+		}()
+	}
+
+	var deadline <-chan time.Time
+	if o.duration > 0 {
+		deadline = time.After(o.duration)
+	}
+	// phase is the timeline phase playing, and phaseOver fires when it's
+	// time for the next.
+	phase := -1
+	var phaseOver <-chan time.Time
+	startPhase := func(i int) {
+		phase, phaseOver = i, nil
+		if i < 0 {
+			return
+		}
+		phaseOver = time.After(timeline.phases[i].length)
+		nextOptions, err := timeline.options(args, profile, i)
+		if err != nil {
+			flash.show(err.Error())
+			return
+		}
+		next, err := nextOptions.scene()
+		if err != nil {
+			flash.show(err.Error())
+			return
+		}
+		if d := timeline.phases[i].transition; d > 0 {
+			next.modulators = append([]Modulator{newTransition(sc.params, d)}, next.modulators...)
+		}
+		attach(next)
+		remember(nextOptions.charset, next.charset)
+		o, sc = *nextOptions, next
+		logger.Info("switch", "mode", o.mode, "theme", o.theme, "charset", o.charset)
+		send(Reconfigure{sc.SimConfig})
+	}
+	if timeline != nil {
+		startPhase(0)
+	}
+	// showing is false while --daemon waits for the terminal to go idle,
+	// checking every idlePoll.
+	showing := watcher == nil
+	var idleChecks <-chan time.Time
+	if watcher != nil {
+		ticker := time.NewTicker(idlePoll)
+		defer ticker.Stop()
+		idleChecks = ticker.C
+	}
+	// hide stops drawing and hands the terminal back, reporting whether it
+	// has by the time it returns.
+	hide := func() bool {
+		paused := make(chan struct{})
+		send(Suspend{paused})
+		select {
+		case <-paused:
+			return true
+		case <-done:
+			return false
+		}
+	}
+	suspends := make(chan os.Signal, 1)
+	notifySuspend(suspends)
+	// suspend hands the terminal back and stops the process, taking the
+	// terminal over again once it's continued.
+	suspend := func() {
+		if showing && !hide() {
+			return
+		}
+		unraw()
+		stop(suspends)
+		if restore, err := makeRaw(os.Stdin); err == nil {
+			unraw = restore
+		}
+		if showing {
+			send(Resume{})
+		}
+	}
+	for running := true; running; {
+		select {
+		case <-sigs:
+			running = false
+		case s := <-suspends:
+			switch {
+			case !isContinue(s):
+				suspend()
+			case showing:
+				send(Resume{})
+			}
+		case <-idleChecks:
+			switch show, gone := watcher.check(); {
+			case show:
+				showing = true
+				send(Resume{})
+			case gone:
+				showing = false
+				hide()
+			}
+		case <-done:
+			running = false
+		case <-frames:
+			if drawErr = r.frame(); drawErr != nil {
+				running = false
+			}
+			retime()
+		case <-deadline:
+			running = lock != nil
+		case <-phaseOver:
+			startPhase(timeline.next(phase))
+		case <-reloads:
+			// Only the look is reloaded; rebuilding the whole scene would
+			// restart timers and reopen files.
+			if settings, err := loadConfig(); err == nil {
+				profiles = profileNames(settings)
+			}
+			next, err := profileOptions(args, profile)
+			if err != nil {
+				flash.show(err.Error())
+				break
+			}
+			theme, err := lookupTheme(next.theme)
+			if err == nil {
+				_, err = resolveBackground(next.background)
+			}
+			if err != nil {
+				flash.show(err.Error())
+				break
+			}
+			charset, err := next.loadCharset()
+			if err != nil {
+				flash.show(err.Error())
+				break
+			}
+			remember(next.charset, charset)
+			o.theme, o.background, o.charset, o.speed, o.chaos = next.theme, next.background, next.charset, next.speed, next.chaos
+			sc.palette, sc.charset, sc.params = o.palette(theme), charset, o.params()
+			send(Reconfigure{sc.SimConfig})
+			flash.show("config reloaded")
+		case k := <-keys:
+			if k == keyFocusIn || k == keyFocusOut {
+				send(SetFocus{k == keyFocusIn})
+				retime()
+				continue
+			}
+			if sc.escalation != nil {
+				sc.escalation.touch()
+			}
+			if watcher != nil {
+				if watcher.key() {
+					// The key that brings the terminal back does nothing
+					// else.
+					showing = false
+					hide()
+					continue
+				}
+				if !showing {
+					running = !isQuitKey(k)
+					continue
+				}
+			}
+			if lock != nil {
+				running = !lock.feed(k)
+				continue
+			}
+			switch {
+			case quits(k, o.exitOnKey):
+				running = false
+			case k == keyCtrlZ && jobControl:
+				suspend()
+			case k == 'b':
+				send(ToggleBoss{})
+			case k == '!':
+				send(PanicBurst{})
+			case k == 'h':
+				send(ToggleHeatmap{})
+			case k == 'p' && len(profiles) > 0:
+				profile = profiles[(slices.Index(profiles, profile)+1)%len(profiles)]
+				nextOptions, err := profileOptions(args, profile)
+				if err != nil {
+					flash.show(err.Error())
+					break
+				}
+				next, err := nextOptions.scene()
+				if err != nil {
+					flash.show(err.Error())
+					break
+				}
+				attach(next)
+				remember(nextOptions.charset, next.charset)
+				o, sc = *nextOptions, next
+				logger.Info("switch", "mode", o.mode, "theme", o.theme, "charset", o.charset)
+				send(Reconfigure{sc.SimConfig})
+				flash.show("profile " + profile)
+			case k == '+' || k == '=' || k == '-' || k == ']' || k == '[':
+				switch k {
+				case '+', '=':
+					o.speed = min(o.speed*1.25, maxSpeed)
+				case '-':
+					o.speed = max(o.speed/1.25, minSpeed)
+				case ']':
+					o.chaos = min(o.chaos+0.5, maxChaos)
+				case '[':
+					o.chaos = max(o.chaos-0.5, 0)
+				}
+				send(SetParams{o.params()})
+				flash.show(fmt.Sprintf("speed %.2f, chaos %.1f", o.speed, o.chaos))
+			case k == 't' || k == 'T':
+				i := slices.Index(themeNames(), o.theme)
+				if k == 't' {
+					i = (i + 1) % len(themes)
+				} else if len(themes) > 1 {
+					i = (i + 1 + rand.Intn(len(themes)-1)) % len(themes)
+				}
+				o.theme, sc.palette = themes[i].name, o.palette(&themes[i])
+				send(SetPalette{sc.palette})
+				flash.show("theme " + o.theme)
+			case k == 'g':
+				names := append(charsetNames(), loadedNames...)
+				o.charset = names[(slices.Index(names, o.charset)+1)%len(names)]
+				if sc.charset = lookupCharset(o.charset); sc.charset == nil {
+					sc.charset = loaded[o.charset]
+				}
+				send(SetCharset{sc.charset})
+				flash.show("charset " + o.charset)
+			case k == 'S':
+				name, err := saveProfile(&o)
+				if err != nil {
+					flash.show(err.Error())
+					break
+				}
+				profiles = append(profiles, name)
+				profile = name
+				flash.show("saved profile " + name)
+			case sc.reactKeys && unicode.IsPrint(k):
+				// Keys typed faster than bursts can be drawn are dropped.
+				offer(SpawnBurst{string(k)})
+			default:
+				offer(ModeInput{k})
+			}
+		}
+	}
+	if !o.singleLoop {
+		send(Quit{})
+		<-done
+	}
+	if drawErr != nil {
+		return fmt.Errorf("writing to the terminal: %w", drawErr)
+	}
+	if r.syncErr != nil {
+		return fmt.Errorf("lost the sync host: %w", r.syncErr)
+	}
+	stats, format := r.stats(time.Since(started)), o.stats
+	report = func() { writeStats(os.Stdout, format, stats) }
+	return nil
+}
+
+// isSet reports whether the named flag was given, on the command line or
+// in the config file.
+func isSet(fs *flag.FlagSet, name string) bool {
+	set := false
+	fs.Visit(func(f *flag.Flag) {
+		if f.Name == name {
+			set = true
+		}
+	})
+	return set
+}
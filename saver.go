@@ -0,0 +1,73 @@
+package main
+
+import (
+	"errors"
+	"time"
+)
+
+// idleSource reports how long the desktop has gone without input.
+type idleSource func() (time.Duration, error)
+
+// idlePoll is how often --daemon checks how long it's been idle.
+const idlePoll = time.Second
+
+// Watcher tells --daemon when to show the animation and when to put the
+// terminal back: once there's been no input for idle, and as soon as
+// there is again. Input is keys typed at the terminal, and with desktop
+// set, anything the desktop has had besides.
+type Watcher struct {
+	idle    time.Duration
+	desktop idleSource
+
+	lastKey time.Time
+	showing bool
+}
+
+func newWatcher(idle time.Duration, keys bool) (*Watcher, error) {
+	if idle <= 0 {
+		return nil, errors.New("--idle has to be positive")
+	}
+	w := &Watcher{idle: idle, desktop: desktopIdle(), lastKey: time.Now()}
+	if w.desktop == nil && !keys {
+		return nil, errors.New("--daemon needs keys from a terminal to tell when it's idle, or a build with -tags idle to ask the desktop")
+	}
+	return w, nil
+}
+
+// idleFor is how long there's been no input.
+func (w *Watcher) idleFor() time.Duration {
+	idle := time.Since(w.lastKey)
+	if w.desktop != nil {
+		if d, err := w.desktop(); err == nil {
+			idle = min(idle, d)
+		}
+	}
+	return idle
+}
+
+// key notes a key press, reporting whether the animation should go away
+// for it.
+func (w *Watcher) key() bool {
+	w.lastKey = time.Now()
+	return w.hide()
+}
+
+// check reports whether the animation should come up, or go away, as
+// whatever's changed since the last check.
+func (w *Watcher) check() (show, hide bool) {
+	switch idle := w.idleFor(); {
+	case !w.showing && idle >= w.idle:
+		w.showing = true
+		return true, false
+	case w.showing && idle < w.idle:
+		w.showing = false
+		return false, true
+	}
+	return false, false
+}
+
+func (w *Watcher) hide() bool {
+	hide := w.showing
+	w.showing = false
+	return hide
+}
@@ -0,0 +1,60 @@
+//go:build idle && !windows
+
+package main
+
+import (
+	"fmt"
+	"os"
+	"os/exec"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// desktopIdle asks the desktop how long it's been idle: GNOME on Wayland
+// through Mutter's idle monitor, with gdbus, or X11 through xprintidle. It's
+// nil if neither is there.
+func desktopIdle() idleSource {
+	if os.Getenv("WAYLAND_DISPLAY") != "" {
+		if _, err := exec.LookPath("gdbus"); err == nil {
+			return mutterIdle
+		}
+	}
+	if os.Getenv("DISPLAY") != "" {
+		if _, err := exec.LookPath("xprintidle"); err == nil {
+			return x11Idle
+		}
+	}
+	return nil
+}
+
+// x11Idle runs xprintidle, which prints the idle time in milliseconds.
+func x11Idle() (time.Duration, error) {
+	out, err := exec.Command("xprintidle").Output()
+	if err != nil {
+		return 0, err
+	}
+	return parseIdleMillis(strings.TrimSpace(string(out)))
+}
+
+// mutterIdle asks Mutter's idle monitor, which answers with a GVariant such
+// as (uint64 12345,), in milliseconds.
+func mutterIdle() (time.Duration, error) {
+	out, err := exec.Command("gdbus", "call", "--session",
+		"--dest", "org.gnome.Mutter.IdleMonitor",
+		"--object-path", "/org/gnome/Mutter/IdleMonitor/Core",
+		"--method", "org.gnome.Mutter.IdleMonitor.GetIdletime").Output()
+	if err != nil {
+		return 0, err
+	}
+	s := strings.Trim(strings.TrimSpace(string(out)), "(,)")
+	return parseIdleMillis(strings.TrimPrefix(s, "uint64 "))
+}
+
+func parseIdleMillis(s string) (time.Duration, error) {
+	ms, err := strconv.ParseUint(s, 10, 64)
+	if err != nil {
+		return 0, fmt.Errorf("bad idle time %q", s)
+	}
+	return time.Duration(ms) * time.Millisecond, nil
+}
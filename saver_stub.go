@@ -0,0 +1,9 @@
+//go:build !idle
+
+package main
+
+// desktopIdle is nil without the idle tag: only keys typed at the terminal
+// count as input.
+func desktopIdle() idleSource {
+	return nil
+}
@@ -0,0 +1,33 @@
+//go:build idle && windows
+
+package main
+
+import (
+	"syscall"
+	"time"
+	"unsafe"
+)
+
+var (
+	procGetLastInputInfo = syscall.NewLazyDLL("user32.dll").NewProc("GetLastInputInfo")
+	procGetTickCount     = kernel32.NewProc("GetTickCount")
+)
+
+type lastInputInfo struct {
+	size uint32
+	time uint32
+}
+
+// desktopIdle asks Windows when it last had input.
+func desktopIdle() idleSource {
+	return func() (time.Duration, error) {
+		info := lastInputInfo{size: uint32(unsafe.Sizeof(lastInputInfo{}))}
+		if ok, _, err := procGetLastInputInfo.Call(uintptr(unsafe.Pointer(&info))); ok == 0 {
+			return 0, err
+		}
+		now, _, _ := procGetTickCount.Call()
+		// The tick counts wrap every 49 days; the subtraction wraps with
+		// them.
+		return time.Duration(uint32(now)-info.time) * time.Millisecond, nil
+	}
+}
@@ -0,0 +1,783 @@
+package main
+
+import (
+	"cmp"
+	"fmt"
+	"io"
+	"math"
+	"slices"
+	"strconv"
+	"unicode"
+	"unicode/utf8"
+)
+
+// cell is one character cell: its text (a character plus any combining
+// marks), colors and attributes, and whether it's part of a hyperlink. A
+// wide character takes two cells, the first marked wide and the second with
+// wideTail for its text.
+type cell struct {
+	text  string
+	fg    Color
+	bg    Color
+	hasBg bool
+	attrs Attr
+	wide  bool
+	link  bool
+}
+
+// wideTail is the text of the cell a wide character spills into, which
+// flush sends nothing for.
+const wideTail = "\x00"
+
+// span is the columns from lo up to but not including hi; empty if hi <= lo.
+type span struct{ lo, hi int }
+
+func (s span) union(t span) span {
+	switch {
+	case s.hi <= s.lo:
+		return t
+	case t.hi <= t.lo:
+		return s
+	}
+	return span{min(s.lo, t.lo), max(s.hi, t.hi)}
+}
+
+// Screen owns the terminal while the animation runs: the alternate screen,
+// the hidden cursor, and the cells drawn this frame. flush sends only the
+// cells that changed since the last frame, grouped by color so each color
+// is set once, in a single write. Its buffers are kept from frame to frame
+// so drawing doesn't allocate.
+//
+// Each frame starts blank, so whatever isn't drawn again is erased, unless
+// persist is set. Only the spans of each row drawn this frame or last are
+// compared, so a sparse frame is quick to send.
+type Screen struct {
+	w             io.Writer
+	width, height int
+
+	// cells is the frame being drawn; shown is what the terminal has.
+	cells, shown []cell
+
+	// dirty is the span of each row drawn this frame and drawn the span
+	// drawn last frame; full means compare every cell regardless.
+	dirty, drawn []span
+	full         bool
+
+	// persist keeps every cell as it was until it's drawn over, never
+	// erasing anything.
+	persist bool
+
+	// buf is the output waiting for the next write, and changed the
+	// cells flush is sending.
+	buf     []byte
+	changed []int
+
+	// interned holds the glyph strings built by intern.
+	interned map[string]string
+
+	// brightness dims every color put, 0 to 1.
+	brightness float64
+
+	// attrs are given to every cell put, and noAttrs leaves them all out
+	// of what's sent, for terminals that draw them badly.
+	attrs   Attr
+	noAttrs bool
+
+	// link is given to every cell put too, and the cells with it are sent
+	// as a hyperlink to linkURL, if that's set.
+	link    bool
+	linkURL string
+
+	// ascii sends an ASCII stand-in for every glyph, for terminals that
+	// can't show anything else.
+	ascii bool
+
+	// colors16 sends the nearest of the 16 basic colors rather than
+	// truecolor, and flushEvery sends only one frame in that many, for
+	// terminals slow to draw.
+	colors16   bool
+	flushEvery int
+	skipped    int
+
+	// focus has the terminal report gaining and losing focus, as keys.
+	focus bool
+
+	// keep draws on the normal screen rather than the alternate one, so the
+	// last frame is left behind on exit.
+	keep bool
+	alt  bool
+
+	// region, if set, confines drawing to a rectangle of the normal screen
+	// with its top left corner at the origin, leaving the rest alone.
+	region           *Region
+	originX, originY int
+
+	// window is the rectangle put draws into, its coordinates relative to
+	// the window's top left corner: the whole screen, or one pane of a
+	// split.
+	window struct{ x, y, width, height int }
+
+	// mirror reflects everything put within the window.
+	mirror Mirror
+
+	// images are the graphics to send with the next flush, and kitty
+	// whether any kitty images have been shown, for leave to delete.
+	images []image
+	kitty  bool
+
+	// titles saves the window title on enter and puts it back on leave,
+	// for setTitle to change in between.
+	titles bool
+
+	// tmux wraps the graphics so tmux passes them through to the terminal
+	// outside.
+	tmux bool
+
+	// pipe, with --output-pipe, gets a copy of everything written, and
+	// repaint is set when it missed some, so the next flush erases and
+	// draws everything again.
+	pipe    *outputPipe
+	repaint bool
+}
+
+// image is a graphics escape drawing a picture with its top left corner at
+// the cell (x, y). under says whether text shows on top of it; if not, the
+// cells are sent again after it.
+type image struct {
+	x, y  int
+	data  []byte
+	under bool
+}
+
+// putImage queues a graphics escape to draw at the window's top left
+// corner. data has to stay as it is until the next flush.
+func (scr *Screen) putImage(data []byte, under bool) {
+	scr.images = append(scr.images, image{scr.window.x, scr.window.y, data, under})
+}
+
+func newScreen(w io.Writer) *Screen {
+	return &Screen{w: w, brightness: 1, interned: make(map[string]string)}
+}
+
+// enter takes over the screen, clearing it, so the next flush redraws every
+// cell, for example after the boss screen.
+func (scr *Screen) enter() {
+	switch {
+	case scr.region != nil:
+		scr.writeString("\x1b7\x1b[?25l")
+		scr.erase()
+	case scr.keep:
+		scr.writeString("\x1b[?25l\x1b[2J")
+	default:
+		scr.writeString("\x1b[?1049h\x1b[?25l\x1b[2J")
+		scr.alt = true
+	}
+	if scr.focus {
+		scr.writeString("\x1b[?1004h")
+	}
+	if scr.titles {
+		scr.writeString("\x1b[22;0t")
+	}
+	clear(scr.shown)
+	scr.full = true
+	scr.write()
+}
+
+// defaultLinkURL is where hyperlinks go unless --link-url says otherwise.
+const defaultLinkURL = "https://github.com/ovnanova/AAAAAAAAA"
+
+// appendLink buffers the OSC 8 sequence opening a hyperlink to linkURL, or
+// closing it.
+func (scr *Screen) appendLink(open bool) {
+	scr.writeString("\x1b]8;;")
+	if open {
+		scr.writeString(scr.linkURL)
+	}
+	scr.writeString("\x1b\\")
+}
+
+// setTitle buffers an OSC 2 sequence setting the window title, without any
+// control characters in title that would end it early.
+func (scr *Screen) setTitle(title string) {
+	scr.writeString("\x1b]2;")
+	for _, r := range title {
+		if r >= ' ' && r != 0x7f {
+			scr.buf = utf8.AppendRune(scr.buf, r)
+		}
+	}
+	scr.buf = append(scr.buf, '\a')
+}
+
+// graphics buffers a graphics escape, wrapped for tmux if need be.
+func (scr *Screen) graphics(seq string) {
+	if scr.tmux {
+		scr.buf = appendPassthrough(scr.buf, []byte(seq))
+	} else {
+		scr.writeString(seq)
+	}
+}
+
+// leave restores colors and the cursor. On the alternate screen it also
+// clears and switches back; with keep set the last frame stays where it is
+// and the cursor drops below it. A region is cleared unless keep is set, and
+// the cursor put back where it was.
+func (scr *Screen) leave() {
+	scr.writeString("\x1b[0m\x1b[?25h")
+	if scr.focus {
+		scr.writeString("\x1b[?1004l")
+	}
+	if scr.titles {
+		scr.writeString("\x1b[23;0t")
+	}
+	if scr.kitty {
+		scr.graphics("\x1b_Ga=d,q=2\x1b\\")
+		scr.kitty = false
+	}
+	switch {
+	case scr.alt:
+		scr.writeString("\x1b[2J\x1b[?1049l")
+		scr.alt = false
+	case scr.region != nil:
+		if !scr.keep {
+			scr.erase()
+		}
+		scr.writeString("\x1b8")
+	case scr.keep:
+		scr.buf = fmt.Appendf(scr.buf, "\x1b[%d;1H\n", scr.height)
+	}
+	scr.write()
+}
+
+// boss drops back to the normal screen and prints the fake session there,
+// cursor showing, until enter is called again.
+func (scr *Screen) boss() {
+	scr.leave()
+	scr.writeString("\x1b[2J\x1b[H" + fakeSession())
+	scr.write()
+}
+
+// resize fits the screen to a terminal of the given size, or to its
+// region of one, clearing it and the cells when that changes. It returns
+// the size there is to draw in.
+func (scr *Screen) resize(width, height int) (int, int) {
+	x, y := 0, 0
+	if scr.region != nil {
+		x, y, width, height = scr.region.rect(width, height)
+	}
+	if width != scr.width || height != scr.height || x != scr.originX || y != scr.originY {
+		scr.erase()
+		scr.originX, scr.originY = x, y
+		scr.width, scr.height = width, height
+		scr.cells = make([]cell, width*height)
+		scr.shown = make([]cell, width*height)
+		scr.dirty = make([]span, height)
+		scr.drawn = make([]span, height)
+		scr.full = true
+		if scr.region != nil {
+			scr.erase()
+		}
+	}
+	scr.setWindow(0, 0, width, height)
+	return width, height
+}
+
+// erase blanks the screen, or just the region.
+func (scr *Screen) erase() {
+	if scr.region == nil {
+		scr.writeString("\x1b[2J")
+		return
+	}
+	scr.writeString("\x1b[0m")
+	for y := range scr.height {
+		scr.moveTo(0, y)
+		for range scr.width {
+			scr.buf = append(scr.buf, ' ')
+		}
+	}
+}
+
+// moveTo moves the cursor to the zero-based cell (x, y) of the screen.
+func (scr *Screen) moveTo(x, y int) {
+	scr.buf = append(scr.buf, "\x1b["...)
+	scr.buf = strconv.AppendInt(scr.buf, int64(scr.originY+y+1), 10)
+	scr.buf = append(scr.buf, ';')
+	scr.buf = strconv.AppendInt(scr.buf, int64(scr.originX+x+1), 10)
+	scr.buf = append(scr.buf, 'H')
+}
+
+// put prints s in color c starting at the zero-based cell (x, y), cut off at
+// the right edge so it never wraps onto the next row.
+func (scr *Screen) put(x, y int, s string, c Color) {
+	scr.set(x, y, s, c, Color{}, false)
+}
+
+// putBg is put with a background color behind s as well.
+func (scr *Screen) putBg(x, y int, s string, fg, bg Color) {
+	scr.set(x, y, s, fg, bg, true)
+}
+
+func (scr *Screen) set(x, y int, s string, fg, bg Color, hasBg bool) {
+	if x < 0 || y < 0 || x >= scr.window.width || y >= scr.window.height {
+		return
+	}
+	if scr.brightness < 1 {
+		fg, bg = fg.dim(scr.brightness), bg.dim(scr.brightness)
+	}
+	start, wide := -1, false
+	for i, r := range s {
+		if unicode.In(r, unicode.Mn, unicode.Me) {
+			continue
+		}
+		if start >= 0 {
+			if x >= scr.window.width {
+				return
+			}
+			scr.setCell(x, y, cell{s[start:i], fg, bg, hasBg, scr.attrs, wide, scr.link})
+			x += cellWidth(wide)
+		}
+		start, wide = i, isWide(r) && !scr.ascii
+	}
+	if start >= 0 && x < scr.window.width {
+		scr.setCell(x, y, cell{s[start:], fg, bg, hasBg, scr.attrs, wide, scr.link})
+	}
+}
+
+// cellWidth is how many cells a character takes, wide or not.
+func cellWidth(wide bool) int {
+	if wide {
+		return 2
+	}
+	return 1
+}
+
+// setCell sets the cell at (x, y) of the window and its reflections.
+func (scr *Screen) setCell(x, y int, c cell) {
+	scr.setAt(x, y, c)
+	if scr.mirror != MirrorOff {
+		scr.mirror.reflections(x, y, scr.window.width, scr.window.height, func(x, y int) {
+			scr.setAt(x, y, c)
+		})
+	}
+}
+
+func (scr *Screen) setAt(x, y int, c cell) {
+	win := scr.window
+	if x < 0 || y < 0 || x >= win.width || y >= win.height || c.wide && x+1 >= win.width {
+		return
+	}
+	x, y = x+win.x, y+win.y
+	placeCell(scr.cells[y*scr.width:(y+1)*scr.width], x, c)
+	hi := x + cellWidth(c.wide)
+	// Either neighbour may have been the other half of a wide character.
+	scr.dirty[y] = scr.dirty[y].union(span{max(x-1, 0), min(hi+1, scr.width)})
+}
+
+// placeCell writes c into cell x of row, and its tail into the next if
+// it's wide, blanking the other halves of any wide characters it's drawn
+// over. A wide character with no room for its tail isn't written.
+func placeCell(row []cell, x int, c cell) {
+	if c.wide && x+1 >= len(row) {
+		return
+	}
+	unpair(row, x)
+	row[x] = c
+	if c.wide {
+		unpair(row, x+1)
+		row[x+1] = cell{text: wideTail, fg: c.fg, bg: c.bg, hasBg: c.hasBg, attrs: c.attrs}
+	}
+}
+
+// unpair blanks the other half of the wide character that cell x of row is
+// half of, if it is, before it's drawn over.
+func unpair(row []cell, x int) {
+	switch {
+	case row[x].wide && x+1 < len(row):
+		row[x+1] = cell{}
+	case row[x].text == wideTail && x > 0:
+		row[x-1] = cell{}
+	}
+}
+
+// invert swaps the colors of every cell in the window, lighting the blank
+// ones in c.
+func (scr *Screen) invert(c Color) {
+	for y := range scr.window.height {
+		invertCells(scr.row(y), c.dim(scr.brightness))
+	}
+}
+
+func invertCells(cells []cell, c Color) {
+	for i := range cells {
+		if cells[i].text == "" {
+			cells[i] = cell{text: " ", bg: c, hasBg: true}
+			continue
+		}
+		cells[i].fg, cells[i].bg, cells[i].hasBg = cells[i].bg, cells[i].fg, true
+	}
+}
+
+// row returns row y of the window's cells, nil if it's outside, for
+// working on what's been drawn in place. The whole row is redrawn.
+func (scr *Screen) row(y int) []cell {
+	win := scr.window
+	if y < 0 || y >= win.height {
+		return nil
+	}
+	y += win.y
+	scr.dirty[y] = scr.dirty[y].union(span{win.x, win.x + win.width})
+	return scr.cells[y*scr.width+win.x : y*scr.width+win.x+win.width]
+}
+
+// setWindow confines put to the given rectangle of the screen.
+func (scr *Screen) setWindow(x, y, width, height int) {
+	scr.window.x, scr.window.y, scr.window.width, scr.window.height = x, y, width, height
+}
+
+func (scr *Screen) bell() {
+	scr.buf = append(scr.buf, '\a')
+}
+
+// flush sends the cells that changed since the last flush, sorted by color
+// so that runs sharing a color need only one color change, and moving the
+// cursor only when the next cell isn't where it already is.
+func (scr *Screen) flush() error {
+	if scr.flushEvery > 1 {
+		// The cells changed in the frames skipped are still dirty, and
+		// go with the next one sent.
+		if scr.skipped++; scr.skipped < scr.flushEvery {
+			scr.images = scr.images[:0]
+			return nil
+		}
+		scr.skipped = 0
+	}
+	if scr.repaint {
+		scr.erase()
+		clear(scr.shown)
+		scr.full, scr.repaint = true, false
+	}
+	for _, im := range scr.images {
+		scr.moveTo(im.x, im.y)
+		if scr.tmux {
+			scr.buf = appendPassthrough(scr.buf, im.data)
+		} else {
+			scr.buf = append(scr.buf, im.data...)
+		}
+		if im.under {
+			scr.kitty = true
+		} else {
+			clear(scr.shown)
+			scr.full = true
+		}
+	}
+	scr.images = scr.images[:0]
+	endDiff := stage("diff")
+	changed := scr.changed[:0]
+	for y := range scr.height {
+		s := scr.dirty[y].union(scr.drawn[y])
+		if scr.full {
+			s = span{0, scr.width}
+		}
+		for i := y*scr.width + s.lo; i < y*scr.width+s.hi; i++ {
+			if scr.cells[i] != scr.shown[i] {
+				changed = append(changed, i)
+			}
+		}
+	}
+	scr.changed = changed
+	slices.SortStableFunc(changed, func(a, b int) int {
+		return compareStyle(scr.cells[a], scr.cells[b])
+	})
+	endDiff()
+	endEncode := stage("encode")
+	cursor := -1
+	var last *cell
+	// attrs are the attributes sent, turned off again at the end so
+	// nothing else drawn picks them up.
+	var attrs Attr
+	// link is whether the cells sent are in a hyperlink, closed at the end
+	// too.
+	link := false
+	for _, i := range changed {
+		c := &scr.cells[i]
+		x := i % scr.width
+		if c.text == wideTail && x > 0 && scr.cells[i-1].wide {
+			// The wide character before sends this half too.
+			continue
+		}
+		if i != cursor {
+			scr.moveTo(i%scr.width, i/scr.width)
+		}
+		if last == nil || c.fg != last.fg {
+			scr.buf = scr.appendColor(scr.buf, 38, c.fg)
+		}
+		if last == nil || c.hasBg != last.hasBg || c.bg != last.bg {
+			if c.hasBg {
+				scr.buf = scr.appendColor(scr.buf, 48, c.bg)
+			} else {
+				scr.writeString("\x1b[49m")
+			}
+		}
+		if c.attrs != attrs && !scr.noAttrs {
+			scr.buf = appendAttrs(scr.buf, c.attrs)
+			attrs = c.attrs
+		}
+		if c.link != link && scr.linkURL != "" {
+			scr.appendLink(c.link)
+			link = c.link
+		}
+		last = c
+		// What's left of a wide character split up by a pass working on
+		// the cells directly is sent as a blank.
+		wide := c.wide && x+1 < scr.width && scr.cells[i+1].text == wideTail
+		switch {
+		case c.text == "" || c.text == wideTail || c.wide && !wide:
+			scr.buf = append(scr.buf, ' ')
+		case scr.ascii:
+			scr.writeString(asciiGlyph(c.text))
+		default:
+			scr.writeString(c.text)
+		}
+		// Past the last column the cursor's position depends on the
+		// terminal, so the next cell always moves it.
+		cursor = i + cellWidth(wide)
+		if cursor%scr.width == 0 {
+			cursor = -1
+		}
+	}
+	if attrs != 0 {
+		scr.buf = appendAttrs(scr.buf, 0)
+	}
+	if link {
+		scr.appendLink(false)
+	}
+	copy(scr.shown, scr.cells)
+	scr.next()
+	endEncode()
+	return scr.write()
+}
+
+// next gets the cells ready for the next frame, blanking what this one drew
+// unless persist is set.
+func (scr *Screen) next() {
+	for y, s := range scr.dirty {
+		if scr.persist {
+			// Nothing is erased, so only what the next frame draws can
+			// change.
+			s = span{}
+		} else {
+			clear(scr.cells[y*scr.width+s.lo : y*scr.width+s.hi])
+		}
+		scr.drawn[y], scr.dirty[y] = s, span{}
+	}
+	scr.full = false
+}
+
+func (scr *Screen) writeString(s string) {
+	scr.buf = append(scr.buf, s...)
+}
+
+// write sends everything buffered in one go.
+func (scr *Screen) write() error {
+	defer stage("output")()
+	if scr.pipe != nil && !scr.pipe.send(scr.buf) {
+		logger.Debug("output pipe behind, dropping a frame")
+		scr.repaint = true
+	}
+	_, err := scr.w.Write(scr.buf)
+	scr.buf = scr.buf[:0]
+	return err
+}
+
+// intern returns b as a string, reusing the string from an earlier call with
+// the same bytes so that glyphs built on the fly cost nothing once seen.
+func (scr *Screen) intern(b []byte) string {
+	if s, ok := scr.interned[string(b)]; ok {
+		return s
+	}
+	if len(scr.interned) >= maxInterned {
+		clear(scr.interned)
+	}
+	s := string(b)
+	scr.interned[s] = s
+	return s
+}
+
+const maxInterned = 4096
+
+// appendColor appends the SGR sequence setting a foreground (38) or
+// background (48) in the colors the screen sends.
+func (scr *Screen) appendColor(b []byte, layer int, c Color) []byte {
+	if scr.colors16 {
+		return appendColor16(b, layer, c)
+	}
+	return appendColor(b, layer, c)
+}
+
+// basicColors are the 16 basic colors as the Windows console shows them,
+// black to white then their bright versions.
+var basicColors = [16]Color{
+	{12, 12, 12}, {197, 15, 31}, {19, 161, 14}, {193, 156, 0},
+	{0, 55, 218}, {136, 23, 152}, {58, 150, 221}, {204, 204, 204},
+	{118, 118, 118}, {231, 72, 86}, {22, 198, 12}, {249, 241, 165},
+	{59, 120, 255}, {180, 0, 158}, {97, 214, 214}, {242, 242, 242},
+}
+
+// appendColor16 appends the SGR sequence setting the basic color nearest c
+// as a foreground (for layer 38) or background (48).
+func appendColor16(b []byte, layer int, c Color) []byte {
+	best, dist := 0, math.MaxInt
+	for i, bc := range basicColors {
+		dr, dg, db := int(c.R)-int(bc.R), int(c.G)-int(bc.G), int(c.B)-int(bc.B)
+		if d := dr*dr + dg*dg + db*db; d < dist {
+			best, dist = i, d
+		}
+	}
+	// 30 to 37 and 90 to 97 are the foregrounds, 40 to 47 and 100 to 107
+	// the backgrounds.
+	code := layer - 8 + best
+	if best >= 8 {
+		code += 60 - 8
+	}
+	b = append(b, "\x1b["...)
+	b = strconv.AppendInt(b, int64(code), 10)
+	return append(b, 'm')
+}
+
+// appendColor appends the SGR sequence setting a truecolor foreground (38)
+// or background (48).
+func appendColor(b []byte, layer int, c Color) []byte {
+	b = append(b, "\x1b["...)
+	b = strconv.AppendInt(b, int64(layer), 10)
+	b = append(b, ";2;"...)
+	b = strconv.AppendInt(b, int64(c.R), 10)
+	b = append(b, ';')
+	b = strconv.AppendInt(b, int64(c.G), 10)
+	b = append(b, ';')
+	b = strconv.AppendInt(b, int64(c.B), 10)
+	return append(b, 'm')
+}
+
+func compareStyle(a, b cell) int {
+	return cmp.Or(
+		cmp.Compare(a.fg.R, b.fg.R), cmp.Compare(a.fg.G, b.fg.G), cmp.Compare(a.fg.B, b.fg.B),
+		compareBool(a.hasBg, b.hasBg),
+		cmp.Compare(a.bg.R, b.bg.R), cmp.Compare(a.bg.G, b.bg.G), cmp.Compare(a.bg.B, b.bg.B),
+		cmp.Compare(a.attrs, b.attrs),
+		compareBool(a.link, b.link),
+	)
+}
+
+func compareBool(a, b bool) int {
+	switch {
+	case a == b:
+		return 0
+	case a:
+		return 1
+	}
+	return -1
+}
+
+// clip keeps at most n cells of s, treating combining marks as part of the
+// preceding character.
+func clip(s string, n int) string {
+	cells := 0
+	for i, r := range s {
+		if unicode.In(r, unicode.Mn, unicode.Me) {
+			continue
+		}
+		if cells == n {
+			return s[:i]
+		}
+		cells++
+	}
+	return s
+}
+
+// eachCell calls visit with every cell's worth of s in turn: a character
+// and the combining marks after it.
+func eachCell(s string, visit func(text string)) {
+	start := -1
+	for i, r := range s {
+		if unicode.In(r, unicode.Mn, unicode.Me) {
+			continue
+		}
+		if start >= 0 {
+			visit(s[start:i])
+		}
+		start = i
+	}
+	if start >= 0 {
+		visit(s[start:])
+	}
+}
+
+// isWide reports whether r takes two cells: the East Asian wide and
+// fullwidth characters, and most emoji.
+func isWide(r rune) bool {
+	if r < 0x1100 {
+		return false
+	}
+	for _, w := range wideRanges {
+		if r >= w[0] && r <= w[1] {
+			return true
+		}
+	}
+	return false
+}
+
+var wideRanges = [][2]rune{
+	{0x1100, 0x115F},   // Hangul Jamo
+	{0x2E80, 0x303E},   // CJK radicals to CJK symbols and punctuation
+	{0x3041, 0x33FF},   // kana, bopomofo and CJK compatibility
+	{0x3400, 0x4DBF},   // CJK extension A
+	{0x4E00, 0x9FFF},   // CJK unified ideographs
+	{0xA000, 0xA4CF},   // Yi
+	{0xAC00, 0xD7A3},   // Hangul syllables
+	{0xF900, 0xFAFF},   // CJK compatibility ideographs
+	{0xFE30, 0xFE4F},   // CJK compatibility forms
+	{0xFF00, 0xFF60},   // fullwidth forms
+	{0xFFE0, 0xFFE6},   // fullwidth signs
+	{0x1F300, 0x1F64F}, // pictographs and emoticons
+	{0x1F900, 0x1F9FF}, // supplemental pictographs
+	{0x20000, 0x3FFFD}, // CJK extensions B onwards
+}
+
+// glyphWidth is how many cells the first character of s takes.
+func glyphWidth(s string) int {
+	for _, r := range s {
+		return cellWidth(isWide(r))
+	}
+	return 1
+}
+
+// glyphWidth is how many cells the first character of s takes on scr,
+// which draws every character narrow in ASCII mode.
+func (scr *Screen) glyphWidth(s string) int {
+	return cellWidth(glyphWidth(s) == 2 && !scr.ascii)
+}
+
+// fitWidth is the longest start of s that takes no more than n cells.
+func fitWidth(s string, n int) string {
+	cells := 0
+	for i, r := range s {
+		if unicode.In(r, unicode.Mn, unicode.Me) {
+			continue
+		}
+		if cells += cellWidth(isWide(r)); cells > n {
+			return s[:i]
+		}
+	}
+	return s
+}
+
+// cellCount is how many characters s has, not counting combining marks,
+// which is the cells it takes unless some are wide.
+func cellCount(s string) int {
+	cells := 0
+	for _, r := range s {
+		if !unicode.In(r, unicode.Mn, unicode.Me) {
+			cells++
+		}
+	}
+	return cells
+}
@@ -0,0 +1,101 @@
+package main
+
+import (
+	"bytes"
+	"io"
+	"regexp"
+	"strconv"
+	"testing"
+	"testing/quick"
+)
+
+// BenchmarkFlush measures diffing a full screen of changed cells and
+// emitting the escapes for them.
+func BenchmarkFlush(b *testing.B) {
+	sim, scr := headless(testScene(b).SimConfig, 200, 60, io.Discard)
+	sim.persist = true
+	for range 100 {
+		sim.update()
+		sim.draw(scr)
+	}
+	b.ResetTimer()
+	for range b.N {
+		clear(scr.shown)
+		scr.full = true
+		scr.flush()
+	}
+}
+
+func BenchmarkAppendColor(b *testing.B) {
+	buf := make([]byte, 0, 64)
+	for i := range b.N {
+		buf = appendColor(buf[:0], 38, Color{uint8(i), 128, 255})
+	}
+}
+
+var cursorMove = regexp.MustCompile(`\x1b\[(\d+);(\d+)H`)
+
+// TestMovesInGrid checks that whatever the size, every cursor movement the
+// screen sends lands on the grid.
+func TestMovesInGrid(t *testing.T) {
+	var out bytes.Buffer
+	prop := func(r propRun) bool {
+		return r.play(t, &out, func(sim *Sim, scr *Screen, size termSize) bool {
+			defer out.Reset()
+			for _, m := range cursorMove.FindAllSubmatch(out.Bytes(), -1) {
+				row, _ := strconv.Atoi(string(m[1]))
+				col, _ := strconv.Atoi(string(m[2]))
+				if row < 1 || col < 1 || row > size.height || col > size.width {
+					t.Logf("moved to row %d, column %d", row, col)
+					return false
+				}
+			}
+			return true
+		})
+	}
+	if err := quick.Check(prop, &quick.Config{MaxCount: 30}); err != nil {
+		t.Error(err)
+	}
+}
+
+// widePairs checks that every wide character in the screen's cells has its
+// tail after it, and every tail a wide character before it.
+func widePairs(t *testing.T, scr *Screen) {
+	t.Helper()
+	for y := range scr.height {
+		row := scr.cells[y*scr.width : (y+1)*scr.width]
+		for x, c := range row {
+			switch {
+			case c.wide && (x+1 >= len(row) || row[x+1].text != wideTail):
+				t.Fatalf("%s at (%d, %d) has no tail", c.text, x, y)
+			case c.text == wideTail && (x == 0 || !row[x-1].wide):
+				t.Fatalf("tail at (%d, %d) has no wide character", x, y)
+			case !c.wide && c.text != wideTail && glyphWidth(c.text) == 2:
+				t.Fatalf("%s at (%d, %d) isn't marked wide", c.text, x, y)
+			}
+		}
+	}
+}
+
+// TestPassesKeepWidePairs runs each pass that writes glyphs of its own over
+// frames of fullwidth glyphs.
+func TestPassesKeepWidePairs(t *testing.T) {
+	passes := map[string]Pass{
+		"noise":     noiseBlock{block{3, 2, 21, 5}},
+		"chromatic": &chromatic{},
+	}
+	for name, pass := range passes {
+		t.Run(name, func(t *testing.T) {
+			sc := testScene(t)
+			sc.charset = lookupCharset("fullwidth")
+			sim, scr := headless(sc.SimConfig, 40, 10, io.Discard)
+			for range propSteps {
+				sim.update()
+				sim.draw(scr)
+				pass.apply(sim, scr)
+				widePairs(t, scr)
+				scr.flush()
+			}
+		})
+	}
+}
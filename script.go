@@ -0,0 +1,254 @@
+//go:build lua
+
+package main
+
+import (
+	"fmt"
+	"os"
+	"os/exec"
+	"slices"
+	"strconv"
+	"strings"
+)
+
+// luaDriver runs in the lua interpreter next to the script. It loads the
+// script, says which hooks it has, and then answers requests from stdin on
+// stdout, a line at a time, until stdin closes:
+//
+//	tick <tick> <width> <height> <chaos> <speed> <n>
+//	<n lines of: <new> <x> <y> <heading> <speed> <r> <g> <b>>
+//
+// is answered with a line of <heading> <speed> <r> <g> <b> for every
+// stream, after on_spawn for the new ones and on_tick for the lot, and
+//
+//	cells <seconds> <n>
+//	<n lines of: <x> <y>>
+//
+// with a line for every cell: - to leave it, a brightness from 0 to 1 to
+// scale it by, or <r> <g> <b> to color it. A script that fails answers
+// error and the message instead.
+const luaDriver = `
+local function fail(err)
+  io.write("error ", (tostring(err):gsub("\n", " ")), "\n")
+  io.stdout:flush()
+end
+local ok, err = pcall(dofile, os.getenv("AAAA_SCRIPT"))
+if not ok then return fail(err) end
+local hooks = {}
+for _, name in ipairs({"on_tick", "on_spawn", "cell"}) do
+  if type(_G[name]) == "function" then hooks[#hooks + 1] = name end
+end
+io.write("hooks ", table.concat(hooks, " "), "\n")
+io.stdout:flush()
+local function fields()
+  local t = {}
+  for f in io.read("*l"):gmatch("%S+") do t[#t + 1] = tonumber(f) or f end
+  return t
+end
+local function byte(x)
+  return math.max(0, math.min(255, math.floor(x + 0.5)))
+end
+local function serve()
+  while io.read(0) do
+    local req = fields()
+    if req[1] == "tick" then
+      local engine = {tick = req[2], width = req[3], height = req[4], chaos = req[5], speed = req[6], streams = {}}
+      for i = 1, req[7] do
+        local f = fields()
+        local s = {x = f[2], y = f[3], heading = f[4], speed = f[5], r = f[6], g = f[7], b = f[8]}
+        if f[1] == 1 and on_spawn then on_spawn(s) end
+        engine.streams[i] = s
+      end
+      if on_tick then on_tick(engine) end
+      for _, s in ipairs(engine.streams) do
+        io.write(string.format("%.17g %.17g %d %d %d\n", s.heading, s.speed, byte(s.r), byte(s.g), byte(s.b)))
+      end
+    elseif req[1] == "cells" then
+      for _ = 1, req[3] do
+        local f = fields()
+        local a, b, c = cell(f[1], f[2], req[2])
+        if c then
+          io.write(string.format("%d %d %d\n", byte(a), byte(b), byte(c)))
+        elseif a then
+          io.write(string.format("%.17g\n", a))
+        else
+          io.write("-\n")
+        end
+      end
+    end
+    io.stdout:flush()
+  end
+end
+local ok, err = pcall(serve)
+if not ok then fail(err) end
+`
+
+// luaScript is a --script running in a lua interpreter of its own.
+type luaScript struct {
+	*child
+	hooks []string
+
+	// seen remembers the streams the script has seen, so on_spawn only
+	// gets new ones.
+	seen map[*Stream]bool
+}
+
+// loadScript starts a lua interpreter on path and adds what the script
+// hooks into to the scene: its on_tick and on_spawn steer the streams in
+// place of --movement, and its cell runs over every frame after --fx.
+func loadScript(path string, sc *scene) error {
+	if _, err := os.Stat(path); err != nil {
+		return err
+	}
+	lua, err := exec.LookPath("lua")
+	if err != nil {
+		return fmt.Errorf("--script needs lua on the PATH")
+	}
+	cmd := exec.Command(lua, "-e", luaDriver)
+	cmd.Env = append(os.Environ(), "AAAA_SCRIPT="+path)
+	c, err := startChild("script", cmd)
+	if err != nil {
+		return err
+	}
+	line, err := c.readLine()
+	if err != nil {
+		c.close()
+		return err
+	}
+	hooks, ok := strings.CutPrefix(line, "hooks")
+	if !ok {
+		c.close()
+		return fmt.Errorf("%s: lua said %q", path, line)
+	}
+	s := &luaScript{child: c, hooks: strings.Fields(hooks), seen: map[*Stream]bool{}}
+	if s.has("on_tick") || s.has("on_spawn") {
+		sc.movement = &scriptMovement{script: s, fallback: sc.movement}
+	}
+	if s.has("cell") {
+		sc.fx = append(sc.fx, &scriptCells{script: s})
+	}
+	return nil
+}
+
+func (s *luaScript) has(hook string) bool {
+	return slices.Contains(s.hooks, hook)
+}
+
+// scriptMovement steers the streams with a script's on_tick, after its
+// on_spawn has had the new ones, or with the fallback movement if it has
+// no on_tick or has failed.
+type scriptMovement struct {
+	script   *luaScript
+	fallback Movement
+}
+
+func (m *scriptMovement) steer(sim *Sim) {
+	if m.script.failed || !m.script.has("on_tick") {
+		m.fallback.steer(sim)
+	}
+	if m.script.failed {
+		return
+	}
+	if err := m.tick(sim); err != nil {
+		m.script.fail(err)
+	}
+}
+
+func (m *scriptMovement) tick(sim *Sim) error {
+	s := m.script
+	fmt.Fprintf(s.in, "tick %d %d %d %g %g %d\n", sim.tick, sim.width, sim.height, sim.chaos, sim.speed, len(sim.streams))
+	seen := make(map[*Stream]bool, len(sim.streams))
+	for _, st := range sim.streams {
+		isNew := 0
+		if !s.seen[st] {
+			isNew = 1
+		}
+		seen[st] = true
+		fmt.Fprintf(s.in, "%d %g %g %g %g %d %d %d\n", isNew, st.x, st.y, st.heading, st.speed, st.color.R, st.color.G, st.color.B)
+	}
+	s.seen = seen
+	if err := s.in.Flush(); err != nil {
+		return err
+	}
+	onTick := s.has("on_tick")
+	for _, st := range sim.streams {
+		f, err := s.readNumbers(5)
+		if err != nil {
+			return err
+		}
+		st.speed = max(0, f[1])
+		st.color = Color{uint8(f[2]), uint8(f[3]), uint8(f[4])}
+		if onTick {
+			st.heading = f[0]
+			st.turn(1, 0.5)
+		}
+	}
+	return nil
+}
+
+// scriptCells runs a script's cell over every cell with something in it.
+type scriptCells struct {
+	script *luaScript
+}
+
+func (p *scriptCells) apply(sim *Sim, scr *Screen) {
+	if p.script.failed {
+		return
+	}
+	if err := p.cells(sim, scr); err != nil {
+		p.script.fail(err)
+	}
+}
+
+func (p *scriptCells) cells(sim *Sim, scr *Screen) error {
+	s := p.script
+	var cells []*cell
+	var at [][2]int
+	for y := range scr.window.height {
+		row := scr.row(y)
+		for x := range row {
+			if row[x].text != "" && row[x].text != " " && row[x].text != wideTail {
+				cells = append(cells, &row[x])
+				at = append(at, [2]int{x, y})
+			}
+		}
+	}
+	fmt.Fprintf(s.in, "cells %g %d\n", float64(sim.tick)*tickInterval.Seconds(), len(cells))
+	for _, xy := range at {
+		fmt.Fprintf(s.in, "%d %d\n", xy[0], xy[1])
+	}
+	if err := s.in.Flush(); err != nil {
+		return err
+	}
+	for _, c := range cells {
+		line, err := s.readLine()
+		if err != nil {
+			return err
+		}
+		f := strings.Fields(line)
+		switch len(f) {
+		case 1:
+			if f[0] == "-" {
+				continue
+			}
+			v, err := strconv.ParseFloat(f[0], 64)
+			if err != nil {
+				return fmt.Errorf("script's cell answered %q", line)
+			}
+			dimCell(c, min(max(v, 0), 1))
+		case 3:
+			var rgb [3]uint8
+			for i := range rgb {
+				v, err := strconv.ParseUint(f[i], 10, 8)
+				if err != nil {
+					return fmt.Errorf("script's cell answered %q", line)
+				}
+				rgb[i] = uint8(v)
+			}
+			c.fg = Color{rgb[0], rgb[1], rgb[2]}.dim(scr.brightness)
+		default:
+			return fmt.Errorf("script's cell answered %q", line)
+		}
+	}
+	return nil
+}
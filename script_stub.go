@@ -0,0 +1,9 @@
+//go:build !lua
+
+package main
+
+import "errors"
+
+func loadScript(path string, sc *scene) error {
+	return errors.New("built without Lua support; rebuild with -tags lua")
+}
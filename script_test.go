@@ -0,0 +1,56 @@
+//go:build lua
+
+package main
+
+import (
+	"flag"
+	"io"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"testing"
+)
+
+// TestScript runs a script with every hook and checks the streams and
+// cells come out the way it says.
+func TestScript(t *testing.T) {
+	if _, err := exec.LookPath("lua"); err != nil {
+		t.Skip("no lua on the PATH")
+	}
+	path := filepath.Join(t.TempDir(), "mode.lua")
+	script := `
+function on_spawn(s) s.r, s.g, s.b = 255, 0, 0 end
+function on_tick(engine)
+  for _, s in ipairs(engine.streams) do s.heading = 0 end
+end
+function cell(x, y, t) return 0, 255, 0 end
+`
+	if err := os.WriteFile(path, []byte(script), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	var o runOptions
+	o.register(flag.NewFlagSet("test", flag.ContinueOnError))
+	o.background, o.seed, o.script = "dark", 1, path
+	sc, err := o.scene()
+	if err != nil {
+		t.Fatal(err)
+	}
+	sim, scr := headless(sc.SimConfig, 80, 24, io.Discard)
+	for range 50 {
+		sim.update()
+		sim.draw(scr)
+	}
+	if len(sim.streams) == 0 {
+		t.Fatal("no streams")
+	}
+	for _, s := range sim.streams {
+		if s.color != (Color{255, 0, 0}) || s.heading != 0 {
+			t.Fatalf("stream is %v heading %v, want red heading 0", s.color, s.heading)
+		}
+	}
+	for _, c := range scr.cells {
+		if c.text != "" && c.text != " " && c.text != wideTail && c.fg != (Color{0, 255, 0}.dim(scr.brightness)) {
+			t.Fatalf("cell %q is %v, want green", c.text, c.fg)
+		}
+	}
+}
@@ -0,0 +1,112 @@
+package main
+
+import (
+	"fmt"
+	"slices"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// season is a stretch of the year that --seasonal dresses the animation up
+// for, by applying settings on top of the rest from the config file. The
+// config file adds seasons, or replaces these by name, with [season.name]
+// tables:
+//
+//	[season.spring]
+//	dates = "03-20..04-10"
+//	theme = "matrix"
+//
+// dates is the first and last day, as month-day; a season can run over
+// the new year, such as "12-30..01-02".
+type season struct {
+	name     string
+	from, to monthDay
+	settings []setting
+}
+
+var seasons = []season{
+	{"halloween", monthDay{10, 20}, monthDay{10, 31}, []setting{{key: "theme", value: "pumpkin"}, {key: "charset", value: "runes"}}},
+	{"winter", monthDay{12, 18}, monthDay{12, 31}, []setting{{key: "theme", value: "holly"}}},
+	{"valentine", monthDay{2, 10}, monthDay{2, 14}, []setting{{key: "theme", value: "vapor"}}},
+}
+
+// monthDay is a day of any year.
+type monthDay struct{ month, day int }
+
+func (d monthDay) before(e monthDay) bool {
+	return d.month < e.month || d.month == e.month && d.day < e.day
+}
+
+// parseMonthDay reads a day as month-day, such as 10-31.
+func parseMonthDay(s string) (monthDay, bool) {
+	month, day, ok := strings.Cut(s, "-")
+	m, err1 := strconv.Atoi(month)
+	d, err2 := strconv.Atoi(day)
+	if !ok || err1 != nil || err2 != nil || m < 1 || m > 12 || d < 1 || d > 31 {
+		return monthDay{}, false
+	}
+	return monthDay{m, d}, true
+}
+
+// covers reports whether the season includes the day t falls on.
+func (s *season) covers(t time.Time) bool {
+	d := monthDay{int(t.Month()), t.Day()}
+	if s.to.before(s.from) {
+		return !d.before(s.from) || !s.to.before(d)
+	}
+	return !d.before(s.from) && !s.to.before(d)
+}
+
+// configSeasons gathers the [season.name] tables of the config file.
+func configSeasons(settings []setting) ([]season, error) {
+	var found []season
+	for _, st := range settings {
+		if st.season == "" {
+			continue
+		}
+		i := len(found) - 1
+		if i < 0 || found[i].name != st.season {
+			found = append(found, season{name: st.season})
+			i++
+		}
+		s := &found[i]
+		if st.key != "dates" {
+			s.settings = append(s.settings, st)
+			continue
+		}
+		from, to, _ := strings.Cut(st.value, "..")
+		var ok1, ok2 bool
+		s.from, ok1 = parseMonthDay(from)
+		s.to, ok2 = parseMonthDay(to)
+		if !ok1 || !ok2 {
+			return nil, fmt.Errorf("config line %d, column %d: bad dates %q (want month-day..month-day, such as 10-20..10-31)", st.line, st.column, st.value)
+		}
+	}
+	for _, s := range found {
+		if s.from == (monthDay{}) {
+			return nil, fmt.Errorf("config: season %s has no dates", s.name)
+		}
+	}
+	return found, nil
+}
+
+// currentSeason is the season t falls in, from the config file's before
+// the built-in ones, or nil for none.
+func currentSeason(settings []setting, t time.Time) (*season, error) {
+	found, err := configSeasons(settings)
+	if err != nil {
+		return nil, err
+	}
+	for _, s := range seasons {
+		if !slices.ContainsFunc(found, func(f season) bool { return f.name == s.name }) {
+			found = append(found, s)
+		}
+	}
+	for i := range found {
+		if found[i].covers(t) {
+			return &found[i], nil
+		}
+	}
+	return nil, nil
+}
@@ -0,0 +1,107 @@
+package main
+
+import (
+	"io"
+	"sync"
+	"time"
+)
+
+// maxClients is how many clients --serve-telnet or --serve-web animates at
+// once; any more are turned away.
+const maxClients = 64
+
+// clientWriteTimeout is how long a client gets to take a frame before it's
+// dropped.
+const clientWriteTimeout = 10 * time.Second
+
+// server is what --serve-telnet and --serve-web have in common: the options
+// every client's animation is built from, and a count of clients.
+type server struct {
+	o     *runOptions
+	slots chan struct{}
+}
+
+// newServer checks the options by building a scene from them, so bad ones
+// fail before listening. The clients' terminals can't be asked about
+// their backgrounds, and the server's own isn't theirs, so it's dark
+// unless --background says.
+func newServer(o *runOptions) (*server, error) {
+	if o.background == "auto" {
+		o.background = "dark"
+	}
+	if _, err := o.scene(); err != nil {
+		return nil, err
+	}
+	return &server{o: o, slots: make(chan struct{}, maxClients)}, nil
+}
+
+// admit takes a client slot, reporting false if they're all taken. A
+// client that's admitted has to call leave when it's done.
+func (s *server) admit() bool {
+	select {
+	case s.slots <- struct{}{}:
+		return true
+	default:
+		return false
+	}
+}
+
+func (s *server) leave() { <-s.slots }
+
+// animate runs an animation of its own for a client, drawing to w at the
+// size that size reports, until input sends Quit or writing to w fails.
+// input reads from the client, and stops sending once done is closed.
+func (s *server) animate(w io.Writer, size func() (int, int), input func(commands chan<- Command, done <-chan struct{})) error {
+	sc, err := s.o.scene()
+	if err != nil {
+		return err
+	}
+	scr := newScreen(w)
+	scr.enter()
+	defer scr.leave()
+
+	commands := make(chan Command, 1)
+	done := make(chan struct{})
+	defer close(done)
+	go input(commands, done)
+
+	r := &renderer{cols: 1, rows: 1, scr: scr, size: size, sims: []*Sim{newSim(sc.SimConfig)}}
+	return r.run(commands)
+}
+
+// The largest window size a client can report. Anyone can connect and
+// claim any size, and the screen allocates a cell for every one of it.
+const (
+	maxClientWidth  = 1000
+	maxClientHeight = 500
+)
+
+// clientSize is a client's window size, as it last reported it, cut down
+// to maxClientWidth by maxClientHeight.
+type clientSize struct {
+	mu            sync.Mutex
+	width, height int
+}
+
+func (c *clientSize) get() (int, int) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	return c.width, c.height
+}
+
+func (c *clientSize) set(width, height int) {
+	if width <= 0 || height <= 0 {
+		return
+	}
+	c.mu.Lock()
+	c.width, c.height = min(width, maxClientWidth), min(height, maxClientHeight)
+	c.mu.Unlock()
+}
+
+// sendQuit tells animate the client is gone, unless it already stopped.
+func sendQuit(commands chan<- Command, done <-chan struct{}) {
+	select {
+	case commands <- Quit{}:
+	case <-done:
+	}
+}
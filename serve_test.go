@@ -0,0 +1,92 @@
+package main
+
+import (
+	"bufio"
+	"net"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+)
+
+// TestTelnetHugeWindow checks that a telnet client claiming a window of
+// 65535x65535 gets no more than the largest size there is.
+func TestTelnetHugeWindow(t *testing.T) {
+	server, client := net.Pipe()
+	c := &telnetClient{conn: server, size: clientSize{width: 80, height: 24}}
+	commands := make(chan Command, 1)
+	go c.readInput(commands, make(chan struct{}))
+
+	// 65535 is all 255s, each doubled as the protocol escapes them.
+	naws := []byte{telnetIAC, telnetSB, telnetNAWS}
+	for range 8 {
+		naws = append(naws, telnetIAC)
+	}
+	naws = append(naws, telnetIAC, telnetSE)
+	if _, err := client.Write(naws); err != nil {
+		t.Fatal(err)
+	}
+	client.Close()
+	<-commands
+
+	if w, h := c.size.get(); w != maxClientWidth || h != maxClientHeight {
+		t.Errorf("window is %dx%d, want %dx%d", w, h, maxClientWidth, maxClientHeight)
+	}
+}
+
+// TestWebHugeWindow is the same for a page claiming a billion columns and
+// rows.
+func TestWebHugeWindow(t *testing.T) {
+	server, client := net.Pipe()
+	c := &webClient{conn: server, r: bufio.NewReader(server), size: clientSize{width: 80, height: 24}}
+	commands := make(chan Command, 1)
+	go c.readInput(commands, make(chan struct{}))
+
+	msg := `{"cols":1000000000,"rows":1000000000}`
+	// A masked text frame; a zero mask leaves the payload as it is.
+	frame := append([]byte{0x80 | wsText, 0x80 | byte(len(msg)), 0, 0, 0, 0}, msg...)
+	if _, err := client.Write(frame); err != nil {
+		t.Fatal(err)
+	}
+	client.Close()
+	<-commands
+
+	if w, h := c.size.get(); w != maxClientWidth || h != maxClientHeight {
+		t.Errorf("window is %dx%d, want %dx%d", w, h, maxClientWidth, maxClientHeight)
+	}
+}
+
+// TestWebOrigin checks that a page from another site can't open the
+// WebSocket, while our own page and clients that aren't browsers can get
+// past the check.
+func TestWebOrigin(t *testing.T) {
+	for _, tc := range []struct {
+		origin string
+		ok     bool
+	}{
+		{"", true},
+		{"http://localhost:8080", true},
+		{"http://LocalHost:8080", true},
+		{"http://localhost:8081", false},
+		{"https://evil.example", false},
+		{"null", false},
+	} {
+		r := httptest.NewRequest("GET", "http://localhost:8080/ws", nil)
+		if tc.origin != "" {
+			r.Header.Set("Origin", tc.origin)
+		}
+		if ok := sameOrigin(r); ok != tc.ok {
+			t.Errorf("Origin %q: sameOrigin = %v, want %v", tc.origin, ok, tc.ok)
+		}
+	}
+
+	r := httptest.NewRequest("GET", "http://localhost:8080/ws", nil)
+	r.Header.Set("Origin", "https://evil.example")
+	r.Header.Set("Upgrade", "websocket")
+	r.Header.Set("Connection", "Upgrade")
+	r.Header.Set("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+	w := httptest.NewRecorder()
+	(&server{}).serveWebSocket(w, r)
+	if w.Code != http.StatusForbidden {
+		t.Errorf("another site's page got %d, want %d", w.Code, http.StatusForbidden)
+	}
+}
@@ -0,0 +1,551 @@
+package main
+
+import (
+	"math"
+	"math/rand"
+	"slices"
+	"strings"
+	"time"
+)
+
+const (
+	tickInterval = 100 * time.Millisecond
+
+	forkChance = 0.01
+	fadeTicks  = 10
+
+	// maxChaos is the chaos multiplier at full eruption.
+	maxChaos = 5
+
+	// burstCeiling caps streams spawned on demand at this many times
+	// the configured maximum.
+	burstCeiling = 4
+
+	gravityStrength    = 0.12
+	gravityRestitution = 0.7
+
+	// washChance is the chance per tick, scaled by chaos, that a palette's
+	// wash starts sweeping down the screen, washBand rows tall.
+	washChance = 0.003
+	washBand   = 3
+)
+
+type SimConfig struct {
+	mode       Mode
+	palette    *Palette
+	charset    *Charset
+	movement   Movement
+	gravity    bool
+	collisions Collisions
+	maxStreams int
+
+	// density is how many streams there are per 1000 cells when
+	// maxStreams is 0 and the screen's size decides.
+	density float64
+
+	// lifetime is the average number of ticks a stream lives for.
+	lifetime int
+
+	zalgo int
+
+	// words, when set, gives every stream a word from the corpus to print
+	// instead of random glyph strings, each letter swapped for a glyph with
+	// probability glitch.
+	words  []string
+	glitch float64
+
+	// trails is how the cells behind each stream are drawn, and
+	// orientation which way the strings at their heads run.
+	trails      Trails
+	orientation Orientation
+
+	// deathBursts has streams shed a shower of glyphs as they die, of old
+	// age or merged into another.
+	deathBursts bool
+
+	// evolve has forks inherit their parents' traits, mutated, and the
+	// oldest streams fork the most.
+	evolve bool
+
+	// linkChance is the chance of a stream's head being a hyperlink.
+	linkChance float64
+
+	// mutate, when positive, gives each stream without a word a string of
+	// its own to print, kept from tick to tick, with this chance each tick
+	// that it mutates by a glyph.
+	mutate float64
+
+	overlays   []Overlay
+	modulators []Modulator
+
+	// params are the base params the sim starts from.
+	params Params
+
+	// seed seeds the sim's random numbers; 0 picks one from the clock.
+	seed int64
+
+	// persist leaves everything drawn on screen until drawn over, rather
+	// than erasing what each frame doesn't draw again.
+	persist bool
+
+	// mirror reflects the streams and particles, though not the overlays.
+	mirror Mirror
+
+	// glitchEvents is the chance per tick of a glitch event, and
+	// glitchStrength how bad they get, from 0 to 1.
+	glitchEvents   float64
+	glitchStrength float64
+
+	// fx are the post-processing passes run over every frame.
+	fx []Pass
+
+	// backend, if set, draws the streams and particles as pixels instead
+	// of glyphs.
+	backend Backend
+}
+
+type Sim struct {
+	SimConfig
+
+	// base holds the configured params; the embedded Params are the ones in
+	// effect this tick.
+	base Params
+	Params
+
+	width, height int
+	streams       []*Stream
+	particles     []*Particle
+	tick          int
+
+	// washRow is the row below the wash band sweeping down the screen, -1
+	// when there's no wash.
+	washRow int
+
+	// scratch is reused to build zalgo'd glyphs while drawing.
+	scratch []byte
+
+	// rng is the sim's one source of random numbers, handed down to
+	// everything it updates and draws, so the same seed plays the same
+	// animation. source is the state behind it.
+	rng    *rand.Rand
+	source source
+
+	// raster is what the backend draws from, if there is one.
+	raster Raster
+
+	// boosts are pushed on for a while, by the panic key for one.
+	boosts []Boost
+
+	// heat counts the streams through each cell, row by row, and hottest
+	// is the most any cell has had. heatView draws them instead of the
+	// streams.
+	heat     []uint32
+	hottest  uint32
+	heatView bool
+
+	// thinning is the share of the streams cut to keep within --max-cpu.
+	thinning float64
+
+	// spawned counts the streams ever spawned, forks and bursts included,
+	// and touched the cells streams have been through, each counted again
+	// after a resize, for the stats on exit.
+	spawned int
+	touched int
+}
+
+func newSim(cfg SimConfig) *Sim {
+	src := newSource(cfg.seed)
+	sim := &Sim{SimConfig: cfg, base: cfg.params, Params: cfg.params, washRow: -1, source: src, rng: rand.New(src)}
+	sim.resetHeat()
+	sim.mode.init(sim)
+	return sim
+}
+
+// newRand returns a generator seeded with seed, or with the clock if it's 0.
+func newRand(seed int64) *rand.Rand {
+	return rand.New(newSource(seed))
+}
+
+func (sim *Sim) resize(width, height int) {
+	if width == sim.width && height == sim.height {
+		return
+	}
+	logger.Debug("resize", "width", width, "height", height)
+	wasTiny := sim.tiny()
+	sim.width, sim.height = width, height
+	if tiny := sim.tiny(); tiny != wasTiny {
+		logger.Info("too small to animate", "tiny", tiny, "width", width, "height", height)
+	}
+	sim.resetHeat()
+	sim.mode.onResize(sim, width, height)
+}
+
+// reconfigure switches to cfg while the animation runs. The streams already
+// out stay, taking on the new palette and charset.
+func (sim *Sim) reconfigure(cfg SimConfig) {
+	old := sim.mode
+	sim.SimConfig = cfg
+	if cfg.mode != old {
+		cfg.mode.init(sim)
+	}
+	sim.base = cfg.params
+	sim.setPalette(cfg.palette)
+	sim.setCharset(cfg.charset)
+}
+
+// setPalette switches palettes, recoloring the streams already out.
+func (sim *Sim) setPalette(p *Palette) {
+	sim.palette = p
+	for _, s := range sim.streams {
+		s.color = p.randomColor(sim.rng)
+		s.bg = p.randomBackground(sim.rng)
+		s.attrs = p.randomAttrs(sim.rng)
+	}
+}
+
+// setCharset switches charsets, giving the streams already out fresh glyphs.
+func (sim *Sim) setCharset(cs *Charset) {
+	sim.charset = cs
+	for _, s := range sim.streams {
+		s.glyphs = streamGlyphs(sim.rng, cs)
+	}
+}
+
+func (sim *Sim) update() {
+	defer stage("simulate")()
+	if sim.tiny() {
+		sim.tick++
+		return
+	}
+	sim.modulate()
+	sim.mode.tick(sim)
+	sim.updateWash()
+	for _, o := range sim.overlays {
+		o.update(sim)
+	}
+	if sim.glitchEvents > 0 && sim.rng.Float64() < sim.glitchEvents {
+		sim.boost(newGlitchEvent(sim, sim.glitchStrength))
+	}
+	for _, b := range sim.boosts {
+		b.update(sim)
+	}
+	sim.boosts = slices.DeleteFunc(sim.boosts, Boost.over)
+	sim.tick++
+}
+
+// boost pushes b onto the sim's boosts.
+func (sim *Sim) boost(b Boost) {
+	sim.boosts = append(sim.boosts, b)
+}
+
+// populate ages every stream, retires the ones that have outlived their
+// lifetime, forks a few survivors and spawns newcomers while there is room.
+func (sim *Sim) populate() {
+	alive := sim.streams[:0]
+	var children []*Stream
+	for _, s := range sim.streams {
+		s.age++
+		if s.age >= s.lifetime {
+			if sim.deathBursts {
+				sim.shower(s)
+			}
+			continue
+		}
+		alive = append(alive, s)
+		chance := forkChance * sim.chaos
+		if sim.evolve {
+			chance *= sim.evolveForkChance(s)
+		}
+		if sim.rng.Float64() < chance {
+			child := s.fork(sim.rng, sim.newLifetime())
+			if sim.evolve {
+				sim.inherit(child, s)
+			}
+			children = append(children, child)
+		}
+	}
+	clear(sim.streams[len(alive):])
+	sim.streams = alive
+	capacity := sim.capacity()
+	limit := int(float64(capacity) * sim.chaos)
+	for _, child := range children {
+		if len(sim.streams) >= limit {
+			break
+		}
+		sim.streams = append(sim.streams, child)
+		sim.spawned++
+	}
+	// Spawning capacity/lifetime streams a tick, on average, keeps the
+	// population near capacity.
+	spawns := float64(capacity) / float64(sim.lifetime) * sim.chaos
+	for ; len(sim.streams) < limit && sim.rng.Float64() < spawns; spawns-- {
+		s := newStream(sim.rng, sim.width, sim.height, sim.newLifetime(), sim.palette, sim.charset)
+		s.orientation = sim.orientation.pick(sim.rng)
+		if sim.linkChance > 0 {
+			s.link = sim.rng.Float64() < sim.linkChance
+		}
+		if len(sim.words) > 0 {
+			s.word = sim.words[sim.rng.Intn(len(sim.words))]
+		}
+		sim.streams = append(sim.streams, s)
+		sim.spawned++
+	}
+}
+
+// capacity is how many streams can be alive at once at chaos 1: maxStreams,
+// or if that's 0, as many as density gives the screen, less any thinning.
+func (sim *Sim) capacity() int {
+	n := float64(sim.maxStreams)
+	if sim.maxStreams == 0 {
+		n = sim.density * float64(sim.width*sim.height) / 1000
+	}
+	return max(1, int(math.Round(n*(1-sim.thinning))))
+}
+
+// spawnBurst throws a handful of streams printing glyph out of a random
+// spot, on top of the usual population up to a hard ceiling.
+func (sim *Sim) spawnBurst(glyph string) {
+	const size = 5
+	if sim.tiny() || len(sim.streams)+size > burstCeiling*sim.capacity() {
+		return
+	}
+	glyphs := newWeighted(Choice[string]{glyph, Common})
+	x, y := sim.rng.Float64()*float64(sim.width-1), sim.rng.Float64()*float64(sim.height-1)
+	for i := range size {
+		s := newStream(sim.rng, sim.width, sim.height, sim.newLifetime(), sim.palette, glyphs)
+		s.orientation = sim.orientation.pick(sim.rng)
+		s.x, s.y = x, y
+		s.heading = 2 * math.Pi * float64(i) / size
+		s.vx, s.vy = headingVector(s.heading, s.speed)
+		sim.streams = append(sim.streams, s)
+		sim.spawned++
+	}
+}
+
+// newLifetime picks a lifetime within half of the configured average either
+// way, so streams spawned together don't all die together.
+func (sim *Sim) newLifetime() int {
+	return sim.lifetime/2 + sim.rng.Intn(sim.lifetime+1)
+}
+
+func (sim *Sim) blocked(x, y int) bool {
+	for _, o := range sim.overlays {
+		if obstacle, ok := o.(Obstacle); ok && obstacle.blocks(x, y) {
+			return true
+		}
+	}
+	return false
+}
+
+// applyGravity hands the vertical axis over to gravity: streams keep their
+// horizontal steering but arc and fall, and anything that has settled on the
+// floor is thrown back up.
+func (sim *Sim) applyGravity() {
+	floor := float64(sim.height - 1)
+	for _, s := range sim.streams {
+		s.ay = gravityStrength
+		if s.y >= floor && math.Abs(s.vy) < 2*gravityStrength {
+			s.vy = -(1 + sim.rng.Float64()*1.5)
+		}
+	}
+}
+
+// draw paints the mode, with the wash, the boosts and then the overlays on
+// top, and runs the fx over the lot.
+func (sim *Sim) draw(scr *Screen) {
+	defer stage("draw")()
+	if sim.tiny() {
+		scr.brightness, scr.persist, scr.mirror = 1, false, MirrorOff
+		sim.drawTiny(scr)
+		return
+	}
+	scr.brightness = sim.brightness
+	scr.persist = sim.persist
+	scr.mirror = sim.mirror
+	if sim.heatView {
+		sim.drawHeat(scr)
+	} else {
+		sim.mode.render(sim, scr)
+	}
+	sim.drawWash(scr)
+	scr.mirror = MirrorOff
+	for _, b := range sim.boosts {
+		b.draw(sim, scr)
+	}
+	for _, o := range sim.overlays {
+		o.draw(sim, scr)
+	}
+	for _, p := range sim.fx {
+		p.apply(sim, scr)
+	}
+}
+
+// drawCells paints each stream Matrix-style: its trail in its own color,
+// dimming with age, and the head in the palette's bright primary.
+func (sim *Sim) drawCells(scr *Screen) {
+	for _, s := range sim.streams {
+		scr.attrs = s.attrs
+		for i, c := range s.trail {
+			brightness := float64(i+1) / float64(len(s.trail)+1)
+			var glyph string
+			if sim.trails == TrailsLines {
+				glyph = trailGlyph(s, i)
+			} else {
+				glyph = sim.glyph(scr, s)
+			}
+			scr.put(c[0], c[1], glyph, s.color.dim(brightness))
+		}
+	}
+	for _, s := range sim.streams {
+		x, y := s.cell()
+		length := maxStringLength
+		if left := s.lifetime - s.age; left < fadeTicks {
+			length = max(1, maxStringLength*left/fadeTicks)
+		}
+		scr.attrs, scr.link = s.attrs, s.link
+		sim.drawHead(scr, s, x, y, length)
+	}
+	scr.attrs, scr.link = 0, false
+	for _, p := range sim.particles {
+		x, y := int(math.Round(p.x)), int(math.Round(p.y))
+		if sim.palette.inverse {
+			scr.putBg(x, y, p.glyph, Color{}, p.shade())
+		} else {
+			scr.put(x, y, p.glyph, p.shade())
+		}
+	}
+}
+
+// drawRaster plots each stream as a line through its trail to the head,
+// and each particle as a pixel, at the backend's finer resolution.
+func (sim *Sim) drawRaster(scr *Screen) {
+	sx, sy := sim.backend.scale()
+	r := &sim.raster
+	r.reset(sim.width*sx, sim.height*sy)
+	// pixel is the pixel at the cell coordinates x, y; whole numbers are
+	// the middle of a cell.
+	pixel := func(x, y float64) (int, int) {
+		return int(math.Floor((x + 0.5) * float64(sx))), int(math.Floor((y + 0.5) * float64(sy)))
+	}
+	for _, s := range sim.streams {
+		var px, py int
+		for i, c := range s.trail {
+			x, y := pixel(float64(c[0]), float64(c[1]))
+			if i > 0 {
+				r.line(px, py, x, y, s.color.dim(float64(i+1)/float64(len(s.trail)+1)))
+			}
+			px, py = x, y
+		}
+		x, y := pixel(s.x, s.y)
+		if len(s.trail) > 0 {
+			r.line(px, py, x, y, s.color)
+		}
+		r.plot(x, y, sim.palette.primary)
+	}
+	for _, p := range sim.particles {
+		x, y := pixel(p.x, p.y)
+		r.plot(x, y, p.shade())
+	}
+	sim.backend.render(scr, r)
+}
+
+// drawHead prints a stream's head at (x, y): its word, or a random run of
+// its glyphs, at most length cells long, running the way the stream's
+// orientation says. Glyphs go to the screen one by one rather than joined
+// into a string, so there's nothing to allocate.
+func (sim *Sim) drawHead(scr *Screen, s *Stream, x, y, length int) {
+	d := s.direction()
+	// put prints text as the next cell of the head, along from the last by
+	// the last's width.
+	at := 0
+	put := func(text string, fg Color) {
+		x, y := x+at*d.dx, y+at*d.dy
+		if s.bg != nil {
+			scr.putBg(x, y, text, fg, *s.bg)
+		} else {
+			scr.put(x, y, text, fg)
+		}
+		at += max(glyphWidth(text)*abs(d.dx), abs(d.dy))
+	}
+	if s.word != "" {
+		word := zalgo(sim.rng, glitch(sim.rng, clip(s.word, length), sim.glitch, s.glyphs), sim.zalgo)
+		if sim.palette.gradient == GradientOff && d == (step{1, 0}) {
+			put(word, sim.palette.primary)
+			return
+		}
+		i, n := 0, cellCount(word)
+		eachCell(word, func(text string) {
+			put(text, sim.headColor(s, i, n))
+			i++
+		})
+		return
+	}
+	if s.text != nil {
+		n := min(len(s.text), length)
+		for i, g := range s.text[:n] {
+			put(sim.zalgoGlyph(scr, g), sim.headColor(s, i, n))
+		}
+		return
+	}
+	n := sim.rng.Intn(length) + 1
+	for i := range n {
+		put(sim.glyph(scr, s), sim.headColor(s, i, n))
+	}
+}
+
+// headFade is how bright the back of a head is with GradientFade.
+const headFade = 0.35
+
+// headColor is the color of cell i of the n in a stream's head, by the
+// palette's gradient.
+func (sim *Sim) headColor(s *Stream, i, n int) Color {
+	switch sim.palette.gradient {
+	case GradientFade:
+		return sim.palette.primary.mix(s.color.dim(headFade), float64(i)/float64(max(1, n-1)))
+	case GradientRainbow:
+		return rainbow(float64(i)/float64(maxStringLength) + float64(sim.tick)/100)
+	}
+	return sim.palette.primary
+}
+
+// glyph picks one of the stream's glyphs, zalgo'd.
+func (sim *Sim) glyph(scr *Screen, s *Stream) string {
+	return sim.zalgoGlyph(scr, s.glyphs.pick(sim.rng))
+}
+
+// zalgoGlyph attaches combining marks to g, in the reused scratch buffer.
+func (sim *Sim) zalgoGlyph(scr *Screen, g string) string {
+	if sim.zalgo <= 0 {
+		return g
+	}
+	sim.scratch = appendZalgo(sim.rng, sim.scratch[:0], g, sim.zalgo)
+	return scr.intern(sim.scratch)
+}
+
+func (sim *Sim) updateWash() {
+	switch {
+	case sim.washRow >= 0:
+		sim.washRow += 2
+		if sim.washRow > sim.height+washBand+2 {
+			sim.washRow = -1
+		}
+	case sim.palette.wash != nil && sim.rng.Float64() < washChance*sim.chaos:
+		sim.washRow = 0
+	}
+}
+
+// drawWash paints the wash band in the palette's wash color and blanks the
+// rows it has just left, so it wipes the screen clean as it goes.
+func (sim *Sim) drawWash(scr *Screen) {
+	if sim.washRow < 0 || sim.palette.wash == nil {
+		return
+	}
+	blank := strings.Repeat(" ", sim.width)
+	for y := sim.washRow - washBand - 2; y < sim.washRow; y++ {
+		if y < sim.washRow-washBand {
+			scr.put(0, y, blank, sim.palette.primary)
+		} else {
+			scr.putBg(0, y, blank, sim.palette.primary, *sim.palette.wash)
+		}
+	}
+}
@@ -0,0 +1,262 @@
+package main
+
+import (
+	"bytes"
+	"flag"
+	"fmt"
+	"io"
+	"math/rand"
+	"reflect"
+	"strings"
+	"testing"
+	"testing/quick"
+)
+
+// BenchmarkRand compares a draw from the shared global generator with one
+// from a generator owned by the sim.
+func BenchmarkRand(b *testing.B) {
+	b.Run("global", func(b *testing.B) {
+		for range b.N {
+			rand.Float64()
+		}
+	})
+	b.Run("owned", func(b *testing.B) {
+		rng := newRand(1)
+		for range b.N {
+			rng.Float64()
+		}
+	})
+}
+
+// testScene is the default scene on a dark background with a fixed seed.
+func testScene(tb testing.TB) *scene {
+	var o runOptions
+	o.register(flag.NewFlagSet("test", flag.ContinueOnError))
+	o.background, o.seed = "dark", 1
+	sc, err := o.scene()
+	if err != nil {
+		tb.Fatal(err)
+	}
+	return sc
+}
+
+func BenchmarkFrame(b *testing.B) {
+	sim, scr := headless(testScene(b).SimConfig, 200, 60, io.Discard)
+	b.ResetTimer()
+	for range b.N {
+		sim.update()
+		sim.draw(scr)
+		scr.flush()
+	}
+}
+
+func BenchmarkUpdate(b *testing.B) {
+	sim, _ := headless(testScene(b).SimConfig, 200, 60, io.Discard)
+	for range 100 {
+		sim.update()
+	}
+	b.ResetTimer()
+	for range b.N {
+		sim.update()
+	}
+}
+
+// BenchmarkDraw measures generating the glyphs and filling in the cells,
+// with nothing sent.
+func BenchmarkDraw(b *testing.B) {
+	sim, scr := headless(testScene(b).SimConfig, 200, 60, io.Discard)
+	for range 100 {
+		sim.update()
+	}
+	b.ResetTimer()
+	for range b.N {
+		sim.draw(scr)
+	}
+}
+
+// termSize is a terminal size for testing/quick to make up: as often as not
+// tiny, down to 1x1, and now and then huge, up to propHuge.
+type termSize struct{ width, height int }
+
+const propHuge = 500
+
+func (termSize) Generate(rng *rand.Rand, size int) reflect.Value {
+	pick := func() int {
+		switch rng.Intn(4) {
+		case 0:
+			return 1 + rng.Intn(2)
+		case 1:
+			return 1 + rng.Intn(propHuge)
+		}
+		return 1 + rng.Intn(100)
+	}
+	// Both sides huge is more cells than the tests can get through.
+	s := termSize{pick(), pick()}
+	if s.width > 100 && s.height > 100 {
+		s.height = 1 + rng.Intn(100)
+	}
+	return reflect.ValueOf(s)
+}
+
+// propRun is a run for testing/quick to make up: a seed, the movement and
+// collisions to run with, and the sizes to run at in turn, as though the
+// terminal were resized, each for propSteps ticks.
+type propRun struct {
+	seed       int64
+	movement   string
+	collisions string
+	sizes      []termSize
+}
+
+const propSteps = 50
+
+func (propRun) Generate(rng *rand.Rand, size int) reflect.Value {
+	r := propRun{
+		seed:       1 + rng.Int63(),
+		movement:   movementNames[rng.Intn(len(movementNames))],
+		collisions: collisionNames[rng.Intn(len(collisionNames))],
+	}
+	for range 1 + rng.Intn(4) {
+		r.sizes = append(r.sizes, termSize{}.Generate(rng, size).Interface().(termSize))
+	}
+	return reflect.ValueOf(r)
+}
+
+// play runs r's scene on a headless screen writing to w, calling check
+// after every tick with the size it's at.
+func (r propRun) play(tb testing.TB, w io.Writer, check func(sim *Sim, scr *Screen, size termSize) bool) bool {
+	var o runOptions
+	o.register(flag.NewFlagSet("test", flag.ContinueOnError))
+	o.background, o.seed = "dark", r.seed
+	o.movement, o.collisions = r.movement, r.collisions
+	sc, err := o.scene()
+	if err != nil {
+		tb.Fatal(err)
+	}
+	first := r.sizes[0]
+	sim, scr := headless(sc.SimConfig, first.width, first.height, w)
+	for _, size := range r.sizes {
+		scr.resize(size.width, size.height)
+		sim.resize(size.width, size.height)
+		for range propSteps {
+			sim.update()
+			sim.draw(scr)
+			scr.flush()
+			if !check(sim, scr, size) {
+				tb.Logf("seed %d, %s movement, %s collisions, at %dx%d", r.seed, r.movement, r.collisions, size.width, size.height)
+				return false
+			}
+		}
+	}
+	return true
+}
+
+// TestStreamsInBounds checks that whatever the size, and however often it
+// changes, every stream is on a cell of the screen after every tick.
+func TestStreamsInBounds(t *testing.T) {
+	prop := func(r propRun) bool {
+		return r.play(t, io.Discard, func(sim *Sim, scr *Screen, size termSize) bool {
+			for _, s := range sim.streams {
+				if x, y := s.cell(); x < 0 || y < 0 || x >= size.width || y >= size.height {
+					t.Logf("stream at (%d, %d)", x, y)
+					return false
+				}
+			}
+			return true
+		})
+	}
+	if err := quick.Check(prop, &quick.Config{MaxCount: 30}); err != nil {
+		t.Error(err)
+	}
+}
+
+// TestTiny checks that a sim too small to animate holds still, saying so
+// where there's room, and picks up again once it's made larger.
+func TestTiny(t *testing.T) {
+	sim, scr := headless(testScene(t).SimConfig, 40, 2, io.Discard)
+	shows := func(text string) bool {
+		var b strings.Builder
+		for _, c := range scr.shown {
+			b.WriteString(c.text)
+		}
+		return strings.Contains(b.String(), text)
+	}
+	for range propSteps {
+		sim.update()
+		sim.draw(scr)
+		scr.flush()
+	}
+	if len(sim.streams) > 0 {
+		t.Errorf("%d streams out at 40x2", len(sim.streams))
+	}
+	if !shows("terminal too small") {
+		t.Error("40x2 doesn't say it's too small")
+	}
+
+	scr.resize(80, 24)
+	sim.resize(80, 24)
+	for range propSteps {
+		sim.update()
+		sim.draw(scr)
+		scr.flush()
+	}
+	if len(sim.streams) == 0 {
+		t.Error("no streams out after growing to 80x24")
+	}
+	if shows("too small") {
+		t.Error("80x24 still says it's too small")
+	}
+}
+
+// TestSeedDeterministic checks that with every movement the same seed
+// draws the same frames.
+func TestSeedDeterministic(t *testing.T) {
+	for _, movement := range movementNames {
+		t.Run(movement, func(t *testing.T) {
+			frames := func() []byte {
+				var o runOptions
+				o.register(flag.NewFlagSet("test", flag.ContinueOnError))
+				o.background, o.seed, o.movement = "dark", 1, movement
+				sc, err := o.scene()
+				if err != nil {
+					t.Fatal(err)
+				}
+				var out bytes.Buffer
+				sim, scr := headless(sc.SimConfig, 80, 24, &out)
+				for range 50 {
+					sim.update()
+					sim.draw(scr)
+					scr.flush()
+				}
+				return out.Bytes()
+			}
+			if a, b := frames(), frames(); !bytes.Equal(a, b) {
+				t.Error("seed 1 drew two different runs")
+			}
+		})
+	}
+}
+
+// TestRandomPanes checks that "random" panes only pick modes they can
+// build, and that a seed picks the same themes and modes every time.
+func TestRandomPanes(t *testing.T) {
+	picks := func() []string {
+		var o runOptions
+		o.register(flag.NewFlagSet("test", flag.ContinueOnError))
+		o.background, o.seed = "dark", 1
+		o.splitThemes = strings.Repeat("random,", maxSplit*maxSplit)
+		o.splitModes = strings.Repeat("random,", maxSplit*maxSplit)
+		scenes, err := o.splitScenes(maxSplit * maxSplit)
+		if err != nil {
+			t.Fatal(err)
+		}
+		var picks []string
+		for _, sc := range scenes {
+			picks = append(picks, fmt.Sprintf("%T %v %d", sc.mode, *sc.palette, len(sc.overlays)))
+		}
+		return picks
+	}
+	if a, b := picks(), picks(); !reflect.DeepEqual(a, b) {
+		t.Errorf("seed 1 picked different panes:\n%v\n%v", a, b)
+	}
+}
@@ -0,0 +1,456 @@
+package main
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"math/rand"
+	pcg "math/rand/v2"
+	"time"
+)
+
+// source is what a sim's random numbers come from: math/rand/v2's PCG,
+// behind math/rand's interface. Unlike math/rand's own source, its state
+// can be saved, so a snapshot can carry it.
+type source struct{ *pcg.PCG }
+
+var _ rand.Source64 = source{}
+
+// newSource seeds a source with seed, or with the clock if it's 0.
+func newSource(seed int64) source {
+	if seed == 0 {
+		seed = time.Now().UnixNano()
+	}
+	return source{pcg.NewPCG(uint64(seed), 0)}
+}
+
+func (s source) Int63() int64 { return int64(s.Uint64() >> 1) }
+
+func (s source) Seed(seed int64) { s.PCG.Seed(uint64(seed), 0) }
+
+// simSnapshot is a sim as it is between ticks: everything its next ticks
+// depend on, so another sim restored from it plays on the same. What the
+// overlays read from the clock or a file isn't in it, and nor is what the
+// sim's config already says. The params are the ones in effect for the
+// tick.
+type simSnapshot struct {
+	Tick       int                `json:"tick"`
+	Width      int                `json:"width"`
+	Height     int                `json:"height"`
+	Chaos      float64            `json:"chaos"`
+	Speed      float64            `json:"speed"`
+	Brightness float64            `json:"brightness"`
+	RNG        []byte             `json:"rng"`
+	WashRow    int                `json:"washRow"`
+	Heat       []uint32           `json:"heat"`
+	Hottest    uint32             `json:"hottest"`
+	Streams    []streamSnapshot   `json:"streams"`
+	Particles  []particleSnapshot `json:"particles,omitempty"`
+	Boosts     []boostSnapshot    `json:"boosts,omitempty"`
+
+	// Mode, Movement and Overlays are what those that have state of their
+	// own say about it, an overlay without any null.
+	Mode     json.RawMessage   `json:"mode,omitempty"`
+	Movement json.RawMessage   `json:"movement,omitempty"`
+	Overlays []json.RawMessage `json:"overlays,omitempty"`
+}
+
+// snapshotter is a mode, movement or overlay with state of its own for a
+// snapshot to carry.
+type snapshotter interface {
+	snapshot() any
+	restore(data json.RawMessage) error
+}
+
+type streamSnapshot struct {
+	X           float64         `json:"x"`
+	Y           float64         `json:"y"`
+	VX          float64         `json:"vx"`
+	VY          float64         `json:"vy"`
+	AX          float64         `json:"ax,omitempty"`
+	AY          float64         `json:"ay,omitempty"`
+	Heading     float64         `json:"heading"`
+	Turns       float64         `json:"turns"`
+	Age         int             `json:"age"`
+	Lifetime    int             `json:"lifetime"`
+	Color       Color           `json:"color"`
+	Bg          *Color          `json:"bg,omitempty"`
+	Attrs       Attr            `json:"attrs,omitempty"`
+	Glyphs      []glyphSnapshot `json:"glyphs"`
+	Speed       float64         `json:"speed"`
+	Orientation Orientation     `json:"orientation,omitempty"`
+	Link        bool            `json:"link,omitempty"`
+	Word        string          `json:"word,omitempty"`
+	Text        []string        `json:"text,omitempty"`
+	Trail       [][2]int        `json:"trail"`
+}
+
+type glyphSnapshot struct {
+	Glyph  string `json:"glyph"`
+	Weight Weight `json:"weight"`
+}
+
+type particleSnapshot struct {
+	X     float64 `json:"x"`
+	Y     float64 `json:"y"`
+	VX    float64 `json:"vx"`
+	VY    float64 `json:"vy"`
+	Glyph string  `json:"glyph"`
+	Color Color   `json:"color"`
+	Life  int     `json:"life"`
+	Fade  int     `json:"fade,omitempty"`
+}
+
+// boostSnapshot is a panic burst, with its age, or a glitch event.
+type boostSnapshot struct {
+	Panic  *int           `json:"panic,omitempty"`
+	Glitch *glitchSummary `json:"glitch,omitempty"`
+}
+
+type glitchSummary struct {
+	Left   int           `json:"left"`
+	Passes []passSummary `json:"passes"`
+}
+
+// passSummary is one of a glitch event's passes: rows shifted, or a block
+// inverted or filled with noise.
+type passSummary struct {
+	Kind   string `json:"kind"`
+	X      int    `json:"x,omitempty"`
+	Y      int    `json:"y"`
+	Width  int    `json:"width,omitempty"`
+	Height int    `json:"height"`
+	DX     int    `json:"dx,omitempty"`
+}
+
+// snapshot takes a snapshot of the sim, as it is after a tick's update
+// and before it's drawn.
+func (sim *Sim) snapshot() (*simSnapshot, error) {
+	rng, err := sim.source.MarshalBinary()
+	if err != nil {
+		return nil, err
+	}
+	snap := &simSnapshot{
+		Tick:       sim.tick,
+		Width:      sim.width,
+		Height:     sim.height,
+		Chaos:      sim.chaos,
+		Speed:      sim.speed,
+		Brightness: sim.brightness,
+		RNG:        rng,
+		WashRow:    sim.washRow,
+		Heat:       sim.heat,
+		Hottest:    sim.hottest,
+	}
+	for _, s := range sim.streams {
+		ss := streamSnapshot{
+			X: s.x, Y: s.y, VX: s.vx, VY: s.vy, AX: s.ax, AY: s.ay,
+			Heading: s.heading, Turns: s.turns, Age: s.age, Lifetime: s.lifetime,
+			Color: s.color, Bg: s.bg, Attrs: s.attrs, Speed: s.speed,
+			Orientation: s.orientation, Link: s.link, Word: s.word, Text: s.text, Trail: s.trail,
+		}
+		for _, c := range s.glyphs.choices {
+			ss.Glyphs = append(ss.Glyphs, glyphSnapshot{c.item, c.weight})
+		}
+		snap.Streams = append(snap.Streams, ss)
+	}
+	for _, p := range sim.particles {
+		snap.Particles = append(snap.Particles, particleSnapshot{p.x, p.y, p.vx, p.vy, p.glyph, p.color, p.life, p.fade})
+	}
+	for _, b := range sim.boosts {
+		switch b := b.(type) {
+		case *panicBurst:
+			snap.Boosts = append(snap.Boosts, boostSnapshot{Panic: &b.age})
+		case *glitchEvent:
+			g := &glitchSummary{Left: b.left}
+			for _, p := range b.passes {
+				switch p := p.(type) {
+				case shiftRows:
+					g.Passes = append(g.Passes, passSummary{Kind: "shift", Y: p.y, Height: p.height, DX: p.dx})
+				case invertBlock:
+					g.Passes = append(g.Passes, passSummary{"invert", p.x, p.y, p.width, p.height, 0})
+				case noiseBlock:
+					g.Passes = append(g.Passes, passSummary{"noise", p.x, p.y, p.width, p.height, 0})
+				}
+			}
+			snap.Boosts = append(snap.Boosts, boostSnapshot{Glitch: g})
+		}
+	}
+	part := func(v any) (json.RawMessage, error) {
+		s, ok := v.(snapshotter)
+		if !ok {
+			return nil, nil
+		}
+		return json.Marshal(s.snapshot())
+	}
+	if snap.Mode, err = part(sim.mode); err != nil {
+		return nil, err
+	}
+	if snap.Movement, err = part(sim.movement); err != nil {
+		return nil, err
+	}
+	for _, o := range sim.overlays {
+		data, err := part(o)
+		if err != nil {
+			return nil, err
+		}
+		snap.Overlays = append(snap.Overlays, data)
+	}
+	return snap, nil
+}
+
+// restore puts the sim in the state snap was taken in. The sim has to have
+// been built from the same config as the one snap was taken of.
+func (sim *Sim) restore(snap *simSnapshot) error {
+	if snap.Width < 0 || snap.Height < 0 || len(snap.Heat) != snap.Width*snap.Height {
+		return errors.New("snapshot: heat doesn't fit the size")
+	}
+	if len(snap.Overlays) > len(sim.overlays) {
+		return errors.New("snapshot: more overlays than the sim has")
+	}
+	if err := sim.source.UnmarshalBinary(snap.RNG); err != nil {
+		return fmt.Errorf("snapshot: %w", err)
+	}
+	sim.tick, sim.width, sim.height = snap.Tick, snap.Width, snap.Height
+	sim.Params = Params{chaos: snap.Chaos, speed: snap.Speed, brightness: snap.Brightness}
+	sim.washRow, sim.heat, sim.hottest = snap.WashRow, snap.Heat, snap.Hottest
+	sim.streams = sim.streams[:0]
+	for _, ss := range snap.Streams {
+		s := &Stream{
+			x: ss.X, y: ss.Y, vx: ss.VX, vy: ss.VY, ax: ss.AX, ay: ss.AY,
+			heading: ss.Heading, turns: ss.Turns, age: ss.Age, lifetime: ss.Lifetime,
+			color: ss.Color, bg: ss.Bg, attrs: ss.Attrs, speed: ss.Speed,
+			orientation: ss.Orientation, link: ss.Link, word: ss.Word, text: ss.Text, trail: ss.Trail,
+			glyphs: newWeighted[string](),
+		}
+		for _, g := range ss.Glyphs {
+			s.glyphs.add(g.Glyph, g.Weight)
+		}
+		sim.streams = append(sim.streams, s)
+	}
+	sim.particles = sim.particles[:0]
+	for _, p := range snap.Particles {
+		sim.particles = append(sim.particles, &Particle{p.X, p.Y, p.VX, p.VY, p.Glyph, p.Color, p.Life, p.Fade})
+	}
+	sim.boosts = sim.boosts[:0]
+	for _, b := range snap.Boosts {
+		switch {
+		case b.Panic != nil:
+			sim.boosts = append(sim.boosts, &panicBurst{age: *b.Panic})
+		case b.Glitch != nil:
+			e := &glitchEvent{left: b.Glitch.Left}
+			for _, p := range b.Glitch.Passes {
+				area := block{p.X, p.Y, p.Width, p.Height}
+				switch p.Kind {
+				case "shift":
+					e.passes = append(e.passes, shiftRows{p.Y, p.Height, p.DX})
+				case "invert":
+					e.passes = append(e.passes, invertBlock{area})
+				case "noise":
+					e.passes = append(e.passes, noiseBlock{area})
+				}
+			}
+			sim.boosts = append(sim.boosts, e)
+		}
+	}
+	part := func(v any, data json.RawMessage) error {
+		if s, ok := v.(snapshotter); ok && data != nil && string(data) != "null" {
+			return s.restore(data)
+		}
+		return nil
+	}
+	if err := part(sim.mode, snap.Mode); err != nil {
+		return fmt.Errorf("snapshot: mode: %w", err)
+	}
+	if err := part(sim.movement, snap.Movement); err != nil {
+		return fmt.Errorf("snapshot: movement: %w", err)
+	}
+	for i, data := range snap.Overlays {
+		if err := part(sim.overlays[i], data); err != nil {
+			return fmt.Errorf("snapshot: overlay %d: %w", i+1, err)
+		}
+	}
+	return nil
+}
+
+type mazeSnapshot struct {
+	Phase   mazePhase `json:"phase"`
+	Cols    int       `json:"cols"`
+	Rows    int       `json:"rows"`
+	X0      int       `json:"x0"`
+	Y0      int       `json:"y0"`
+	Open    []bool    `json:"open"`
+	Walls   int       `json:"walls"`
+	Path    [][2]int  `json:"path"`
+	Visited []bool    `json:"visited"`
+	Ticks   int       `json:"ticks"`
+}
+
+func (m *mazeMode) snapshot() any {
+	return mazeSnapshot{m.phase, m.cols, m.rows, m.x0, m.y0, m.open, m.walls, m.path, m.visited, m.ticks}
+}
+
+func (m *mazeMode) restore(data json.RawMessage) error {
+	var s mazeSnapshot
+	if err := json.Unmarshal(data, &s); err != nil {
+		return err
+	}
+	if len(s.Open) != (2*s.Cols+1)*(2*s.Rows+1) || len(s.Visited) != s.Cols*s.Rows {
+		return errors.New("the maze doesn't fit its size")
+	}
+	m.phase, m.cols, m.rows, m.x0, m.y0 = s.Phase, s.Cols, s.Rows, s.X0, s.Y0
+	m.open, m.walls, m.path, m.visited, m.ticks = s.Open, s.Walls, s.Path, s.Visited, s.Ticks
+	return nil
+}
+
+type typerSnapshot struct {
+	Next     int               `json:"next"`
+	Left     string            `json:"left,omitempty"`
+	Rows     [][]typedSnapshot `json:"rows"`
+	Typists  []typistSnapshot  `json:"typists"`
+	MaxWidth int               `json:"maxWidth"`
+}
+
+type typedSnapshot struct {
+	Text  string `json:"text"`
+	Color Color  `json:"color"`
+}
+
+type typistSnapshot struct {
+	Row   int      `json:"row"`
+	Color Color    `json:"color"`
+	Line  []string `json:"line"`
+	At    int      `json:"at"`
+	Pace  float64  `json:"pace"`
+	Pause int      `json:"pause,omitempty"`
+	Erase int      `json:"erase,omitempty"`
+}
+
+func (m *typerMode) snapshot() any {
+	s := typerSnapshot{Next: m.next, Left: m.left, MaxWidth: m.maxWidth}
+	for _, row := range m.rows {
+		var r []typedSnapshot
+		for _, t := range row {
+			r = append(r, typedSnapshot{t.text, t.color})
+		}
+		s.Rows = append(s.Rows, r)
+	}
+	for _, t := range m.typists {
+		s.Typists = append(s.Typists, typistSnapshot{t.row, t.color, t.line, t.at, t.pace, t.pause, t.erase})
+	}
+	return s
+}
+
+func (m *typerMode) restore(data json.RawMessage) error {
+	var s typerSnapshot
+	if err := json.Unmarshal(data, &s); err != nil {
+		return err
+	}
+	if len(m.corpus) > 0 && (s.Next < 0 || s.Next >= len(m.corpus)) {
+		return errors.New("the corpus isn't the host's")
+	}
+	m.next, m.left, m.maxWidth = s.Next, s.Left, s.MaxWidth
+	m.rows = make([][]typed, len(s.Rows))
+	for y, row := range s.Rows {
+		for _, t := range row {
+			m.rows[y] = append(m.rows[y], typed{t.Text, t.Color})
+		}
+	}
+	m.typists = m.typists[:0]
+	for _, t := range s.Typists {
+		if t.Row < 0 || t.Row >= len(m.rows) {
+			return errors.New("a typist is off the screen")
+		}
+		m.typists = append(m.typists, &typist{t.Row, t.Color, t.Line, t.At, t.Pace, t.Pause, t.Erase})
+	}
+	return nil
+}
+
+type noiseSnapshot struct {
+	Field []uint8 `json:"field,omitempty"`
+}
+
+func (m *noiseMovement) snapshot() any {
+	if m.field == nil {
+		return noiseSnapshot{}
+	}
+	return noiseSnapshot{m.field.p[:256]}
+}
+
+func (m *noiseMovement) restore(data json.RawMessage) error {
+	var s noiseSnapshot
+	if err := json.Unmarshal(data, &s); err != nil {
+		return err
+	}
+	switch len(s.Field) {
+	case 0:
+		m.field = nil
+	case 256:
+		m.field = &perlin{}
+		copy(m.field.p[:256], s.Field)
+		copy(m.field.p[256:], s.Field)
+	default:
+		return errors.New("bad noise field")
+	}
+	return nil
+}
+
+type tickerSnapshot struct {
+	Cells  []string `json:"cells"`
+	Colors []Color  `json:"colors"`
+	Offset float64  `json:"offset"`
+}
+
+func (t *Ticker) snapshot() any { return tickerSnapshot{t.cells, t.colors, t.offset} }
+
+func (t *Ticker) restore(data json.RawMessage) error {
+	var s tickerSnapshot
+	if err := json.Unmarshal(data, &s); err != nil {
+		return err
+	}
+	if len(s.Cells) != len(t.cells) || len(s.Colors) != len(s.Cells) {
+		return errors.New("the ticker's text isn't the host's")
+	}
+	t.cells, t.colors, t.offset = s.Cells, s.Colors, s.Offset
+	return nil
+}
+
+type pictureSnapshot struct {
+	Width  int                   `json:"width"`
+	Height int                   `json:"height"`
+	Cells  []pictureCellSnapshot `json:"cells"`
+	Left   int                   `json:"left"`
+	Total  int                   `json:"total"`
+}
+
+type pictureCellSnapshot struct {
+	Text  string `json:"text,omitempty"`
+	Fg    Color  `json:"fg"`
+	Bg    Color  `json:"bg"`
+	HasBg bool   `json:"hasBg,omitempty"`
+	Hits  uint8  `json:"hits,omitempty"`
+}
+
+func (p *Picture) snapshot() any {
+	s := pictureSnapshot{Width: p.width, Height: p.height, Left: p.left, Total: p.total}
+	for _, c := range p.cells {
+		s.Cells = append(s.Cells, pictureCellSnapshot{c.text, c.fg, c.bg, c.hasBg, c.hits})
+	}
+	return s
+}
+
+func (p *Picture) restore(data json.RawMessage) error {
+	var s pictureSnapshot
+	if err := json.Unmarshal(data, &s); err != nil {
+		return err
+	}
+	if s.Width < 0 || s.Height < 0 || len(s.Cells) != s.Width*s.Height {
+		return errors.New("the picture doesn't fit its size")
+	}
+	p.width, p.height, p.left, p.total = s.Width, s.Height, s.Left, s.Total
+	p.cells = make([]pictureCell, len(s.Cells))
+	for i, c := range s.Cells {
+		p.cells[i] = pictureCell{c.Text, c.Fg, c.Bg, c.HasBg, c.Hits}
+	}
+	return nil
+}
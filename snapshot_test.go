@@ -0,0 +1,79 @@
+package main
+
+import (
+	"encoding/json"
+	"flag"
+	"io"
+	"slices"
+	"testing"
+)
+
+// TestSnapshotRestore checks that a sim restored from another's snapshot,
+// sent as a sync keyframe is, draws the same frames from then on, in each
+// mode that depends on nothing but the seed and with each movement.
+func TestSnapshotRestore(t *testing.T) {
+	const steps = 50
+	type run struct{ mode, movement string }
+	var runs []run
+	for _, mode := range goldenModes {
+		runs = append(runs, run{mode, "walk"})
+	}
+	for _, movement := range movementNames {
+		runs = append(runs, run{"streams", movement})
+	}
+	for _, r := range runs {
+		t.Run(r.mode+"-"+r.movement, func(t *testing.T) {
+			newRun := func() (*Sim, *Screen) {
+				var o runOptions
+				o.register(flag.NewFlagSet("test", flag.ContinueOnError))
+				o.background, o.seed = "dark", 1
+				o.mode, o.movement = r.mode, r.movement
+				o.text = "AAAAAAAAA snapshot"
+				sc, err := o.scene()
+				if err != nil {
+					t.Fatal(err)
+				}
+				return headless(sc.SimConfig, 80, 24, io.Discard)
+			}
+			host, hostScr := newRun()
+			var data []byte
+			for i := range steps {
+				host.update()
+				if i == steps-1 {
+					snap, err := host.snapshot()
+					if err != nil {
+						t.Fatal(err)
+					}
+					if data, err = json.Marshal(snap); err != nil {
+						t.Fatal(err)
+					}
+				}
+				host.draw(hostScr)
+				hostScr.flush()
+			}
+
+			joiner, joinerScr := newRun()
+			var snap simSnapshot
+			if err := json.Unmarshal(data, &snap); err != nil {
+				t.Fatal(err)
+			}
+			if err := joiner.restore(&snap); err != nil {
+				t.Fatal(err)
+			}
+			joiner.draw(joinerScr)
+			joinerScr.flush()
+			for range steps {
+				for _, sim := range []*Sim{host, joiner} {
+					sim.update()
+				}
+				host.draw(hostScr)
+				joiner.draw(joinerScr)
+				hostScr.flush()
+				joinerScr.flush()
+			}
+			if !slices.Equal(hostScr.shown, joinerScr.shown) {
+				t.Error("the restored sim drew a different frame")
+			}
+		})
+	}
+}
@@ -0,0 +1,98 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+)
+
+const maxSplit = 6
+
+// randomPaneModes are the modes a "random" pane picks from: the ones that
+// need no --file or --text to show something.
+var randomPaneModes = []string{"streams", "clock", "maze", "typer"}
+
+// parseSplit reads --split, columns by rows such as 2x2.
+func parseSplit(s string) (cols, rows int, err error) {
+	if _, err = fmt.Sscanf(s, "%dx%d", &cols, &rows); err != nil || cols < 1 || rows < 1 || cols > maxSplit || rows > maxSplit {
+		return 0, 0, fmt.Errorf("bad split %q (want columns x rows, such as 2x2, up to %dx%d)", s, maxSplit, maxSplit)
+	}
+	return cols, rows, nil
+}
+
+// splitScenes builds a scene for each pane. Panes take their themes and
+// modes from --split-themes and --split-modes in turn, where "random" picks
+// one; past the end of the lists the first pane keeps --theme and the rest
+// get random themes, and every pane keeps --mode. The picks come from
+// --seed, so a seeded split is the same every time.
+func (o *runOptions) splitScenes(panes int) ([]*scene, error) {
+	themesFor := strings.Split(o.splitThemes, ",")
+	modesFor := strings.Split(o.splitModes, ",")
+	rng := newRand(o.seed)
+	var scenes []*scene
+	for i := range panes {
+		pane := *o
+		theme := ""
+		if i < len(themesFor) {
+			theme = themesFor[i]
+		}
+		switch {
+		case theme == "random" || theme == "" && i > 0:
+			pane.theme = themes[rng.Intn(len(themes))].name
+		case theme != "":
+			pane.theme = theme
+		}
+		if i < len(modesFor) && modesFor[i] != "" {
+			pane.mode = modesFor[i]
+			if pane.mode == "random" {
+				pane.mode = randomPaneModes[rng.Intn(len(randomPaneModes))]
+			}
+		}
+		if pane.seed != 0 {
+			pane.seed += int64(i)
+		}
+		sc, err := pane.scene()
+		if err != nil {
+			return nil, fmt.Errorf("pane %d: %w", i+1, err)
+		}
+		scenes = append(scenes, sc)
+	}
+	return scenes, nil
+}
+
+// paneRect is where pane (col, row) of a cols by rows split goes on a screen
+// of the given size, with a one-cell border between panes. The last column
+// and row take up any slack.
+func paneRect(col, row, cols, rows, width, height int) (x, y, w, h int) {
+	w = max(1, (width-(cols-1))/cols)
+	h = max(1, (height-(rows-1))/rows)
+	x, y = col*(w+1), row*(h+1)
+	if col == cols-1 {
+		w = max(1, width-x)
+	}
+	if row == rows-1 {
+		h = max(1, height-y)
+	}
+	return x, y, w, h
+}
+
+// drawBorders draws the thin lines between panes.
+func drawBorders(scr *Screen, cols, rows, width, height int, c Color) {
+	for col := range cols - 1 {
+		x, _, w, _ := paneRect(col, 0, cols, rows, width, height)
+		for y := range height {
+			scr.put(x+w, y, "│", c)
+		}
+	}
+	for row := range rows - 1 {
+		_, y, _, h := paneRect(0, row, cols, rows, width, height)
+		for x := range width {
+			glyph := "─"
+			for col := range cols - 1 {
+				if cx, _, cw, _ := paneRect(col, 0, cols, rows, width, height); x == cx+cw {
+					glyph = "┼"
+				}
+			}
+			scr.put(x, y+h, glyph, c)
+		}
+	}
+}
@@ -0,0 +1,49 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"time"
+)
+
+var statsNames = []string{"text", "json", "off"}
+
+// Stats sum up a run, for the report on exit.
+type Stats struct {
+	Duration     time.Duration `json:"-"`
+	Seconds      float64       `json:"duration_seconds"`
+	Frames       int           `json:"frames"`
+	FPS          float64       `json:"fps"`
+	Spawned      int           `json:"streams_spawned"`
+	CellsTouched int           `json:"cells_touched"`
+	PeakStreams  int           `json:"peak_streams"`
+}
+
+// stats sums up the frames the renderer has drawn over d, and the sims it
+// drew them from.
+func (r *renderer) stats(d time.Duration) Stats {
+	st := Stats{Duration: d, Seconds: d.Seconds(), Frames: r.frames, PeakStreams: r.peak}
+	if d > 0 {
+		st.FPS = float64(r.frames) / d.Seconds()
+	}
+	for _, sim := range r.sims {
+		st.Spawned += sim.spawned
+		st.CellsTouched += sim.touched
+	}
+	return st
+}
+
+// writeStats writes st as --stats asks: a few lines of text, a line of
+// JSON, or nothing for off.
+func writeStats(w io.Writer, format string, st Stats) error {
+	switch format {
+	case "json":
+		return json.NewEncoder(w).Encode(st)
+	case "text":
+		_, err := fmt.Fprintf(w, "ran %s, %d frames at %.1f fps\n%d streams spawned, %d at most at once, through %d cells\n",
+			st.Duration.Round(time.Second), st.Frames, st.FPS, st.Spawned, st.PeakStreams, st.CellsTouched)
+		return err
+	}
+	return nil
+}
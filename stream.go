@@ -0,0 +1,150 @@
+package main
+
+import (
+	"math"
+	"math/rand"
+	"slices"
+)
+
+// Stream is a wandering point on the canvas. Position and velocity are in
+// cells (and cells per tick); the stream is drawn at the nearest cell.
+type Stream struct {
+	x, y   float64
+	vx, vy float64
+
+	// ax and ay accumulate forces until the next integrate.
+	ax, ay float64
+
+	// heading is the direction, in radians, the stream is turning towards,
+	// and turns scales its chance of picking a new one.
+	heading float64
+	turns   float64
+
+	age, lifetime int
+
+	// Identity picked at spawn, so a stream can be followed across the
+	// screen: its color, background and attributes, the glyphs it prints
+	// and a speed multiplier.
+	color  Color
+	bg     *Color
+	attrs  Attr
+	glyphs *Charset
+	speed  float64
+
+	// orientation is which way the string runs from the head.
+	orientation Orientation
+
+	// link has the head drawn as a hyperlink, with --link-chance.
+	link bool
+
+	// word, if set, is printed instead of a random glyph string, and
+	// failing that text, with --mutate.
+	word string
+	text []string
+
+	// trail holds the most recent cells the stream has visited, oldest first.
+	trail [][2]int
+}
+
+const trailLength = 8
+
+func newStream(rng *rand.Rand, width, height, lifetime int, palette *Palette, charset *Charset) *Stream {
+	s := &Stream{
+		x:        float64(rng.Intn(width)),
+		y:        float64(rng.Intn(height)),
+		heading:  randomHeading(rng),
+		turns:    1,
+		lifetime: lifetime,
+		color:    palette.randomColor(rng),
+		bg:       palette.randomBackground(rng),
+		attrs:    palette.randomAttrs(rng),
+		glyphs:   streamGlyphs(rng, charset),
+		speed:    0.6 + rng.Float64()*0.8,
+	}
+	s.vx, s.vy = headingVector(s.heading, 0.5*s.speed)
+	return s
+}
+
+// streamGlyphs picks the handful of glyphs a stream prints from charset.
+func streamGlyphs(rng *rand.Rand, charset *Charset) *Charset {
+	return charset.sample(rng, 1+rng.Intn(5))
+}
+
+// fork splits off a child at the same spot, veering off to one side.
+func (s *Stream) fork(rng *rand.Rand, lifetime int) *Stream {
+	child := *s
+	child.age, child.lifetime = 0, lifetime
+	child.trail = slices.Clone(s.trail)
+	child.text = slices.Clone(s.text)
+	veer := math.Pi / 4
+	if rng.Intn(2) == 0 {
+		veer = -veer
+	}
+	child.heading += veer
+	child.vx, child.vy = headingVector(child.heading, math.Hypot(s.vx, s.vy))
+	return &child
+}
+
+// randomHeading picks one of the eight compass directions.
+func randomHeading(rng *rand.Rand) float64 {
+	return float64(rng.Intn(8)) * math.Pi / 4
+}
+
+// headingVector converts an angle (0 = east, counter-clockwise on screen) to
+// a velocity of the given speed. Screen rows grow downwards, hence the
+// flipped y.
+func headingVector(angle, speed float64) (float64, float64) {
+	return math.Cos(angle) * speed, -math.Sin(angle) * speed
+}
+
+func (s *Stream) cell() (int, int) {
+	return int(math.Round(s.x)), int(math.Round(s.y))
+}
+
+// record pushes the current cell onto the trail if the stream has moved.
+func (s *Stream) record() {
+	x, y := s.cell()
+	if n := len(s.trail); n > 0 && s.trail[n-1] == [2]int{x, y} {
+		return
+	}
+	if len(s.trail) == trailLength {
+		s.trail = append(s.trail[:0], s.trail[1:]...)
+	}
+	s.trail = append(s.trail, [2]int{x, y})
+}
+
+func (s *Stream) accelerate(ax, ay float64) {
+	s.ax += ax
+	s.ay += ay
+}
+
+// turn eases the velocity towards heading at the given speed (scaled by the
+// stream's own); rate 1 turns instantly, smaller rates keep more momentum.
+func (s *Stream) turn(speed, rate float64) {
+	tx, ty := headingVector(s.heading, speed*s.speed)
+	s.accelerate((tx-s.vx)*rate, (ty-s.vy)*rate)
+}
+
+// integrate applies the accumulated acceleration and moves the stream by its
+// velocity times speed, reflecting the velocity off the edges. restitution
+// scales the speed kept after a bounce.
+func (s *Stream) integrate(width, height int, speed, restitution float64) {
+	s.vx += s.ax
+	s.vy += s.ay
+	s.ax, s.ay = 0, 0
+	s.x += s.vx * speed
+	s.y += s.vy * speed
+	maxX, maxY := float64(max(width-1, 0)), float64(max(height-1, 0))
+	if s.x < 0 || s.x > maxX {
+		s.vx = -s.vx * restitution
+		s.x = clampFloat(s.x, 0, maxX)
+	}
+	if s.y < 0 || s.y > maxY {
+		s.vy = -s.vy * restitution
+		s.y = clampFloat(s.y, 0, maxY)
+	}
+}
+
+func clampFloat(v, lo, hi float64) float64 {
+	return max(lo, min(v, hi))
+}
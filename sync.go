@@ -0,0 +1,284 @@
+package main
+
+import (
+	"bufio"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"slices"
+	"sync"
+)
+
+// syncMessage is one tick of a --sync-host animation as the host simulated
+// it: at what size and with what params, after throwing which bursts.
+type syncMessage struct {
+	Tick       int      `json:"tick"`
+	Width      int      `json:"width"`
+	Height     int      `json:"height"`
+	Chaos      float64  `json:"chaos"`
+	Speed      float64  `json:"speed"`
+	Brightness float64  `json:"brightness"`
+	Bursts     []string `json:"bursts,omitempty"`
+}
+
+// sameState reports whether m and other simulate at the same size and
+// params.
+func (m syncMessage) sameState(other syncMessage) bool {
+	return m.Width == other.Width && m.Height == other.Height &&
+		m.Chaos == other.Chaos && m.Speed == other.Speed && m.Brightness == other.Brightness
+}
+
+// syncHello is what a joiner gets first: the host's seed, how many ticks
+// it has simulated, its latest keyframe, if it has one yet, and the ticks
+// since that changed size or params or threw bursts, which is all it takes
+// to replay them.
+type syncHello struct {
+	Seed     int64           `json:"seed"`
+	Tick     int             `json:"tick"`
+	Keyframe json.RawMessage `json:"keyframe,omitempty"`
+	Log      []syncMessage   `json:"log"`
+}
+
+// syncKeyframe is a snapshot of the host's sim, taken after simulating
+// the tick of state. The host keeps it encoded, since the snapshot shares
+// memory with the sim.
+type syncKeyframe struct {
+	State syncMessage  `json:"state"`
+	Sim   *simSnapshot `json:"sim"`
+}
+
+const (
+	// syncBacklog is how many ticks a joiner may fall behind before it's
+	// dropped.
+	syncBacklog = 64
+
+	// syncKeyframeEvery is how many ticks apart the host takes keyframes,
+	// so a joiner has at most this many to replay, and the log at most
+	// this many to keep.
+	syncKeyframeEvery = 600
+)
+
+// checkSync rejects combinations of options that --sync-host and
+// --sync-join can't keep in step.
+func (o *runOptions) checkSync() error {
+	switch {
+	case o.syncHost == "" && o.syncJoin == "":
+		return nil
+	case o.syncHost != "" && o.syncJoin != "":
+		return errors.New("--sync-host and --sync-join can't be used together")
+	case o.split != "":
+		return errors.New("--split can't be synced")
+	case o.syncJoin != "" && o.singleLoop:
+		return errors.New("--sync-join can't be used with --single-loop")
+	}
+	return nil
+}
+
+// syncHost broadcasts the ticks of the animation to every terminal that
+// joins it. Only the seed, sizes, params and bursts are sent, so switching
+// themes, charsets or profiles on the host leaves the joiners behind.
+type syncHost struct {
+	listener net.Listener
+	seed     int64
+
+	// bursts are the bursts thrown since the last tick, only touched by
+	// the render goroutine.
+	bursts []string
+
+	mu       sync.Mutex
+	ticks    int
+	last     syncMessage
+	keyframe json.RawMessage
+	log      []syncMessage
+	joiners  map[chan syncMessage]bool
+}
+
+// hostSync listens on addr for terminals to join an animation seeded with
+// seed.
+func hostSync(addr string, seed int64) (*syncHost, error) {
+	l, err := net.Listen("tcp", addr)
+	if err != nil {
+		return nil, err
+	}
+	h := &syncHost{listener: l, seed: seed, joiners: map[chan syncMessage]bool{}}
+	go func() {
+		for {
+			conn, err := l.Accept()
+			if err != nil {
+				return
+			}
+			go h.serve(conn)
+		}
+	}()
+	return h, nil
+}
+
+func (h *syncHost) close() { h.listener.Close() }
+
+func (h *syncHost) serve(conn net.Conn) {
+	defer conn.Close()
+	ch := make(chan syncMessage, syncBacklog)
+	h.mu.Lock()
+	hello := syncHello{Seed: h.seed, Tick: h.ticks, Keyframe: h.keyframe, Log: slices.Clone(h.log)}
+	h.joiners[ch] = true
+	h.mu.Unlock()
+	defer h.drop(ch)
+
+	w := bufio.NewWriter(conn)
+	enc := json.NewEncoder(w)
+	if enc.Encode(hello) != nil || w.Flush() != nil {
+		return
+	}
+	for msg := range ch {
+		if enc.Encode(msg) != nil || w.Flush() != nil {
+			return
+		}
+	}
+}
+
+func (h *syncHost) drop(ch chan syncMessage) {
+	h.mu.Lock()
+	defer h.mu.Unlock()
+	if h.joiners[ch] {
+		delete(h.joiners, ch)
+		close(ch)
+	}
+}
+
+// burst records a burst thrown before the next tick.
+func (h *syncHost) burst(glyph string) {
+	h.bursts = append(h.bursts, glyph)
+}
+
+// step sends every joiner the tick sim has just simulated at the given
+// size. A joiner that has fallen too far behind to keep up is dropped.
+// Every syncKeyframeEvery ticks it takes a keyframe, and forgets the log
+// before it; if the keyframe can't be taken, the log is kept instead.
+func (h *syncHost) step(sim *Sim, width, height int) {
+	msg := syncMessage{
+		Tick:       sim.tick - 1,
+		Width:      width,
+		Height:     height,
+		Chaos:      sim.chaos,
+		Speed:      sim.speed,
+		Brightness: sim.brightness,
+		Bursts:     h.bursts,
+	}
+	h.bursts = nil
+	var keyframe json.RawMessage
+	if msg.Tick > 0 && msg.Tick%syncKeyframeEvery == 0 {
+		snap, err := sim.snapshot()
+		if err == nil {
+			keyframe, err = json.Marshal(syncKeyframe{msg, snap})
+		}
+		if err != nil {
+			logger.Warn("taking a sync keyframe", "err", err)
+			keyframe = nil
+		}
+	}
+	h.mu.Lock()
+	defer h.mu.Unlock()
+	switch {
+	case keyframe != nil:
+		h.keyframe, h.log = keyframe, nil
+	case len(msg.Bursts) > 0 || !msg.sameState(h.last):
+		h.log = append(h.log, msg)
+	}
+	h.last, h.ticks = msg, msg.Tick+1
+	for ch := range h.joiners {
+		select {
+		case ch <- msg:
+		default:
+			delete(h.joiners, ch)
+			close(ch)
+		}
+	}
+}
+
+// syncFeed is the ticks a joiner gets from its host. err is why they
+// stopped, once msgs is closed.
+type syncFeed struct {
+	msgs chan syncMessage
+	err  error
+}
+
+// joinSync joins the animation hosted at addr, returning a sim restored
+// from the host's keyframe and replayed from there to where the host's is,
+// and the ticks to follow it with. cfg has to match the host's options for
+// the animations to match; the host's seed and params replace its own.
+func joinSync(addr string, cfg SimConfig) (*Sim, *syncFeed, error) {
+	conn, err := net.Dial("tcp", addr)
+	if err != nil {
+		return nil, nil, err
+	}
+	dec := json.NewDecoder(bufio.NewReader(conn))
+	var hello syncHello
+	if err := dec.Decode(&hello); err != nil {
+		conn.Close()
+		return nil, nil, fmt.Errorf("joining %s: %w", addr, err)
+	}
+	cfg.seed = hello.Seed
+	cfg.modulators = nil
+	sim := newSim(cfg)
+
+	// Replay the ticks before joining, drawing them too, since drawing
+	// takes random numbers as well.
+	scratch := newScreen(io.Discard)
+	state, log, from := syncMessage{}, hello.Log, 0
+	if hello.Keyframe != nil {
+		var k syncKeyframe
+		err := json.Unmarshal(hello.Keyframe, &k)
+		if err == nil && k.Sim == nil {
+			err = errors.New("keyframe has no sim")
+		}
+		if err == nil {
+			err = sim.restore(k.Sim)
+		}
+		if err != nil {
+			conn.Close()
+			return nil, nil, fmt.Errorf("joining %s: %w", addr, err)
+		}
+		state, from = k.State, k.State.Tick+1
+		sim.base = Params{chaos: state.Chaos, speed: state.Speed, brightness: state.Brightness}
+		scratch.resize(state.Width, state.Height)
+		sim.draw(scratch)
+	}
+	for tick := from; tick < hello.Tick; tick++ {
+		msg := state
+		msg.Tick, msg.Bursts = tick, nil
+		if len(log) > 0 && log[0].Tick == tick {
+			msg, log = log[0], log[1:]
+		}
+		state = msg
+		sim.step(msg)
+		scratch.resize(msg.Width, msg.Height)
+		sim.draw(scratch)
+	}
+
+	feed := &syncFeed{msgs: make(chan syncMessage, syncBacklog)}
+	go func() {
+		defer conn.Close()
+		defer close(feed.msgs)
+		for {
+			var msg syncMessage
+			if err := dec.Decode(&msg); err != nil {
+				feed.err = err
+				return
+			}
+			feed.msgs <- msg
+		}
+	}()
+	return sim, feed, nil
+}
+
+// step simulates a tick the way the host did.
+func (sim *Sim) step(msg syncMessage) {
+	for _, glyph := range msg.Bursts {
+		sim.spawnBurst(glyph)
+	}
+	sim.resize(msg.Width, msg.Height)
+	sim.base = Params{chaos: msg.Chaos, speed: msg.Speed, brightness: msg.Brightness}
+	sim.update()
+}
@@ -0,0 +1,221 @@
+package main
+
+import (
+	"bytes"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"unicode"
+)
+
+const (
+	tailMaxLines = 500
+
+	// tailBacklog is how far from the end of the file tail mode starts
+	// reading it, and tailReadMax how much more it reads a tick, in chunks
+	// of tailChunk, so a huge file or a flood of writes costs no more.
+	tailBacklog = 256 << 10
+	tailReadMax = 1 << 20
+	tailChunk   = 64 << 10
+
+	// tailDecay is the chance per tick that a random character of an older
+	// line gets corrupted on its own, on top of what the streams do.
+	tailDecay = 0.3
+)
+
+type tailLine struct {
+	cells []string
+
+	// colors holds the color of each corrupted cell; the zero Color marks one
+	// still intact.
+	colors []Color
+}
+
+// at is which of the line's cells is drawn over column x, or -1 if none
+// is.
+func (l *tailLine) at(x int) int {
+	for i, glyph := range l.cells {
+		if x -= glyphWidth(glyph); x < 0 {
+			return i
+		}
+	}
+	return -1
+}
+
+// Tail follows a file like tail -f, scrolling its lines up from the bottom
+// of the screen. Streams corrupt any character their head passes over, and
+// older lines slowly rot on their own.
+type Tail struct {
+	path    string
+	file    *os.File
+	offset  int64
+	partial []byte
+	lines   []*tailLine
+
+	// chunk is what read reads into, and skip is set when reading starts
+	// partway through a line, to drop what's left of it.
+	chunk []byte
+	skip  bool
+
+	// drawn is how many columns were printed on each row last frame, so a
+	// shorter line can blank out what is left of a longer one.
+	drawn []int
+}
+
+func newTail(path string) (*Tail, error) {
+	if path == "" {
+		return nil, fmt.Errorf("tail mode needs --file")
+	}
+	t := &Tail{path: path, chunk: make([]byte, tailChunk)}
+	if err := t.open(); err != nil {
+		return nil, err
+	}
+	t.read()
+	return t, nil
+}
+
+// open opens the file and seeks to tailBacklog bytes from its end.
+func (t *Tail) open() error {
+	f, err := os.Open(t.path)
+	if err != nil {
+		return err
+	}
+	var offset int64
+	if info, err := f.Stat(); err == nil && info.Size() > tailBacklog {
+		if offset, err = f.Seek(-tailBacklog, io.SeekEnd); err != nil {
+			f.Close()
+			return err
+		}
+	}
+	if t.file != nil {
+		t.file.Close()
+	}
+	t.file, t.offset, t.partial, t.skip = f, offset, nil, offset > 0
+	return nil
+}
+
+// read picks up what has been appended since the last call, up to
+// tailReadMax of it, starting over when the file has been truncated or
+// replaced.
+func (t *Tail) read() {
+	if info, err := os.Stat(t.path); err == nil {
+		if current, err := t.file.Stat(); err == nil && (!os.SameFile(info, current) || info.Size() < t.offset) {
+			if t.open() != nil {
+				return
+			}
+		}
+	}
+	for total := 0; total < tailReadMax; {
+		n, err := t.file.Read(t.chunk)
+		t.offset += int64(n)
+		total += n
+		t.feed(t.chunk[:n])
+		if err != nil || n == 0 {
+			return
+		}
+	}
+}
+
+// feed splits what's been read into lines. A line longer than tailChunk
+// is cut there, being far wider than any screen.
+func (t *Tail) feed(data []byte) {
+	data = append(t.partial, data...)
+	for {
+		i := bytes.IndexByte(data, '\n')
+		if i < 0 {
+			break
+		}
+		if t.skip {
+			t.skip = false
+		} else {
+			t.push(strings.TrimRight(string(data[:i]), "\r"))
+		}
+		data = data[i+1:]
+	}
+	if len(data) > tailChunk {
+		if !t.skip {
+			t.push(string(data))
+		}
+		data, t.skip = nil, true
+	}
+	t.partial = append(t.partial[:0], data...)
+}
+
+// push adds a line, with its control characters shown as spaces so the
+// file can't send escapes to the terminal.
+func (t *Tail) push(text string) {
+	text = strings.ReplaceAll(text, "\t", "    ")
+	line := &tailLine{}
+	for _, r := range text {
+		if unicode.IsControl(r) {
+			r = ' '
+		}
+		line.cells = append(line.cells, string(r))
+	}
+	line.colors = make([]Color, len(line.cells))
+	t.lines = append(t.lines, line)
+	if len(t.lines) > tailMaxLines {
+		t.lines = t.lines[len(t.lines)-tailMaxLines:]
+	}
+}
+
+// row returns the line shown on screen row y, if any.
+func (t *Tail) row(y, height int) *tailLine {
+	i := len(t.lines) - height + y
+	if i < 0 || i >= len(t.lines) {
+		return nil
+	}
+	return t.lines[i]
+}
+
+func (t *Tail) update(sim *Sim) {
+	t.read()
+	for _, s := range sim.streams {
+		x, y := s.cell()
+		if line := t.row(y, sim.height); line != nil {
+			if i := line.at(x); i >= 0 {
+				line.cells[i] = clip(s.glyphs.pick(sim.rng), 1)
+				line.colors[i] = s.color
+			}
+		}
+	}
+	// Rot a random older line, favouring the oldest on screen.
+	if visible := min(len(t.lines), sim.height); visible > 1 && sim.rng.Float64() < tailDecay {
+		y := sim.rng.Intn(sim.rng.Intn(visible-1) + 1)
+		if line := t.row(sim.height-visible+y, sim.height); line != nil && len(line.cells) > 0 {
+			x := sim.rng.Intn(len(line.cells))
+			line.cells[x] = clip(sim.charset.pick(sim.rng), 1)
+			line.colors[x] = sim.palette.randomColor(sim.rng)
+		}
+	}
+}
+
+func (t *Tail) draw(sim *Sim, scr *Screen) {
+	if len(t.drawn) != sim.height {
+		t.drawn = make([]int, sim.height)
+	}
+	text := sim.palette.primary.dim(0.75)
+	for y := range sim.height {
+		line := t.row(y, sim.height)
+		n := 0
+		if line != nil {
+			for i, glyph := range line.cells {
+				w := scr.glyphWidth(glyph)
+				if n+w > sim.width {
+					break
+				}
+				c := line.colors[i]
+				if c == (Color{}) {
+					c = text
+				}
+				scr.put(n, y, glyph, c)
+				n += w
+			}
+		}
+		if t.drawn[y] > n {
+			scr.put(n, y, strings.Repeat(" ", t.drawn[y]-n), text)
+		}
+		t.drawn[y] = n
+	}
+}
@@ -0,0 +1,72 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"unicode"
+)
+
+// TestTailControls checks that a followed file can't get escapes to the
+// terminal, whether as C0, DEL, C1 or bytes that aren't UTF-8.
+func TestTailControls(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "log")
+	text := "\x1b]52;c;aGVsbG8=\a\x7f \u009b2J \x9b2J\n"
+	if err := os.WriteFile(path, []byte(text), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	tail, err := newTail(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(tail.lines) != 1 {
+		t.Fatalf("read %d lines, want 1", len(tail.lines))
+	}
+	for _, c := range tail.lines[0].cells {
+		if strings.ContainsFunc(c, unicode.IsControl) {
+			t.Errorf("kept %q", c)
+		}
+	}
+}
+
+// TestTailBacklog checks that only the end of a big file is read, from the
+// first whole line in it, and that what's appended is read a bounded
+// amount a tick.
+func TestTailBacklog(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "log")
+	line := strings.Repeat("x", 99) + "\n"
+	big := strings.Repeat(line, 2*tailBacklog/len(line)) + "last\n"
+	if err := os.WriteFile(path, []byte(big), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	tail, err := newTail(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(tail.lines) != tailMaxLines {
+		t.Fatalf("read %d lines, want %d", len(tail.lines), tailMaxLines)
+	}
+	for _, l := range tail.lines[:len(tail.lines)-1] {
+		if len(l.cells) != 99 {
+			t.Fatalf("read a line of %d cells, want 99", len(l.cells))
+		}
+	}
+	if got := strings.Join(tail.lines[len(tail.lines)-1].cells, ""); got != "last" {
+		t.Fatalf("last line is %q", got)
+	}
+
+	f, err := os.OpenFile(path, os.O_APPEND|os.O_WRONLY, 0)
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer f.Close()
+	if _, err := f.WriteString(strings.Repeat(line, 2*tailReadMax/len(line))); err != nil {
+		t.Fatal(err)
+	}
+	before := tail.offset
+	tail.read()
+	if read := tail.offset - before; read > tailReadMax {
+		t.Errorf("read %d bytes in a tick, want at most %d", read, tailReadMax)
+	}
+}
@@ -0,0 +1,148 @@
+package main
+
+import (
+	"bufio"
+	"fmt"
+	"net"
+	"os"
+	"time"
+)
+
+// Telnet commands and options, from RFC 854 and friends.
+const (
+	telnetSE   = 240
+	telnetIP   = 244
+	telnetSB   = 250
+	telnetWILL = 251
+	telnetWONT = 252
+	telnetDO   = 253
+	telnetDONT = 254
+	telnetIAC  = 255
+
+	telnetEcho = 1
+	telnetSGA  = 3
+	telnetNAWS = 31
+)
+
+// serveTelnet listens on --serve-telnet and runs an animation of its own
+// for every client that connects, at the window size the client reports,
+// until the server is killed.
+func serveTelnet(o *runOptions) error {
+	s, err := newServer(o)
+	if err != nil {
+		return err
+	}
+	l, err := net.Listen("tcp", o.serveTelnet)
+	if err != nil {
+		return err
+	}
+	defer l.Close()
+	fmt.Fprintf(os.Stderr, "%s: serving telnet on %s\n", progName, l.Addr())
+
+	for {
+		conn, err := l.Accept()
+		if err != nil {
+			return err
+		}
+		if !s.admit() {
+			conn.Write([]byte("Too many people are watching. Try again later.\r\n"))
+			conn.Close()
+			continue
+		}
+		go func() {
+			defer s.leave()
+			defer conn.Close()
+			if err := serveTelnetClient(s, conn); err != nil {
+				fmt.Fprintf(os.Stderr, "%s: %s: %v\n", progName, conn.RemoteAddr(), err)
+			}
+		}()
+	}
+}
+
+func serveTelnetClient(s *server, conn net.Conn) error {
+	// Take over echoing and go-ahead so the client sends keys as they're
+	// typed, and ask it for its window size.
+	if _, err := conn.Write([]byte{
+		telnetIAC, telnetWILL, telnetEcho,
+		telnetIAC, telnetWILL, telnetSGA,
+		telnetIAC, telnetDO, telnetNAWS,
+	}); err != nil {
+		return err
+	}
+	c := &telnetClient{conn: conn, size: clientSize{width: 80, height: 24}}
+	return s.animate(c, c.size.get, c.readInput)
+}
+
+// telnetClient is a connection to one client of --serve-telnet.
+type telnetClient struct {
+	conn net.Conn
+	size clientSize
+}
+
+// Write sends output to the client. The screen only writes UTF-8, which
+// never has the 255 byte telnet would need escaped.
+func (c *telnetClient) Write(p []byte) (int, error) {
+	c.conn.SetWriteDeadline(time.Now().Add(clientWriteTimeout))
+	return c.conn.Write(p)
+}
+
+// readInput reads the client's keys and telnet commands, keeping track of
+// its window size, until it quits or hangs up.
+func (c *telnetClient) readInput(commands chan<- Command, done <-chan struct{}) {
+	defer sendQuit(commands, done)
+	br := bufio.NewReader(c.conn)
+	for {
+		b, err := br.ReadByte()
+		if err != nil {
+			return
+		}
+		if b != telnetIAC {
+			if isQuitKey(rune(b)) {
+				return
+			}
+			continue
+		}
+		cmd, err := br.ReadByte()
+		if err != nil {
+			return
+		}
+		switch cmd {
+		case telnetIP:
+			return
+		case telnetWILL, telnetWONT, telnetDO, telnetDONT:
+			if _, err := br.ReadByte(); err != nil {
+				return
+			}
+		case telnetSB:
+			sub, err := readTelnetSub(br)
+			if err != nil {
+				return
+			}
+			// NAWS is the option, then width and height as 16-bit numbers.
+			if len(sub) == 5 && sub[0] == telnetNAWS {
+				c.size.set(int(sub[1])<<8|int(sub[2]), int(sub[3])<<8|int(sub[4]))
+			}
+		}
+	}
+}
+
+// readTelnetSub reads a subnegotiation up to its IAC SE, unescaping any
+// doubled IACs in it.
+func readTelnetSub(br *bufio.Reader) ([]byte, error) {
+	var sub []byte
+	for {
+		b, err := br.ReadByte()
+		if err != nil {
+			return nil, err
+		}
+		if b == telnetIAC {
+			if b, err = br.ReadByte(); err != nil {
+				return nil, err
+			}
+			if b == telnetSE {
+				return sub, nil
+			}
+		}
+		sub = append(sub, b)
+	}
+}
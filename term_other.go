@@ -0,0 +1,42 @@
+//go:build !(linux || darwin || freebsd || netbsd || openbsd || dragonfly || windows)
+
+package main
+
+import (
+	"errors"
+	"os"
+)
+
+func terminalSize() (int, int) {
+	return fallbackSize()
+}
+
+func makeRaw(f *os.File) (func(), error) {
+	return nil, errors.New("raw terminal input is not supported on this platform")
+}
+
+func makeRawTimed(f *os.File, tenths uint8) (func(), error) {
+	return makeRaw(f)
+}
+
+func ignoreBrokenPipe() {}
+
+// prepareConsole and legacyConsole only do anything on Windows.
+func prepareConsole() error { return nil }
+
+func legacyConsole() bool { return false }
+
+// terminalUTF8 reports whether the terminal takes UTF-8, going by the
+// locale.
+func terminalUTF8() bool {
+	return localeUTF8(locale())
+}
+
+// There's no job control here: the process can't be stopped.
+const jobControl = false
+
+func notifySuspend(c chan<- os.Signal) {}
+
+func isContinue(s os.Signal) bool { return false }
+
+func stop(c chan<- os.Signal) {}
@@ -0,0 +1,95 @@
+//go:build linux || darwin || freebsd || netbsd || openbsd || dragonfly
+
+package main
+
+import (
+	"os"
+	"os/signal"
+	"syscall"
+	"unsafe"
+)
+
+type winsize struct {
+	rows, cols, xpixels, ypixels uint16
+}
+
+func terminalSize() (int, int) {
+	var ws winsize
+	err := ioctl(os.Stdout.Fd(), syscall.TIOCGWINSZ, unsafe.Pointer(&ws))
+	if err != nil || ws.cols == 0 || ws.rows == 0 {
+		return fallbackSize()
+	}
+	return int(ws.cols), int(ws.rows)
+}
+
+func ioctl(fd, req uintptr, arg unsafe.Pointer) error {
+	if _, _, errno := syscall.Syscall(syscall.SYS_IOCTL, fd, req, uintptr(arg)); errno != 0 {
+		return errno
+	}
+	return nil
+}
+
+// makeRaw puts the terminal on f into raw mode, so keys arrive one at a time
+// without echo or signal handling, and returns a function restoring it.
+func makeRaw(f *os.File) (func(), error) {
+	return rawMode(f, 1, 0)
+}
+
+// makeRawTimed is makeRaw with reads that give up after tenths of a second
+// with nothing to read, returning no bytes.
+func makeRawTimed(f *os.File, tenths uint8) (func(), error) {
+	return rawMode(f, 0, tenths)
+}
+
+func rawMode(f *os.File, vmin, vtime uint8) (func(), error) {
+	var old syscall.Termios
+	if err := ioctl(f.Fd(), ioctlGetTermios, unsafe.Pointer(&old)); err != nil {
+		return nil, err
+	}
+	raw := old
+	raw.Iflag &^= syscall.IGNBRK | syscall.BRKINT | syscall.PARMRK | syscall.ISTRIP | syscall.INLCR | syscall.IGNCR | syscall.ICRNL | syscall.IXON
+	raw.Lflag &^= syscall.ECHO | syscall.ECHONL | syscall.ICANON | syscall.ISIG | syscall.IEXTEN
+	raw.Cflag &^= syscall.CSIZE | syscall.PARENB
+	raw.Cflag |= syscall.CS8
+	raw.Cc[syscall.VMIN] = vmin
+	raw.Cc[syscall.VTIME] = vtime
+	if err := ioctl(f.Fd(), ioctlSetTermios, unsafe.Pointer(&raw)); err != nil {
+		return nil, err
+	}
+	return func() { ioctl(f.Fd(), ioctlSetTermios, unsafe.Pointer(&old)) }, nil
+}
+
+// ignoreBrokenPipe keeps writing to a stdout that has gone away from
+// killing the process outright, leaving the terminal in raw mode.
+func ignoreBrokenPipe() {
+	signal.Ignore(syscall.SIGPIPE)
+}
+
+// prepareConsole and legacyConsole only do anything on Windows.
+func prepareConsole() error { return nil }
+
+func legacyConsole() bool { return false }
+
+// terminalUTF8 reports whether the terminal takes UTF-8, going by the
+// locale.
+func terminalUTF8() bool {
+	return localeUTF8(locale())
+}
+
+// jobControl says stop can stop the process, for Ctrl-Z.
+const jobControl = true
+
+// notifySuspend relays the signals to stop and continue the process to c.
+func notifySuspend(c chan<- os.Signal) {
+	signal.Notify(c, syscall.SIGTSTP, syscall.SIGCONT)
+}
+
+func isContinue(s os.Signal) bool { return s == syscall.SIGCONT }
+
+// stop stops the process as SIGTSTP would if it weren't caught, returning
+// once it's continued, with the signals relayed to c again.
+func stop(c chan<- os.Signal) {
+	signal.Reset(syscall.SIGTSTP)
+	syscall.Kill(syscall.Getpid(), syscall.SIGTSTP)
+	notifySuspend(c)
+}
@@ -0,0 +1,116 @@
+//go:build windows
+
+package main
+
+import (
+	"errors"
+	"os"
+	"syscall"
+	"unsafe"
+)
+
+var (
+	kernel32                       = syscall.NewLazyDLL("kernel32.dll")
+	procGetConsoleScreenBufferInfo = kernel32.NewProc("GetConsoleScreenBufferInfo")
+	procSetConsoleMode             = kernel32.NewProc("SetConsoleMode")
+	procSetConsoleOutputCP         = kernel32.NewProc("SetConsoleOutputCP")
+)
+
+// Console modes, from wincon.h.
+const (
+	enableProcessedInput            = 0x1
+	enableLineInput                 = 0x2
+	enableEchoInput                 = 0x4
+	enableVirtualTerminalProcessing = 0x4
+	disableNewlineAutoReturn        = 0x8
+	enableVirtualTerminalInput      = 0x200
+
+	cpUTF8 = 65001
+)
+
+type coord struct{ x, y int16 }
+
+type consoleScreenBufferInfo struct {
+	size, cursor             coord
+	attributes               uint16
+	left, top, right, bottom int16
+	maxWindow                coord
+}
+
+func terminalSize() (int, int) {
+	var info consoleScreenBufferInfo
+	if ok, _, _ := procGetConsoleScreenBufferInfo.Call(os.Stdout.Fd(), uintptr(unsafe.Pointer(&info))); ok == 0 {
+		return fallbackSize()
+	}
+	return int(info.right-info.left) + 1, int(info.bottom-info.top) + 1
+}
+
+func setConsoleMode(f *os.File, mode uint32) error {
+	if ok, _, err := procSetConsoleMode.Call(f.Fd(), uintptr(mode)); ok == 0 {
+		return err
+	}
+	return nil
+}
+
+// makeRaw puts the console on f into raw mode, so keys arrive one at a time
+// without echo or Ctrl-C handling, as the escape sequences a terminal would
+// send, and returns a function restoring it.
+func makeRaw(f *os.File) (func(), error) {
+	var old uint32
+	if err := syscall.GetConsoleMode(syscall.Handle(f.Fd()), &old); err != nil {
+		return nil, err
+	}
+	raw := old&^(enableProcessedInput|enableLineInput|enableEchoInput) | enableVirtualTerminalInput
+	if err := setConsoleMode(f, raw); err != nil {
+		return nil, err
+	}
+	return func() { setConsoleMode(f, old) }, nil
+}
+
+// makeRawTimed fails: reads from a console can't be made to give up, so
+// anything waiting on the terminal to answer a query would hang.
+func makeRawTimed(f *os.File, tenths uint8) (func(), error) {
+	return nil, errors.New("timed console reads are not supported on Windows")
+}
+
+func ignoreBrokenPipe() {}
+
+// prepareConsole has the console on stdout interpret escape sequences and
+// take UTF-8, which consoles older than Windows 10 can't; nothing drawn
+// would make sense on them.
+func prepareConsole() error {
+	if !isTerminal(os.Stdout) {
+		return nil
+	}
+	var mode uint32
+	if err := syscall.GetConsoleMode(syscall.Handle(os.Stdout.Fd()), &mode); err != nil {
+		// Not a console at all, but a terminal like mintty's pipe.
+		return nil
+	}
+	if err := setConsoleMode(os.Stdout, mode|enableVirtualTerminalProcessing|disableNewlineAutoReturn); err != nil {
+		return errors.New("this console can't show colors or move the cursor; try Windows Terminal")
+	}
+	procSetConsoleOutputCP.Call(cpUTF8)
+	return nil
+}
+
+// legacyConsole reports whether the console is the old console host rather
+// than Windows Terminal or another terminal emulator. Its fonts lack most
+// glyphs, it draws combining marks as boxes and it's slow to redraw, so it
+// gets ASCII, 16 colors and half the frames.
+func legacyConsole() bool {
+	return os.Getenv("WT_SESSION") == "" && os.Getenv("TERM_PROGRAM") == "" && os.Getenv("TERM") == ""
+}
+
+func terminalUTF8() bool {
+	return !legacyConsole()
+}
+
+// There's no job control here: the process can't be stopped.
+const jobControl = false
+
+func notifySuspend(c chan<- os.Signal) {}
+
+func isContinue(s os.Signal) bool { return false }
+
+func stop(c chan<- os.Signal) {}
@@ -0,0 +1,271 @@
+package main
+
+import (
+	"flag"
+	"fmt"
+	"os"
+	"os/signal"
+	"strings"
+	"sync"
+	"syscall"
+	"time"
+)
+
+// Theme is a named palette for dark backgrounds, with optionally its own
+// variant for light ones. Themes without one get their palette adapted.
+type Theme struct {
+	name    string
+	palette *Palette
+	light   *Palette
+}
+
+// variant returns the theme's palette for a light or dark background.
+func (t *Theme) variant(background string) *Palette {
+	if background != "light" {
+		return t.palette
+	}
+	return t.light
+}
+
+// Adapting the palettes up front, rather than when they're first wanted,
+// leaves themes read-only for the servers' clients to share.
+func init() {
+	for i := range themes {
+		if themes[i].light == nil {
+			themes[i].light = themes[i].palette.forLight()
+		}
+	}
+}
+
+var themes = []Theme{
+	{name: "glitch", palette: defaultPalette, light: lightPalette},
+	{name: "matrix", palette: newPalette(Color{200, 255, 200},
+		Choice[Color]{Color{0, 255, 70}, Common},
+		Choice[Color]{Color{0, 190, 50}, Common},
+		Choice[Color]{Color{0, 120, 30}, Uncommon},
+		Choice[Color]{Color{150, 255, 150}, Rare},
+	)},
+	{name: "fire", palette: newPalette(Color{255, 240, 200},
+		Choice[Color]{Color{255, 60, 0}, Common},
+		Choice[Color]{Color{255, 140, 0}, Common},
+		Choice[Color]{Color{255, 210, 40}, Uncommon},
+		Choice[Color]{Color{170, 20, 10}, Uncommon},
+	)},
+	{name: "ice", palette: newPalette(Color{235, 250, 255},
+		Choice[Color]{Color{100, 200, 255}, Common},
+		Choice[Color]{Color{40, 120, 255}, Common},
+		Choice[Color]{Color{170, 240, 255}, Uncommon},
+		Choice[Color]{Color{120, 90, 255}, Rare},
+	)},
+	{name: "vapor", palette: vaporPalette},
+	{name: "amber", palette: newPalette(Color{255, 220, 140},
+		Choice[Color]{Color{255, 176, 0}, Common},
+		Choice[Color]{Color{200, 120, 0}, Uncommon},
+	)},
+	{name: "riot", palette: riotPalette},
+	{name: "pumpkin", palette: pumpkinPalette},
+	{name: "holly", palette: newPalette(Color{255, 250, 240},
+		Choice[Color]{Color{220, 20, 40}, Common},
+		Choice[Color]{Color{20, 170, 60}, Common},
+		Choice[Color]{Color{0, 110, 40}, Uncommon},
+		Choice[Color]{Color{255, 210, 80}, Rare},
+	)},
+	{name: "mono", palette: newPalette(Color{255, 255, 255},
+		Choice[Color]{Color{180, 180, 180}, Common},
+		Choice[Color]{Color{110, 110, 110}, Common},
+	), light: newPalette(Color{0, 0, 0},
+		Choice[Color]{Color{70, 70, 70}, Common},
+		Choice[Color]{Color{140, 140, 140}, Common},
+	)},
+}
+
+// riotPalette is the glitch palette with solid blocks behind some heads,
+// bold, underlined and reversed streams, reverse-video bursts and the odd
+// magenta wash.
+var riotPalette = func() *Palette {
+	p := newPalette(Color{255, 255, 255},
+		Choice[Color]{Color{255, 40, 90}, Common},
+		Choice[Color]{Color{60, 220, 255}, Common},
+		Choice[Color]{Color{255, 230, 0}, Uncommon},
+	)
+	p.backgrounds = newWeighted(
+		Choice[*Color]{nil, 3 * Common},
+		Choice[*Color]{&Color{160, 0, 70}, Uncommon},
+		Choice[*Color]{&Color{0, 90, 140}, Uncommon},
+		Choice[*Color]{&Color{90, 0, 160}, Rare},
+	)
+	p.attrs = newWeighted(
+		Choice[Attr]{0, 3 * Common},
+		Choice[Attr]{AttrBold, Uncommon},
+		Choice[Attr]{AttrUnderline, Rare},
+		Choice[Attr]{AttrReverse, Rare},
+	)
+	p.inverse = true
+	p.wash = &Color{110, 0, 60}
+	return p
+}()
+
+// vaporPalette is pastels, with rainbow heads.
+var vaporPalette = func() *Palette {
+	p := newPalette(Color{255, 230, 255},
+		Choice[Color]{Color{255, 110, 200}, Common},
+		Choice[Color]{Color{120, 230, 255}, Common},
+		Choice[Color]{Color{180, 120, 255}, Uncommon},
+		Choice[Color]{Color{255, 250, 150}, Rare},
+	)
+	p.gradient = GradientRainbow
+	return p
+}()
+
+// pumpkinPalette is orange on black, with solid black behind some heads.
+var pumpkinPalette = func() *Palette {
+	p := newPalette(Color{255, 200, 120},
+		Choice[Color]{Color{255, 120, 0}, Common},
+		Choice[Color]{Color{230, 80, 0}, Common},
+		Choice[Color]{Color{120, 40, 140}, Rare},
+	)
+	p.backgrounds = newWeighted(
+		Choice[*Color]{nil, 2 * Common},
+		Choice[*Color]{&Color{0, 0, 0}, Uncommon},
+	)
+	return p
+}()
+
+func themeNames() []string {
+	names := make([]string, len(themes))
+	for i, t := range themes {
+		names[i] = t.name
+	}
+	return names
+}
+
+func lookupTheme(name string) (*Theme, error) {
+	for i := range themes {
+		if themes[i].name == name {
+			return &themes[i], nil
+		}
+	}
+	return nil, fmt.Errorf("unknown theme %q (want %s)", name, strings.Join(themeNames(), ", "))
+}
+
+// runThemes lists the themes, each with a swatch of its colors, or with
+// preview, shows them off one after another.
+func runThemes(args []string) error {
+	if len(args) > 0 && args[0] == "preview" {
+		return previewThemes(args[1:])
+	}
+	for _, t := range themes {
+		var swatch strings.Builder
+		for _, c := range t.palette.colors.choices {
+			swatch.WriteString(c.item.fg() + strings.Repeat("█", int(c.weight)))
+		}
+		fmt.Printf("%-8s %s\x1b[0m\n", t.name, swatch.String())
+	}
+	return nil
+}
+
+// previewThemes runs the animation, switching to the next theme every few
+// seconds with its name along the bottom row. Space skips ahead; q quits.
+func previewThemes(args []string) error {
+	fs := flag.NewFlagSet("themes preview", flag.ExitOnError)
+	var o runOptions
+	o.register(fs)
+	each := fs.Duration("each", 4*time.Second, "how long to show each theme")
+	if err := parseFlags(fs, args); err != nil {
+		return err
+	}
+	sc, err := o.scene()
+	if err != nil {
+		return err
+	}
+
+	keys := make(chan rune, 64)
+	if restore, err := makeRaw(os.Stdin); err == nil {
+		defer restore()
+		go readKeys(os.Stdin, keys)
+	}
+	sigs := make(chan os.Signal, 1)
+	signal.Notify(sigs, syscall.SIGINT, syscall.SIGTERM)
+
+	scr := newScreen(os.Stdout)
+	scr.enter()
+	defer scr.leave()
+	background, err := resolveBackground(o.background)
+	if err != nil {
+		return err
+	}
+	sim := newSim(sc.SimConfig)
+	current := 0
+	sim.setPalette(themes[current].variant(background))
+	ticker := time.NewTicker(tickInterval)
+	defer ticker.Stop()
+	next := time.NewTicker(*each)
+	defer next.Stop()
+	for {
+		select {
+		case <-sigs:
+			return nil
+		case k := <-keys:
+			if isQuitKey(k) {
+				return nil
+			}
+			if k != ' ' {
+				continue
+			}
+			next.Reset(*each)
+		case <-next.C:
+		case <-ticker.C:
+			width, height := terminalSize()
+			scr.resize(width, height)
+			sim.resize(width, height)
+			sim.update()
+			sim.draw(scr)
+			label := fmt.Sprintf(" %s (%d of %d) ", themes[current].name, current+1, len(themes))
+			scr.put((width-cellCount(label))/2, height-1, label, sim.palette.primary)
+			scr.flush()
+			continue
+		}
+		current = (current + 1) % len(themes)
+		sim.setPalette(themes[current].variant(background))
+	}
+}
+
+var backgroundNames = []string{"auto", "dark", "light"}
+
+// terminalBackground works out whether the terminal is light on dark or
+// dark on light: from COLORFGBG if set, otherwise by asking the terminal for
+// its background color. It only ever asks once, and the first call has to
+// come before anything else starts reading stdin.
+var terminalBackground = sync.OnceValue(func() string {
+	if fgbg := os.Getenv("COLORFGBG"); fgbg != "" {
+		parts := strings.Split(fgbg, ";")
+		switch parts[len(parts)-1] {
+		case "7", "15":
+			return "light"
+		}
+		return "dark"
+	}
+	if !isTerminal(os.Stdin) || !isTerminal(os.Stdout) {
+		return "dark"
+	}
+	p, err := newProbe()
+	if err != nil {
+		return "dark"
+	}
+	defer p.close()
+	if p.backgroundColor() > 0.5 {
+		return "light"
+	}
+	return "dark"
+})
+
+// resolveBackground turns a --background value into light or dark.
+func resolveBackground(name string) (string, error) {
+	switch name {
+	case "auto":
+		return terminalBackground(), nil
+	case "dark", "light":
+		return name, nil
+	}
+	return "", fmt.Errorf("unknown background %q (want %s)", name, strings.Join(backgroundNames, ", "))
+}
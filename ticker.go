@@ -0,0 +1,92 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"sync"
+)
+
+const (
+	// tickerPace is how many cells the ticker scrolls a tick at speed 1.
+	tickerPace = 0.8
+
+	// tickerRot is the share of the ticker's glyphs corrupted for good
+	// each time it goes round, scaled by chaos.
+	tickerRot = 0.04
+)
+
+// Ticker scrolls a line of text right to left along a row, over the
+// streams, on a faint band of its own. Each time the text goes round, a few
+// more of its glyphs are corrupted, for good.
+type Ticker struct {
+	cells []string
+
+	// colors holds the color of each corrupted cell; the zero Color marks
+	// one still intact.
+	colors []Color
+
+	// row is the row to run along, counting up from the bottom if it's
+	// negative, and offset how far the text has scrolled in.
+	row    int
+	offset float64
+}
+
+// newTicker scrolls text along row; text "-" is whatever is read from
+// stdin, its lines run together.
+func newTicker(text string, row int) (*Ticker, error) {
+	if text == "-" {
+		var err error
+		if text, err = readStdinText(); err != nil {
+			return nil, err
+		}
+	}
+	if text = strings.TrimSpace(text); text == "" {
+		return nil, fmt.Errorf("ticker mode needs --text")
+	}
+	t := &Ticker{row: row}
+	eachCell(text, func(g string) { t.cells = append(t.cells, g) })
+	t.colors = make([]Color, len(t.cells))
+	return t, nil
+}
+
+// readStdinText is all of stdin, read the first time a ticker asks for
+// it, so the scene can be built again on a profile switch.
+var readStdinText = sync.OnceValues(func() (string, error) {
+	data, err := io.ReadAll(os.Stdin)
+	return strings.Join(strings.Fields(string(data)), " "), err
+})
+
+func (t *Ticker) update(sim *Sim) {
+	t.offset += sim.speed * tickerPace
+	if int(t.offset) < sim.width+len(t.cells) {
+		return
+	}
+	t.offset = 0
+	for range max(1, int(float64(len(t.cells))*tickerRot*sim.chaos)) {
+		i := sim.rng.Intn(len(t.cells))
+		t.cells[i] = sim.charset.pick(sim.rng)
+		t.colors[i] = sim.palette.randomColor(sim.rng)
+	}
+}
+
+func (t *Ticker) draw(sim *Sim, scr *Screen) {
+	y := t.row
+	if y < 0 {
+		y += sim.height
+	}
+	y = max(0, min(y, sim.height-1))
+	band := sim.palette.primary.dim(0.15)
+	for x := range sim.width {
+		scr.putBg(x, y, " ", Color{}, band)
+	}
+	x0 := sim.width - int(t.offset)
+	for i, g := range t.cells {
+		c := t.colors[i]
+		if c == (Color{}) {
+			c = sim.palette.primary
+		}
+		scr.putBg(x0+i, y, g, c, band)
+	}
+}
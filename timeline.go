@@ -0,0 +1,179 @@
+package main
+
+import (
+	"bufio"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// Timeline is a show loaded by --timeline: phases played in turn, each
+// overriding some of run's flags for a while. A timeline file is in the
+// same TOML subset as the config file, with a [[phase]] table per phase:
+//
+//	loop = true
+//
+//	[[phase]]
+//	length = "30s"
+//	chaos = 0.3
+//	speed = 0.5
+//
+//	[[phase]]
+//	length = "10s"
+//	transition = "2s"
+//	chaos = 5
+//	theme = "fire"
+//
+// length is how long the phase lasts, and transition how long its chaos,
+// speed and brightness take to blend in from the last phase's.
+type Timeline struct {
+	phases []phase
+
+	// loop starts again from the first phase after the last, rather than
+	// staying in the last for good.
+	loop bool
+}
+
+type phase struct {
+	length, transition time.Duration
+	settings           []setting
+}
+
+func loadTimeline(path string) (*Timeline, error) {
+	f, err := os.Open(path)
+	if err != nil {
+		return nil, err
+	}
+	defer f.Close()
+	t, err := parseTimeline(f)
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", path, err)
+	}
+	return t, nil
+}
+
+func parseTimeline(r io.Reader) (*Timeline, error) {
+	var t Timeline
+	// check is a flag set the phases' settings are tried on, so bad ones
+	// are caught before the show starts.
+	check := flagSet("run", new(runOptions).register)()
+	scanner := bufio.NewScanner(r)
+	line := 0
+	for scanner.Scan() {
+		line++
+		raw := scanner.Text()
+		text := strings.TrimSpace(raw)
+		if text == "" || strings.HasPrefix(text, "#") {
+			continue
+		}
+		if text == "[[phase]]" {
+			t.phases = append(t.phases, phase{})
+			continue
+		}
+		if strings.HasPrefix(text, "[") {
+			return nil, errorAt(line, indent(raw), "want a [[phase]] table, got %s", text)
+		}
+		key, value, column, err := parseKeyValue(raw, line)
+		if err != nil {
+			return nil, err
+		}
+		if len(t.phases) == 0 {
+			if key != "loop" {
+				return nil, errorAt(line, indent(raw), "%s belongs in a [[phase]]", key)
+			}
+			if t.loop, err = strconv.ParseBool(value); err != nil {
+				return nil, errorAt(line, column, "loop: %w", err)
+			}
+			continue
+		}
+		p := &t.phases[len(t.phases)-1]
+		switch key {
+		case "length", "transition":
+			d, err := time.ParseDuration(value)
+			if err != nil || d < 0 {
+				return nil, errorAt(line, column, "bad %s %q", key, value)
+			}
+			if key == "length" {
+				p.length = d
+			} else {
+				p.transition = d
+			}
+		default:
+			if check.Lookup(key) == nil {
+				return nil, errorAt(line, indent(raw), "unknown setting %q", key)
+			}
+			if err := check.Set(key, value); err != nil {
+				return nil, errorAt(line, column, "%s: %w", key, err)
+			}
+			p.settings = append(p.settings, setting{key, value, "", "", line, column})
+		}
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, err
+	}
+	if len(t.phases) == 0 {
+		return nil, errors.New("no [[phase]] tables")
+	}
+	for i, p := range t.phases {
+		if p.length <= 0 {
+			return nil, fmt.Errorf("phase %d has no length", i+1)
+		}
+	}
+	return &t, nil
+}
+
+// options returns the run options for phase i: args parsed with the named
+// profile, then the phase's settings on top.
+func (t *Timeline) options(args []string, profile string, i int) (*runOptions, error) {
+	fs := flag.NewFlagSet("run", flag.ContinueOnError)
+	var o runOptions
+	o.register(fs)
+	o.registerTerminal(fs)
+	if err := parseProfile(fs, args, profile); err != nil {
+		return nil, err
+	}
+	for _, s := range t.phases[i].settings {
+		if err := fs.Set(s.key, s.value); err != nil {
+			return nil, fmt.Errorf("timeline line %d, column %d: %s: %w", s.line, s.column, s.key, err)
+		}
+	}
+	return &o, nil
+}
+
+// next is the phase after i, or -1 when the timeline is over.
+func (t *Timeline) next(i int) int {
+	switch {
+	case i+1 < len(t.phases):
+		return i + 1
+	case t.loop:
+		return 0
+	}
+	return -1
+}
+
+// transition blends the params in from those of the phase before, over
+// length from start.
+type transition struct {
+	from   Params
+	start  time.Time
+	length time.Duration
+}
+
+func newTransition(from Params, length time.Duration) *transition {
+	return &transition{from: from, start: time.Now(), length: length}
+}
+
+func (t *transition) modulate(p *Params) {
+	f := float64(time.Since(t.start)) / float64(t.length)
+	if f >= 1 {
+		return
+	}
+	p.chaos = lerp(f, t.from.chaos, p.chaos)
+	p.speed = lerp(f, t.from.speed, p.speed)
+	p.brightness = lerp(f, t.from.brightness, p.brightness)
+}
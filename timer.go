@@ -0,0 +1,67 @@
+package main
+
+import (
+	"fmt"
+	"time"
+)
+
+// Timer overlays a countdown. When it runs out the canvas erupts: chaos goes
+// to the maximum and fireworks burst at random spots.
+type Timer struct {
+	deadline time.Time
+	bell     bool
+
+	shown string
+	text  blockText
+	rung  bool
+}
+
+func newTimer(d time.Duration, bell bool) *Timer {
+	return &Timer{deadline: time.Now().Add(d), bell: bell}
+}
+
+func (t *Timer) finished() bool {
+	return !time.Now().Before(t.deadline)
+}
+
+func (t *Timer) update(sim *Sim) {
+	left := time.Until(t.deadline).Round(time.Second)
+	if left < 0 {
+		left = 0
+	}
+	if s := formatCountdown(left); s != t.shown {
+		t.shown = s
+		t.text.rows = renderFont(s)
+	}
+	t.text = layoutBlockText(t.text.rows, sim.width, sim.height)
+
+	if t.finished() && sim.rng.Float64() < 0.3 {
+		sim.burst(sim.rng.Float64()*float64(sim.width), sim.rng.Float64()*float64(sim.height), explosionParticles)
+	}
+}
+
+func (t *Timer) modulate(p *Params) {
+	if t.finished() {
+		p.chaos = maxChaos
+	}
+}
+
+func (t *Timer) draw(sim *Sim, scr *Screen) {
+	if !t.finished() {
+		t.text.draw(sim, scr, 0, bannerPixel)
+		return
+	}
+	if t.bell && !t.rung {
+		scr.bell()
+		t.rung = true
+	}
+	t.text.draw(sim, scr, 0.5, bannerPixel)
+}
+
+func formatCountdown(d time.Duration) string {
+	h, m, s := int(d.Hours()), int(d.Minutes())%60, int(d.Seconds())%60
+	if h > 0 {
+		return fmt.Sprintf("%d:%02d:%02d", h, m, s)
+	}
+	return fmt.Sprintf("%02d:%02d", m, s)
+}
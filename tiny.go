@@ -0,0 +1,40 @@
+package main
+
+// The smallest sim there's room to animate. Below it, the streams and the
+// modes' layouts have no room to work in, so the sim stands still and
+// says the terminal is too small, or, with no room for that either,
+// blinks a single A, until it's made larger again.
+const (
+	minWidth  = 10
+	minHeight = 3
+)
+
+// tinyMessages are what a tiny sim says, longest first; it shows the first
+// that fits.
+var tinyMessages = []string{"terminal too small", "too small"}
+
+// tinyBlink is the ticks the A is on for, and then off for.
+const tinyBlink = 5
+
+// tiny reports whether the sim is too small to animate.
+func (sim *Sim) tiny() bool {
+	return sim.width < minWidth || sim.height < minHeight
+}
+
+// drawTiny draws a tiny sim: the longest message that fits, across the
+// middle row, or the blinking A in the middle cell.
+func (sim *Sim) drawTiny(scr *Screen) {
+	if sim.width <= 0 || sim.height <= 0 {
+		return
+	}
+	y := sim.height / 2
+	for _, text := range tinyMessages {
+		if n := cellCount(text); n <= sim.width {
+			scr.put((sim.width-n)/2, y, text, sim.palette.primary)
+			return
+		}
+	}
+	if sim.tick/tinyBlink%2 == 0 {
+		scr.put(sim.width/2, y, "A", sim.palette.primary)
+	}
+}
@@ -0,0 +1,47 @@
+package main
+
+import (
+	"math/rand"
+	"strings"
+	"time"
+)
+
+const (
+	// titleText is what the title glitches from.
+	titleText = "AAAAAAAAA"
+
+	// titleInterval is the least time between titles, so the taskbar isn't
+	// redrawn every frame.
+	titleInterval = 500 * time.Millisecond
+
+	// titleGlitch is the chance, at chaos 1, of each letter of the title
+	// being a glyph instead.
+	titleGlitch = 0.3
+)
+
+// titleAnim glitches the terminal's window title, for --title-anim, with
+// its own random numbers so the animation plays the same without it.
+type titleAnim struct {
+	rng  *rand.Rand
+	last time.Time
+}
+
+func newTitleAnim() *titleAnim {
+	return &titleAnim{rng: newRand(0)}
+}
+
+// update sets a fresh title, if it's been long enough since the last, from
+// sim's charset and chaos.
+func (t *titleAnim) update(sim *Sim, scr *Screen) {
+	if time.Since(t.last) < titleInterval {
+		return
+	}
+	t.last = time.Now()
+	title := glitch(t.rng, titleText, min(1, titleGlitch*sim.chaos), sim.charset)
+	if scr.ascii {
+		var sb strings.Builder
+		eachCell(title, func(text string) { sb.WriteString(asciiGlyph(text)) })
+		title = sb.String()
+	}
+	scr.setTitle(title)
+}
@@ -0,0 +1,59 @@
+package main
+
+import (
+	"errors"
+	"os"
+	"os/exec"
+	"strings"
+)
+
+// tmuxPopupSize is how much of the tmux client a --tmux-popup takes, across
+// and down.
+const tmuxPopupSize = "80%"
+
+func inTmux() bool {
+	return os.Getenv("TMUX") != ""
+}
+
+// runTmuxPopup runs the animation again, with args, in a tmux popup over
+// the current client that closes when it quits.
+func runTmuxPopup(args []string) error {
+	if !inTmux() {
+		return errors.New("--tmux-popup only works inside tmux")
+	}
+	exe, err := os.Executable()
+	if err != nil {
+		return err
+	}
+	// The popup itself mustn't open another, whatever the config file
+	// says.
+	command := []string{shellQuote(exe)}
+	for _, arg := range args {
+		if name, _, _ := strings.Cut(strings.TrimLeft(arg, "-"), "="); name != "tmux-popup" {
+			command = append(command, shellQuote(arg))
+		}
+	}
+	command = append(command, "--tmux-popup=false")
+	cmd := exec.Command("tmux", "display-popup", "-E", "-w", tmuxPopupSize, "-h", tmuxPopupSize, strings.Join(command, " "))
+	cmd.Stdin, cmd.Stdout, cmd.Stderr = os.Stdin, os.Stdout, os.Stderr
+	return cmd.Run()
+}
+
+// shellQuote quotes s for sh, which tmux runs popups' commands with.
+func shellQuote(s string) string {
+	return "'" + strings.ReplaceAll(s, "'", `'\''`) + "'"
+}
+
+// appendPassthrough appends seq wrapped for tmux to pass straight through
+// to the terminal outside, as it does nothing with graphics itself: in a
+// DCS tmux; sequence, with every escape in seq doubled.
+func appendPassthrough(b []byte, seq []byte) []byte {
+	b = append(b, "\x1bPtmux;"...)
+	for _, c := range seq {
+		if c == keyEscape {
+			b = append(b, keyEscape)
+		}
+		b = append(b, c)
+	}
+	return append(b, "\x1b\\"...)
+}
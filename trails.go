@@ -0,0 +1,111 @@
+package main
+
+import (
+	"fmt"
+	"slices"
+)
+
+// Trails is how the cells a stream has passed through are drawn.
+type Trails int
+
+const (
+	// TrailsGlyphs prints random glyphs from the stream's own.
+	TrailsGlyphs Trails = iota
+	// TrailsLines joins the cells up with box-drawing lines, like a
+	// circuit trace.
+	TrailsLines
+)
+
+var trailsNames = []string{"glyphs", "lines"}
+
+func parseTrails(name string) (Trails, error) {
+	if i := slices.Index(trailsNames, name); i >= 0 {
+		return Trails(i), nil
+	}
+	return TrailsGlyphs, fmt.Errorf("unknown trails %q (want one of %v)", name, trailsNames)
+}
+
+// step is the direction from one cell to the next, each of x and y -1, 0
+// or 1.
+type step struct{ dx, dy int }
+
+func stepBetween(from, to [2]int) step {
+	return step{sign(to[0] - from[0]), sign(to[1] - from[1])}
+}
+
+func sign(n int) int {
+	switch {
+	case n > 0:
+		return 1
+	case n < 0:
+		return -1
+	}
+	return 0
+}
+
+func (d step) diagonal() bool { return d.dx != 0 && d.dy != 0 }
+
+// corners are the box-drawing corners joining a vertical end (up or down)
+// to a horizontal one (left or right), by up and by right.
+var corners = [2][2]string{
+	{"┐", "┌"}, // down, then left or right
+	{"┘", "└"}, // up
+}
+
+// lineGlyph is the line joining a cell to its neighbours in the directions
+// a and b, either of which may be zero for none. A diagonal end wins over a
+// straight one, since box drawing has no glyphs between the two.
+func lineGlyph(a, b step) string {
+	none := step{}
+	switch {
+	case a == none:
+		a = b
+	case b == none:
+		b = a
+	}
+	if b.diagonal() {
+		a, b = b, a
+	}
+	switch {
+	case a == none:
+		return "·"
+	case a.diagonal():
+		if a.dx == a.dy {
+			return "╲"
+		}
+		return "╱"
+	case a.dy == 0 && b.dy == 0:
+		return "─"
+	case a.dx == 0 && b.dx == 0:
+		return "│"
+	}
+	// One end is up or down and the other left or right.
+	if a.dy == 0 {
+		a, b = b, a
+	}
+	up, right := 0, 0
+	if a.dy < 0 {
+		up = 1
+	}
+	if b.dx > 0 {
+		right = 1
+	}
+	return corners[up][right]
+}
+
+// trailGlyph is the line for cell i of a stream's trail, joining it to the
+// cells either side, the head coming after the last.
+func trailGlyph(s *Stream, i int) string {
+	c := s.trail[i]
+	var back, ahead step
+	if i > 0 {
+		back = stepBetween(c, s.trail[i-1])
+	}
+	if i+1 < len(s.trail) {
+		ahead = stepBetween(c, s.trail[i+1])
+	} else {
+		x, y := s.cell()
+		ahead = stepBetween(c, [2]int{x, y})
+	}
+	return lineGlyph(back, ahead)
+}
@@ -0,0 +1,208 @@
+package main
+
+import (
+	"os"
+	"strings"
+)
+
+// typerMode is a haunted teletype: typists each type a line at a time along
+// a row of their own, a glyph at a time at reading speed, now and then
+// hitting the wrong key or backspacing over what they've typed to type it
+// again. They type the lines of a corpus file in turn, or failing that
+// random words.
+type typerMode struct {
+	// corpus is the lines to type, next the one up, and left what's left
+	// of a line too long for the screen, typed on the next.
+	corpus []string
+	next   int
+	left   string
+
+	// rows is what's been typed on each row of the screen.
+	rows     [][]typed
+	typists  []*typist
+	maxWidth int
+}
+
+type typed struct {
+	text  string
+	color Color
+}
+
+type typist struct {
+	row   int
+	color Color
+
+	// line is the line being typed, and at how far into it.
+	line []string
+	at   int
+
+	// pace builds up by the speed each tick, a glyph typed for every 1;
+	// pause is ticks to wait before going on, and erase how many glyphs to
+	// backspace over first.
+	pace  float64
+	pause int
+	erase int
+}
+
+const (
+	// typerPace is glyphs typed per tick at speed 1, about 6 a second.
+	typerPace = 0.6
+
+	// A typist hits the wrong key with chance typerTypo, scaled by chaos,
+	// and otherwise backspaces over a few glyphs with chance typerRetype,
+	// then pauses typerLinePause ticks at the end of a line.
+	typerTypo      = 0.03
+	typerRetype    = 0.01
+	typerLinePause = 15
+)
+
+// newTyperMode reads the corpus from path, if it's set.
+func newTyperMode(path string) (*typerMode, error) {
+	m := &typerMode{}
+	if path == "" {
+		return m, nil
+	}
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	for _, line := range strings.Split(string(data), "\n") {
+		if line = strings.TrimSpace(line); line != "" {
+			m.corpus = append(m.corpus, line)
+		}
+	}
+	return m, nil
+}
+
+func (m *typerMode) init(sim *Sim) {
+	m.rows = make([][]typed, sim.height)
+	m.maxWidth = max(1, sim.width-1)
+	m.typists = m.typists[:0]
+}
+
+// nextLine is the next line to type, cut to fit the screen.
+func (m *typerMode) nextLine(sim *Sim) string {
+	line := m.left
+	switch {
+	case line != "":
+	case len(m.corpus) > 0:
+		line = m.corpus[m.next]
+		m.next = (m.next + 1) % len(m.corpus)
+	default:
+		words := sim.words
+		if len(words) == 0 {
+			words = defaultWords
+		}
+		var sb strings.Builder
+		for want := m.maxWidth/3 + sim.rng.Intn(m.maxWidth/2+1); sb.Len() < want; {
+			if sb.Len() > 0 {
+				sb.WriteByte(' ')
+			}
+			sb.WriteString(words[sim.rng.Intn(len(words))])
+		}
+		line = sb.String()
+	}
+	m.left = ""
+	if cut := fitWidth(line, m.maxWidth); cut != line {
+		if i := strings.LastIndexByte(cut, ' '); i > 0 {
+			cut = cut[:i]
+		}
+		// A wide glyph on a screen a cell wide is typed all the same.
+		if cut == "" {
+			cut = clip(line, 1)
+		}
+		line, m.left = cut, strings.TrimSpace(line[len(cut):])
+	}
+	return line
+}
+
+// start sets t typing a new line on a row no other typist has, wiping it.
+// There are never more typists than rows, so there's always one free.
+func (m *typerMode) start(sim *Sim, t *typist) {
+	taken := make([]bool, len(m.rows))
+	for _, other := range m.typists {
+		if other != t {
+			taken[other.row] = true
+		}
+	}
+	var free []int
+	for row, other := range taken {
+		if !other {
+			free = append(free, row)
+		}
+	}
+	t.row = free[sim.rng.Intn(len(free))]
+	m.rows[t.row] = m.rows[t.row][:0]
+	t.at, t.erase, t.pause = 0, 0, 0
+	t.line = t.line[:0]
+	eachCell(m.nextLine(sim), func(text string) { t.line = append(t.line, text) })
+	t.color = sim.palette.randomColor(sim.rng)
+}
+
+func (m *typerMode) tick(sim *Sim) {
+	if len(m.rows) == 0 {
+		return
+	}
+	want := max(1, min(len(m.rows), int(sim.chaos*float64(len(m.rows))/3)))
+	for len(m.typists) < want {
+		t := &typist{}
+		m.start(sim, t)
+		m.typists = append(m.typists, t)
+	}
+	m.typists = m.typists[:want]
+	for _, t := range m.typists {
+		for t.pace += sim.speed * typerPace; t.pace >= 1; t.pace-- {
+			m.step(sim, t)
+		}
+	}
+}
+
+// step types, or backspaces, a glyph.
+func (m *typerMode) step(sim *Sim, t *typist) {
+	row := &m.rows[t.row]
+	switch {
+	case t.pause > 0:
+		t.pause--
+	case t.erase > 0:
+		t.erase--
+		*row = (*row)[:len(*row)-1]
+	case t.at >= len(t.line):
+		m.start(sim, t)
+		t.pause = typerLinePause
+	case sim.rng.Float64() < typerTypo*sim.chaos:
+		*row = append(*row, typed{sim.charset.pick(sim.rng), sim.palette.randomColor(sim.rng)})
+		t.at++
+		// Most typos are noticed and corrected; the rest stay.
+		if sim.rng.Intn(3) > 0 {
+			t.erase, t.at, t.pause = 1, t.at-1, 2
+		}
+	case t.at > 0 && sim.rng.Float64() < typerRetype*sim.chaos:
+		t.erase = 1 + sim.rng.Intn(min(t.at, 4))
+		t.at -= t.erase
+	default:
+		*row = append(*row, typed{t.line[t.at], t.color})
+		t.at++
+	}
+}
+
+func (m *typerMode) render(sim *Sim, scr *Screen) {
+	ends := make([]int, len(m.rows))
+	for y, row := range m.rows {
+		x := 0
+		for _, c := range row {
+			scr.put(x, y, c.text, c.color)
+			x += scr.glyphWidth(c.text)
+		}
+		ends[y] = x
+	}
+	// The cursors blink, twice a second.
+	if sim.tick/5%2 == 0 {
+		for _, t := range m.typists {
+			scr.put(ends[t.row], t.row, "█", sim.palette.primary)
+		}
+	}
+}
+
+func (m *typerMode) handleInput(*Sim, rune) {}
+
+func (m *typerMode) onResize(sim *Sim, width, height int) { m.init(sim) }
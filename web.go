@@ -0,0 +1,258 @@
+package main
+
+import (
+	"bufio"
+	"crypto/sha1"
+	"encoding/base64"
+	"encoding/binary"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"net/http"
+	"net/url"
+	"os"
+	"strings"
+	"sync"
+	"time"
+)
+
+// webPage is the page --serve-web serves: a terminal from xterm.js filling
+// the window, fed frames over a WebSocket, sending back its size and keys.
+const webPage = `<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>` + progName + `</title>
+<link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/@xterm/xterm@5.5.0/css/xterm.css">
+<script src="https://cdn.jsdelivr.net/npm/@xterm/xterm@5.5.0/lib/xterm.js"></script>
+<script src="https://cdn.jsdelivr.net/npm/@xterm/addon-fit@0.10.0/lib/addon-fit.js"></script>
+<style>html, body, #term { margin: 0; height: 100%; background: #000; overflow: hidden; }</style>
+</head>
+<body>
+<div id="term"></div>
+<script>
+const term = new Terminal();
+const fit = new FitAddon.FitAddon();
+term.loadAddon(fit);
+term.open(document.getElementById("term"));
+fit.fit();
+const ws = new WebSocket((location.protocol === "https:" ? "wss://" : "ws://") + location.host + "/ws");
+ws.binaryType = "arraybuffer";
+const send = msg => ws.readyState === WebSocket.OPEN && ws.send(JSON.stringify(msg));
+const sendSize = () => send({cols: term.cols, rows: term.rows});
+ws.onopen = sendSize;
+ws.onmessage = e => term.write(new Uint8Array(e.data));
+ws.onclose = () => term.write("\r\n[disconnected]");
+term.onData(keys => send({keys}));
+term.onResize(sendSize);
+addEventListener("resize", () => fit.fit());
+</script>
+</body>
+</html>
+`
+
+// webSocketGUID is what RFC 6455 has servers hash a client's key with.
+const webSocketGUID = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11"
+
+// WebSocket opcodes.
+const (
+	wsText   = 1
+	wsBinary = 2
+	wsClose  = 8
+	wsPing   = 9
+	wsPong   = 10
+)
+
+// wsMaxMessage is the longest message a client may send; sizes and keys
+// are all they send.
+const wsMaxMessage = 4096
+
+var errBadFrame = errors.New("bad WebSocket frame")
+
+// serveWeb listens on --serve-web, serving the page at / and running an
+// animation of its own for every page that connects to /ws, at the size of
+// its terminal, until the server is killed.
+func serveWeb(o *runOptions) error {
+	s, err := newServer(o)
+	if err != nil {
+		return err
+	}
+	mux := http.NewServeMux()
+	mux.HandleFunc("GET /{$}", func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "text/html; charset=utf-8")
+		io.WriteString(w, webPage)
+	})
+	mux.HandleFunc("GET /ws", s.serveWebSocket)
+	l, err := net.Listen("tcp", o.serveWeb)
+	if err != nil {
+		return err
+	}
+	fmt.Fprintf(os.Stderr, "%s: serving http://%s/\n", progName, l.Addr())
+	return http.Serve(l, mux)
+}
+
+func (s *server) serveWebSocket(w http.ResponseWriter, r *http.Request) {
+	key := r.Header.Get("Sec-WebSocket-Key")
+	if key == "" || !strings.EqualFold(r.Header.Get("Upgrade"), "websocket") || !hasToken(r.Header.Get("Connection"), "upgrade") {
+		http.Error(w, "expected a WebSocket", http.StatusBadRequest)
+		return
+	}
+	if !sameOrigin(r) {
+		http.Error(w, "the page is from another site", http.StatusForbidden)
+		return
+	}
+	if !s.admit() {
+		http.Error(w, "too many people are watching, try again later", http.StatusServiceUnavailable)
+		return
+	}
+	defer s.leave()
+	conn, rw, err := http.NewResponseController(w).Hijack()
+	if err != nil {
+		return
+	}
+	defer conn.Close()
+	sum := sha1.Sum([]byte(key + webSocketGUID))
+	fmt.Fprintf(rw, "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: %s\r\n\r\n",
+		base64.StdEncoding.EncodeToString(sum[:]))
+	if err := rw.Flush(); err != nil {
+		return
+	}
+	c := &webClient{conn: conn, r: rw.Reader, size: clientSize{width: 80, height: 24}}
+	if err := s.animate(c, c.size.get, c.readInput); err != nil {
+		fmt.Fprintf(os.Stderr, "%s: %s: %v\n", progName, r.RemoteAddr, err)
+	}
+}
+
+// sameOrigin reports whether a WebSocket was opened by our own page, or by
+// something that isn't a browser and so sends no Origin, rather than by a
+// page from another site the browser has been pointed at.
+func sameOrigin(r *http.Request) bool {
+	origin := r.Header.Get("Origin")
+	if origin == "" {
+		return true
+	}
+	u, err := url.Parse(origin)
+	return err == nil && strings.EqualFold(u.Host, r.Host)
+}
+
+// hasToken reports whether a comma-separated header value has token in it.
+func hasToken(value, token string) bool {
+	for _, t := range strings.Split(value, ",") {
+		if strings.EqualFold(strings.TrimSpace(t), token) {
+			return true
+		}
+	}
+	return false
+}
+
+// webClient is a WebSocket connection to one page of --serve-web.
+type webClient struct {
+	conn net.Conn
+	r    *bufio.Reader
+	size clientSize
+
+	// mu keeps frames whole, since both the screen and readInput send them.
+	mu    sync.Mutex
+	frame []byte
+}
+
+// Write sends output to the page as a binary message.
+func (c *webClient) Write(p []byte) (int, error) {
+	if err := c.writeFrame(wsBinary, p); err != nil {
+		return 0, err
+	}
+	return len(p), nil
+}
+
+func (c *webClient) writeFrame(op byte, p []byte) error {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.frame = append(c.frame[:0], 0x80|op)
+	switch n := len(p); {
+	case n < 126:
+		c.frame = append(c.frame, byte(n))
+	case n <= 0xffff:
+		c.frame = binary.BigEndian.AppendUint16(append(c.frame, 126), uint16(n))
+	default:
+		c.frame = binary.BigEndian.AppendUint64(append(c.frame, 127), uint64(n))
+	}
+	c.frame = append(c.frame, p...)
+	c.conn.SetWriteDeadline(time.Now().Add(clientWriteTimeout))
+	_, err := c.conn.Write(c.frame)
+	return err
+}
+
+// readFrame reads a frame from the page, which has to mask it.
+func (c *webClient) readFrame() (op byte, payload []byte, err error) {
+	var h [8]byte
+	if _, err := io.ReadFull(c.r, h[:2]); err != nil {
+		return 0, nil, err
+	}
+	op, masked, n := h[0]&0x0f, h[1]&0x80 != 0, uint64(h[1]&0x7f)
+	switch n {
+	case 126:
+		if _, err := io.ReadFull(c.r, h[:2]); err != nil {
+			return 0, nil, err
+		}
+		n = uint64(binary.BigEndian.Uint16(h[:2]))
+	case 127:
+		if _, err := io.ReadFull(c.r, h[:8]); err != nil {
+			return 0, nil, err
+		}
+		n = binary.BigEndian.Uint64(h[:8])
+	}
+	if !masked || n > wsMaxMessage {
+		return 0, nil, errBadFrame
+	}
+	var mask [4]byte
+	if _, err := io.ReadFull(c.r, mask[:]); err != nil {
+		return 0, nil, err
+	}
+	payload = make([]byte, n)
+	if _, err := io.ReadFull(c.r, payload); err != nil {
+		return 0, nil, err
+	}
+	for i := range payload {
+		payload[i] ^= mask[i%4]
+	}
+	return op, payload, nil
+}
+
+// webMessage is what the page sends: its terminal's new size, or keys.
+type webMessage struct {
+	Cols int    `json:"cols"`
+	Rows int    `json:"rows"`
+	Keys string `json:"keys"`
+}
+
+// readInput reads the page's messages, keeping track of its terminal's
+// size, until it quits or goes away.
+func (c *webClient) readInput(commands chan<- Command, done <-chan struct{}) {
+	defer sendQuit(commands, done)
+	for {
+		op, payload, err := c.readFrame()
+		if err != nil {
+			return
+		}
+		switch op {
+		case wsClose:
+			c.writeFrame(wsClose, nil)
+			return
+		case wsPing:
+			c.writeFrame(wsPong, payload)
+		case wsText:
+			var msg webMessage
+			if json.Unmarshal(payload, &msg) != nil {
+				continue
+			}
+			c.size.set(msg.Cols, msg.Rows)
+			for _, k := range msg.Keys {
+				if isQuitKey(k) {
+					return
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,65 @@
+package main
+
+import (
+	"math/rand"
+	"slices"
+)
+
+// Choice is one item of a Weighted set.
+type Choice[T any] struct {
+	item   T
+	weight Weight
+}
+
+// Weighted picks items at random in proportion to their weights.
+type Weighted[T any] struct {
+	choices []Choice[T]
+
+	// cumulative holds the running total of the weights, choice by choice,
+	// so pick can binary search it however many choices there are.
+	cumulative []int
+}
+
+func newWeighted[T any](choices ...Choice[T]) *Weighted[T] {
+	w := &Weighted[T]{}
+	for _, c := range choices {
+		w.add(c.item, c.weight)
+	}
+	return w
+}
+
+func (w *Weighted[T]) add(item T, weight Weight) {
+	if weight == 0 {
+		return
+	}
+	w.choices = append(w.choices, Choice[T]{item, weight})
+	w.cumulative = append(w.cumulative, w.total()+int(weight))
+}
+
+func (w *Weighted[T]) total() int {
+	if len(w.cumulative) == 0 {
+		return 0
+	}
+	return w.cumulative[len(w.cumulative)-1]
+}
+
+func (w *Weighted[T]) len() int {
+	return len(w.choices)
+}
+
+func (w *Weighted[T]) pick(rng *rand.Rand) T {
+	// The choice picked is the first whose running total passes n.
+	i, _ := slices.BinarySearch(w.cumulative, rng.Intn(w.total())+1)
+	return w.choices[i].item
+}
+
+// sample returns a new set of up to n distinct choices drawn from w, keeping
+// their weights.
+func (w *Weighted[T]) sample(rng *rand.Rand, n int) *Weighted[T] {
+	n = min(n, len(w.choices))
+	s := &Weighted[T]{}
+	for _, i := range rng.Perm(len(w.choices))[:n] {
+		s.add(w.choices[i].item, w.choices[i].weight)
+	}
+	return s
+}
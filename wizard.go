@@ -0,0 +1,120 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"slices"
+	"strings"
+	"time"
+)
+
+var wizardSpeeds = []struct {
+	name  string
+	speed float64
+}{
+	{"calm", 0.5},
+	{"normal", 1},
+	{"fast", 1.6},
+	{"frantic", 2.5},
+}
+
+type wizardStep struct {
+	title   string
+	options []string
+	apply   func(i int)
+}
+
+// runWizard is the first-run setup: it runs the animation as a live preview
+// behind a menu for picking a theme, a charset and a speed, then writes the
+// picks to the config file and into o. Skipping it with Esc writes a config
+// with no settings, so it isn't offered again.
+func runWizard(o *runOptions, keys <-chan rune) error {
+	sc, err := o.scene()
+	if err != nil {
+		return err
+	}
+	sim := newSim(sc.SimConfig)
+
+	var speedNames []string
+	for _, s := range wizardSpeeds {
+		speedNames = append(speedNames, s.name)
+	}
+	steps := []wizardStep{
+		{"theme", themeNames(), func(i int) { sim.setPalette(o.palette(&themes[i])) }},
+		{"charset", charsetNames(), func(i int) { sim.setCharset(charsets[i].charset) }},
+		{"speed", speedNames, func(i int) { sim.base.speed = wizardSpeeds[i].speed }},
+	}
+	picked := []int{
+		max(0, slices.Index(themeNames(), o.theme)),
+		max(0, slices.Index(charsetNames(), o.charset)),
+		1,
+	}
+
+	scr := newScreen(os.Stdout)
+	scr.enter()
+	defer scr.leave()
+	ticker := time.NewTicker(tickInterval)
+	defer ticker.Stop()
+	for step := 0; step < len(steps); {
+		st := steps[step]
+		n := len(st.options)
+		select {
+		case k := <-keys:
+			switch k {
+			case 'j', 'l', ' ':
+				picked[step] = (picked[step] + 1) % n
+				st.apply(picked[step])
+			case 'k', 'h':
+				picked[step] = (picked[step] + n - 1) % n
+				st.apply(picked[step])
+			case '\r', '\n':
+				step++
+			case keyEscape, keyCtrlC, 'q':
+				scr.leave()
+				_, err := saveConfig(fmt.Sprintf("# %s settings; see '%s config init' for what can go here.\n", progName, progName))
+				return err
+			}
+		case <-ticker.C:
+			width, height := terminalSize()
+			scr.resize(width, height)
+			sim.resize(width, height)
+			sim.update()
+			sim.draw(scr)
+			drawWizard(scr, step, st, picked[step], len(steps))
+			scr.flush()
+		}
+	}
+
+	o.theme = themes[picked[0]].name
+	o.charset = charsets[picked[1]].name
+	o.speed = wizardSpeeds[picked[2]].speed
+	text := fmt.Sprintf("# %s settings, picked by the setup wizard.\n\ntheme = %q\ncharset = %q\nspeed = %v\n",
+		progName, o.theme, o.charset, o.speed)
+	_, err = saveConfig(text)
+	return err
+}
+
+// drawWizard paints the menu for the current step in a box at the top left,
+// blanking what the animation drew underneath.
+func drawWizard(scr *Screen, step int, st wizardStep, picked, steps int) {
+	lines := []string{fmt.Sprintf("%s setup, step %d of %d: pick a %s", progName, step+1, steps, st.title), ""}
+	for i, opt := range st.options {
+		mark := "  "
+		if i == picked {
+			mark = "▶ "
+		}
+		lines = append(lines, mark+opt)
+	}
+	lines = append(lines, "", "j/k to change, enter to pick, esc to skip")
+	width := 0
+	for _, l := range lines {
+		width = max(width, cellCount(l))
+	}
+	for i, l := range lines {
+		c := Color{150, 150, 150}
+		if i == 0 || i == picked+2 {
+			c = Color{255, 255, 255}
+		}
+		scr.put(1, 1+i, " "+l+strings.Repeat(" ", width-cellCount(l))+" ", c)
+	}
+}
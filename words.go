@@ -0,0 +1,44 @@
+package main
+
+import (
+	"fmt"
+	"math/rand"
+	"os"
+	"strings"
+)
+
+var defaultWords = []string{"AAAAAAAAA"}
+
+// loadWords reads a whitespace-separated corpus; "default" selects the
+// built-in one.
+func loadWords(path string) ([]string, error) {
+	if path == "default" {
+		return defaultWords, nil
+	}
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	words := strings.Fields(string(data))
+	if len(words) == 0 {
+		return nil, fmt.Errorf("%s: no words", path)
+	}
+	return words, nil
+}
+
+// glitch swaps each letter of s for a glyph from the charset with the given
+// probability.
+func glitch(rng *rand.Rand, s string, chance float64, charset *Charset) string {
+	if chance <= 0 {
+		return s
+	}
+	var sb strings.Builder
+	for _, r := range s {
+		if rng.Float64() < chance {
+			sb.WriteString(charset.pick(rng))
+		} else {
+			sb.WriteRune(r)
+		}
+	}
+	return sb.String()
+}
@@ -0,0 +1,32 @@
+package main
+
+import (
+	"math/rand"
+	"unicode/utf8"
+)
+
+const maxZalgo = 10
+
+// zalgo attaches random combining diacritics (U+0300 to U+036F) to every
+// base character of s. intensity runs from 0 (untouched) to maxZalgo; higher
+// values mark more characters and stack more marks on each.
+func zalgo(rng *rand.Rand, s string, intensity int) string {
+	if intensity <= 0 {
+		return s
+	}
+	return string(appendZalgo(rng, nil, s, intensity))
+}
+
+// appendZalgo is zalgo appending to b, for callers reusing a buffer.
+func appendZalgo(rng *rand.Rand, b []byte, s string, intensity int) []byte {
+	for _, r := range s {
+		b = utf8.AppendRune(b, r)
+		if rng.Intn(maxZalgo) >= intensity {
+			continue
+		}
+		for range 1 + rng.Intn(intensity) {
+			b = utf8.AppendRune(b, rune(0x300+rng.Intn(0x70)))
+		}
+	}
+	return b
+}